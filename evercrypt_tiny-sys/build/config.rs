@@ -43,7 +43,7 @@ impl Configuration {
         // Current feature detection is simply based on arch detection
         // Therefore we try to chose a reasonable base line
         let arch = env::var("CARGO_CFG_TARGET_ARCH").expect("Cannot determine target architecture");
-        match arch.as_str() {
+        let config = match arch.as_str() {
             "arm" | "aarch64" => Self {
                 arch: Arch::arm,
                 v128: false,
@@ -64,8 +64,8 @@ impl Configuration {
             },
             "x86_64" => Self {
                 arch: Arch::x86_64,
-                v128: false,
-                v256: false,
+                v128: true,
+                v256: true,
                 vale: true,
                 inline_asm: true,
                 intrinsics: true,
@@ -74,6 +74,32 @@ impl Configuration {
             arch => {
                 panic!("Unsupported target platform {arch}")
             }
+        };
+        config.with_env_overrides()
+    }
+
+    /// Applies explicit overrides from the environment
+    ///
+    /// `EVERCRYPT_NATIVE_U128`, `EVERCRYPT_VEC128` and `EVERCRYPT_VEC256` can be set to `1` or `0` to force the
+    /// native 128 bit integer support or the respective vector sources on or off. The 256 bit vector sources require
+    /// AVX2 and cannot be enabled on arm.
+    fn with_env_overrides(mut self) -> Self {
+        Self::env_override("EVERCRYPT_NATIVE_U128", &mut self.native_u128);
+        Self::env_override("EVERCRYPT_VEC128", &mut self.v128);
+        Self::env_override("EVERCRYPT_VEC256", &mut self.v256);
+        if self.arch == Arch::arm && self.v256 {
+            panic!("EVERCRYPT_VEC256 is not supported on arm");
+        }
+        self
+    }
+
+    /// Overrides `flag` if the environment variable `name` is set
+    fn env_override(name: &str, flag: &mut bool) {
+        match env::var(name).as_deref() {
+            Ok("1") => *flag = true,
+            Ok("0") => *flag = false,
+            Ok(value) => panic!("Invalid value for {name}: {value}"),
+            Err(_) => (),
         }
     }
 
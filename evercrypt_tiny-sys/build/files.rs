@@ -13,6 +13,8 @@ pub const DIST_C89: &str = "vendored/v0.4.5-dist/c89-compatible";
 pub const DIST_KARAMEL_INCLUDE: &str = "vendored/v0.4.5-dist/kremlin/include";
 /// The current distribution's KaRaMeL mini-dist include folder
 pub const DIST_KARAMEL_MINIMAL_INCLUDE: &str = "vendored/v0.4.5-dist/kremlin/kremlib/dist/minimal";
+/// The folder with our own C shims
+pub const SHIMS: &str = "shims";
 
 /// A pattern
 #[derive(Debug, Clone)]
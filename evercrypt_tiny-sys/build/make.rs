@@ -7,7 +7,7 @@ use crate::{
     files::{
         FileList,
         Pattern::{Contains, End, Exact, Start},
-        DIST_C89, DIST_KARAMEL_INCLUDE, DIST_KARAMEL_MINIMAL_INCLUDE,
+        DIST_C89, DIST_KARAMEL_INCLUDE, DIST_KARAMEL_MINIMAL_INCLUDE, SHIMS,
     },
 };
 use std::{env, fs, path::Path};
@@ -34,23 +34,46 @@ impl Make {
         let config_h = self.config_h();
         fs::write(config_h_path, config_h).expect("Failed to create config.h");
 
-        // Gather sources and include paths
+        // Gather sources
         let c_sources = dbg!(self.c_sources());
         let asm_sources = dbg!(self.asm_sources());
-        let includes = self.includes();
 
         // Build the library
-        Build::new()
+        self.cc_build(&out_dir).files(c_sources.paths()).files(asm_sources.paths()).compile("evercrypt");
+
+        // Build the vector arithmetic files separately, since they must be compiled with additional target features
+        if self.config.v128 {
+            let vec128_sources = self.vec_sources("128");
+            let mut build = self.cc_build(&out_dir);
+            self.vec128_flags().iter().for_each(|flag| _ = build.flag_if_supported(flag));
+            build.files(vec128_sources.paths()).compile("evercrypt_vec128");
+        }
+        if self.config.v256 {
+            let vec256_sources = self.vec_sources("256");
+            let mut build = self.cc_build(&out_dir);
+            self.vec256_flags().iter().for_each(|flag| _ = build.flag_if_supported(flag));
+            build.files(vec256_sources.paths()).compile("evercrypt_vec256");
+        }
+
+        // Export the vector support to dependent build scripts as `DEP_EVERCRYPT_VEC128` and `DEP_EVERCRYPT_VEC256`
+        println!("cargo:vec128={}", self.config.v128 as u8);
+        println!("cargo:vec256={}", self.config.v256 as u8);
+    }
+
+    /// Creates a new C build with the common include paths and flags
+    fn cc_build(&self, out_dir: &str) -> Build {
+        let mut build = Build::new();
+        build
             .include(out_dir)
-            .includes(includes)
-            .files(c_sources.paths())
-            .files(asm_sources.paths())
+            .includes(self.includes())
             .flag_if_supported("-Wno-unused-parameter")
             .flag_if_supported("-Wno-unused-variable")
             .flag_if_supported("-Wno-unused-but-set-variable")
             .flag_if_supported("-Wno-unused-function")
             .flag_if_supported("-Wno-cpp")
-            .compile("evercrypt");
+            // Prefix FrodoKEM's `randombytes_` (defined in `src/frodo.rs`), so it cannot clash with other C libraries
+            .define("randombytes_", "EverCryptTiny_Frodo_randombytes");
+        build
     }
 
     /// Build a config.h
@@ -121,21 +144,57 @@ impl Make {
             c_sources.remove(Exact("Hacl_Curve25519_64.c"));
             c_sources.remove(Exact("evercrypt_vale_stubs.c"));
         }
-        // Blacklist 128-bit vector arithmetic files
+        // Blacklist 128-bit vector arithmetic files; they are either unsupported or built separately
+        c_sources.remove(End("_128.c"));
+        c_sources.remove(End("_Vec128.c"));
         if !self.config.v128 {
             c_sources.remove(Contains("CP128") + End(".c"));
-            c_sources.remove(End("_128.c"));
-            c_sources.remove(End("_Vec128.c"));
         }
-        // Blacklist 256-bit vector arithmetic files
+        // Blacklist 256-bit vector arithmetic files; they are either unsupported or built separately
+        c_sources.remove(End("_256.c"));
+        c_sources.remove(End("_Vec256.c"));
         if !self.config.v256 {
             c_sources.remove(Contains("CP256") + End(".c"));
-            c_sources.remove(End("_256.c"));
-            c_sources.remove(End("_Vec256.c"));
         }
+
+        // Blacklist FrodoKEM's `randombytes_`, which is replaced by a Rust implementation with a hook for the coins.
+        // The file contains nothing else, and its definition discards the result of the system randomness, so a
+        // failing system RNG would silently yield all-zero coins
+        c_sources.remove(Exact("Hacl_Frodo_KEM.c"));
+        // Blacklist the system randomness if it is replaced by a registered entropy source
+        if env::var_os("CARGO_FEATURE_CUSTOM_ENTROPY").is_some() {
+            c_sources.remove(Exact("Lib_RandomBuffer_System.c"));
+        }
+
+        // Add the ABI shims for functions with 128 bit integer arguments
+        c_sources.add(SHIMS, End(".c"));
         c_sources
     }
 
+    /// Gather the vector arithmetic C source files for the given vector `width`
+    fn vec_sources(&self, width: &str) -> FileList {
+        let mut vec_sources = FileList::new();
+        vec_sources.add(DIST_C89, End(format!("_{width}.c")));
+        vec_sources.add(DIST_C89, End(format!("_Vec{width}.c")));
+        vec_sources
+    }
+
+    /// The compiler flags to enable 128 bit vector instructions
+    fn vec128_flags(&self) -> &'static [&'static str] {
+        match self.config.arch {
+            Arch::arm => &["-march=armv8-a+simd"],
+            Arch::x86 | Arch::x86_64 => &["-mavx", "/arch:AVX"],
+        }
+    }
+
+    /// The compiler flags to enable 256 bit vector instructions
+    fn vec256_flags(&self) -> &'static [&'static str] {
+        match self.config.arch {
+            Arch::arm => &[],
+            Arch::x86 | Arch::x86_64 => &["-mavx", "-mavx2", "/arch:AVX2"],
+        }
+    }
+
     /// Gather all config-specific assembly source files
     fn asm_sources(&self) -> FileList {
         // Collect assembly sources
@@ -0,0 +1,101 @@
+// ABI-safe shims for HACL* functions that take `FStar_UInt128_uint128` by value
+//
+// Depending on the compiler and `KRML_VERIFIED_UINT128`, `FStar_UInt128_uint128` is either a native `__int128`, an
+// `__m128i` or a struct of two `uint64_t`. These representations are passed differently by most calling conventions,
+// so they cannot be mapped to a single Rust type. The shims below take the value as two `uint64_t` halves instead and
+// reassemble it with KaRaMeL's own `FStar_UInt128` functions.
+
+#include "Hacl_Hash_Blake2.h"
+#include "Hacl_Hash_SHA2.h"
+#if defined(HACL_CAN_COMPILE_VEC256)
+#include "Hacl_Hash_Blake2b_256.h"
+#endif
+
+/// Reassembles a 128 bit integer from its halves
+static inline FStar_UInt128_uint128 EverCryptTiny_u128(uint64_t low, uint64_t high)
+{
+  FStar_UInt128_uint128 high_ = FStar_UInt128_shift_left(FStar_UInt128_uint64_to_uint128(high), (uint32_t)64U);
+  return FStar_UInt128_add(high_, FStar_UInt128_uint64_to_uint128(low));
+}
+
+void
+EverCryptTiny_Blake2b_32_blake2b_update_multi(
+  uint32_t len,
+  uint64_t *wv,
+  uint64_t *hash,
+  uint64_t prev_low,
+  uint64_t prev_high,
+  uint8_t *blocks,
+  uint32_t nb
+)
+{
+  Hacl_Blake2b_32_blake2b_update_multi(len, wv, hash, EverCryptTiny_u128(prev_low, prev_high), blocks, nb);
+}
+
+void
+EverCryptTiny_Blake2b_32_blake2b_update_last(
+  uint32_t len,
+  uint64_t *wv,
+  uint64_t *hash,
+  uint64_t prev_low,
+  uint64_t prev_high,
+  uint32_t rem,
+  uint8_t *d
+)
+{
+  Hacl_Blake2b_32_blake2b_update_last(len, wv, hash, EverCryptTiny_u128(prev_low, prev_high), rem, d);
+}
+
+void
+EverCryptTiny_Hash_SHA2_update_last_384(
+  uint64_t *s,
+  uint64_t prev_len_low,
+  uint64_t prev_len_high,
+  uint8_t *input,
+  uint32_t input_len
+)
+{
+  Hacl_Hash_SHA2_update_last_384(s, EverCryptTiny_u128(prev_len_low, prev_len_high), input, input_len);
+}
+
+void
+EverCryptTiny_Hash_SHA2_update_last_512(
+  uint64_t *s,
+  uint64_t prev_len_low,
+  uint64_t prev_len_high,
+  uint8_t *input,
+  uint32_t input_len
+)
+{
+  Hacl_Hash_SHA2_update_last_512(s, EverCryptTiny_u128(prev_len_low, prev_len_high), input, input_len);
+}
+
+#if defined(HACL_CAN_COMPILE_VEC256)
+void
+EverCryptTiny_Blake2b_256_blake2b_update_multi(
+  uint32_t len,
+  Lib_IntVector_Intrinsics_vec256 *wv,
+  Lib_IntVector_Intrinsics_vec256 *hash,
+  uint64_t prev_low,
+  uint64_t prev_high,
+  uint8_t *blocks,
+  uint32_t nb
+)
+{
+  Hacl_Blake2b_256_blake2b_update_multi(len, wv, hash, EverCryptTiny_u128(prev_low, prev_high), blocks, nb);
+}
+
+void
+EverCryptTiny_Blake2b_256_blake2b_update_last(
+  uint32_t len,
+  Lib_IntVector_Intrinsics_vec256 *wv,
+  Lib_IntVector_Intrinsics_vec256 *hash,
+  uint64_t prev_low,
+  uint64_t prev_high,
+  uint32_t rem,
+  uint8_t *d
+)
+{
+  Hacl_Blake2b_256_blake2b_update_last(len, wv, hash, EverCryptTiny_u128(prev_low, prev_high), rem, d);
+}
+#endif
@@ -751,6 +751,9 @@ extern "C" {
 extern "C" {
     pub fn Hacl_Bignum256_eq_mask(a: *mut u64, b: *mut u64) -> u64;
 }
+extern "C" {
+    pub fn Lib_Memzero0_memzero(x0: *mut ::core::ffi::c_void, x1: u64);
+}
 extern "C" {
     pub fn Hacl_HMAC_Blake2b_256_compute_blake2b_256(
         dst: *mut u8,
@@ -2698,6 +2701,12 @@ extern "C" {
 extern "C" {
     pub fn Hacl_Bignum32_eq_mask(len: u32, a: *mut u32, b: *mut u32) -> u32;
 }
+extern "C" {
+    pub fn Lib_RandomBuffer_System_randombytes(buf: *mut u8, len: u32) -> bool;
+}
+extern "C" {
+    pub fn Lib_RandomBuffer_System_crypto_random(buf: *mut u8, len: u32);
+}
 extern "C" {
     pub fn Hacl_P256_ecdsa_sign_p256_sha2(result: *mut u8, mLen: u32, m: *mut u8, privKey: *mut u8, k: *mut u8)
         -> bool;
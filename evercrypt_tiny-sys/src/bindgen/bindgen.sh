@@ -6,12 +6,14 @@ DIST="vendored/v0.4.5-dist"
 
 # Go into script dir and call bindgen
 #  NOTE: This script requires a valid config.h, so it may be necessary to attempt a build first which creates the config.h
+#  NOTE: Functions that take 128 bit integers by value are blocklisted here and bound via the shims in `src/uint128.rs`
 cd "`dirname $0`"
 bindgen \
     \
     --allowlist-function="EverCrypt_.*" --allowlist-type="EverCrypt_.*" --allowlist-var="EverCrypt_.*" \
     --allowlist-function="Spec_.*" --allowlist-type="Spec_.*" --allowlist-var="Spec_.*" \
     --allowlist-function="Hacl_.*" --allowlist-type="Hacl_.*" --allowlist-var="Hacl_.*" \
+    --allowlist-function="Lib_Memzero0_.*" --allowlist-function="Lib_RandomBuffer_System_.*" \
     \
     --blocklist-function="Hacl_Blake2b_32_blake2b_update_multi" \
     --blocklist-function="Hacl_Blake2b_32_blake2b_update_last" \
@@ -0,0 +1,58 @@
+//! A Rust replacement for `Lib_RandomBuffer_System.c` that forwards to a registered entropy source
+//!
+//! HACL* draws system randomness through `Lib_RandomBuffer_System_randombytes` and
+//! `Lib_RandomBuffer_System_crypto_random`, which use `getrandom`, `/dev/urandom` or `CryptGenRandom`. With the
+//! `custom-entropy` feature, the C implementation is not compiled; both functions are defined here instead and call the
+//! source registered via [`EverCryptTiny_set_entropy_source`]. Without a registered source, `randombytes` fails and
+//! `crypto_random` aborts the process, since it cannot report the failure.
+
+use core::{
+    hint, mem,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// An entropy source that fills `len` bytes at `buf` and returns whether it succeeded
+pub type EverCryptTiny_entropy_source = unsafe extern "C" fn(buf: *mut u8, len: u32) -> bool;
+
+/// The registered entropy source or `NULL`
+static ENTROPY_SOURCE: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers `source` as entropy source for all HACL* functions, or removes the current source if `source` is `None`
+pub fn EverCryptTiny_set_entropy_source(source: Option<EverCryptTiny_entropy_source>) {
+    let source = source.map_or(ptr::null_mut(), |source| source as *mut ());
+    ENTROPY_SOURCE.store(source, Ordering::Release);
+}
+
+/// Fills `len` bytes at `buf` from the registered entropy source and returns whether it succeeded
+///
+/// # Safety
+/// `buf` must be valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn Lib_RandomBuffer_System_randombytes(buf: *mut u8, len: u32) -> bool {
+    let Some(source) = NonNull::new(ENTROPY_SOURCE.load(Ordering::Acquire)) else {
+        return false;
+    };
+
+    // The pointer has been created from an `EverCryptTiny_entropy_source`
+    let source: EverCryptTiny_entropy_source = mem::transmute(source.as_ptr());
+    source(buf, len)
+}
+
+/// Fills `len` bytes at `buf` from the registered entropy source and retries until it succeeds
+///
+/// # Panics
+/// This function panics if no entropy source is registered, which aborts the process since it cannot unwind. The C
+/// signature cannot report the failure, and waiting for a source would hang the calling thread forever.
+///
+/// # Safety
+/// `buf` must be valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn Lib_RandomBuffer_System_crypto_random(buf: *mut u8, len: u32) {
+    while !Lib_RandomBuffer_System_randombytes(buf, len) {
+        if ENTROPY_SOURCE.load(Ordering::Acquire).is_null() {
+            panic!("HACL* requires randomness, but no entropy source is registered");
+        }
+        hint::spin_loop();
+    }
+}
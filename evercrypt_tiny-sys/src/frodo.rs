@@ -0,0 +1,46 @@
+//! A Rust replacement for `Hacl_Frodo_KEM.c` that draws FrodoKEM's coins from a registered source
+//!
+//! `Hacl_Frodo*_crypto_kem_keypair` and `Hacl_Frodo*_crypto_kem_enc` draw their coins via `randombytes_`, whose C
+//! implementation ignores failures of `Lib_RandomBuffer_System_randombytes` and continues with all-zero coins. It is
+//! therefore not compiled; `randombytes_` is defined here instead and calls the source registered via
+//! [`EverCryptTiny_set_frodo_randombytes`], e.g. to supply the coins from an RNG of the caller. Without a registered
+//! source, or if the source fails, it falls back to `Lib_RandomBuffer_System_crypto_random`, which retries until it
+//! succeeds (or, with the `custom-entropy` feature, aborts if no entropy source is registered).
+//!
+//! The build renames `randombytes_` to `EverCryptTiny_Frodo_randombytes` via a define, so that the exported symbol does
+//! not clash with the `randombytes_` of other C libraries (e.g. libsodium or PQClean) linked into the same binary.
+
+use crate::Lib_RandomBuffer_System_crypto_random;
+use core::{
+    mem,
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// A source for FrodoKEM's coins that fills `len` bytes at `buf` and returns whether it succeeded
+pub type EverCryptTiny_frodo_randombytes = unsafe extern "C" fn(buf: *mut u8, len: u32) -> bool;
+
+/// The registered source or `NULL`
+static FRODO_RANDOMBYTES: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers `source` as source for FrodoKEM's coins, or removes the current source if `source` is `None`
+pub fn EverCryptTiny_set_frodo_randombytes(source: Option<EverCryptTiny_frodo_randombytes>) {
+    let source = source.map_or(ptr::null_mut(), |source| source as *mut ());
+    FRODO_RANDOMBYTES.store(source, Ordering::Release);
+}
+
+/// Fills `len` bytes at `res` from the registered source or the system randomness
+///
+/// # Safety
+/// `res` must be valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn EverCryptTiny_Frodo_randombytes(len: u32, res: *mut u8) {
+    if let Some(source) = NonNull::new(FRODO_RANDOMBYTES.load(Ordering::Acquire)) {
+        // The pointer has been created from an `EverCryptTiny_frodo_randombytes`
+        let source: EverCryptTiny_frodo_randombytes = mem::transmute(source.as_ptr());
+        if source(res, len) {
+            return;
+        }
+    }
+    Lib_RandomBuffer_System_crypto_random(res, len);
+}
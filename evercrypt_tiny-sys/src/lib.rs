@@ -6,3 +6,13 @@
 #![no_std]
 
 include!("bindgen/bindgen.rs");
+
+#[cfg(feature = "custom-entropy")]
+mod entropy;
+mod frodo;
+mod uint128;
+
+#[cfg(feature = "custom-entropy")]
+pub use crate::entropy::{EverCryptTiny_entropy_source, EverCryptTiny_set_entropy_source};
+pub use crate::frodo::{EverCryptTiny_frodo_randombytes, EverCryptTiny_set_frodo_randombytes};
+pub use crate::uint128::*;
@@ -0,0 +1,171 @@
+//! ABI-safe bindings for the functions that take `FStar_UInt128_uint128` by value
+//!
+//! The C representation of `FStar_UInt128_uint128` depends on the compiler and the KaRaMeL configuration, so bindgen
+//! cannot bind these functions directly. Instead, they are called through the shims in `shims/evercrypt_tiny_u128.c`,
+//! which take the 128 bit value as two 64 bit halves. The wrappers below restore the original signatures on top of
+//! these shims.
+
+use crate::FStar_UInt128_uint128;
+use core::ffi::c_void;
+
+extern "C" {
+    pub fn EverCryptTiny_Blake2b_32_blake2b_update_multi(
+        len: u32,
+        wv: *mut u64,
+        hash: *mut u64,
+        prev_low: u64,
+        prev_high: u64,
+        blocks: *mut u8,
+        nb: u32,
+    );
+}
+extern "C" {
+    pub fn EverCryptTiny_Blake2b_32_blake2b_update_last(
+        len: u32,
+        wv: *mut u64,
+        hash: *mut u64,
+        prev_low: u64,
+        prev_high: u64,
+        rem: u32,
+        d: *mut u8,
+    );
+}
+extern "C" {
+    pub fn EverCryptTiny_Hash_SHA2_update_last_384(
+        s: *mut u64,
+        prev_len_low: u64,
+        prev_len_high: u64,
+        input: *mut u8,
+        input_len: u32,
+    );
+}
+extern "C" {
+    pub fn EverCryptTiny_Hash_SHA2_update_last_512(
+        s: *mut u64,
+        prev_len_low: u64,
+        prev_len_high: u64,
+        input: *mut u8,
+        input_len: u32,
+    );
+}
+extern "C" {
+    pub fn EverCryptTiny_Blake2b_256_blake2b_update_multi(
+        len: u32,
+        wv: *mut *mut c_void,
+        hash: *mut *mut c_void,
+        prev_low: u64,
+        prev_high: u64,
+        blocks: *mut u8,
+        nb: u32,
+    );
+}
+extern "C" {
+    pub fn EverCryptTiny_Blake2b_256_blake2b_update_last(
+        len: u32,
+        wv: *mut *mut c_void,
+        hash: *mut *mut c_void,
+        prev_low: u64,
+        prev_high: u64,
+        rem: u32,
+        d: *mut u8,
+    );
+}
+
+/// Splits a 128 bit integer into its low and high half
+const fn split(value: FStar_UInt128_uint128) -> (u64, u64) {
+    (value as u64, (value >> 64) as u64)
+}
+
+/// `Hacl_Blake2b_32_blake2b_update_multi` via [`EverCryptTiny_Blake2b_32_blake2b_update_multi`]
+///
+/// # Safety
+/// The pointers must be valid as required by HACL*.
+pub unsafe fn Hacl_Blake2b_32_blake2b_update_multi(
+    len: u32,
+    wv: *mut u64,
+    hash: *mut u64,
+    prev: FStar_UInt128_uint128,
+    blocks: *mut u8,
+    nb: u32,
+) {
+    let (prev_low, prev_high) = split(prev);
+    EverCryptTiny_Blake2b_32_blake2b_update_multi(len, wv, hash, prev_low, prev_high, blocks, nb);
+}
+
+/// `Hacl_Blake2b_32_blake2b_update_last` via [`EverCryptTiny_Blake2b_32_blake2b_update_last`]
+///
+/// # Safety
+/// The pointers must be valid as required by HACL*.
+pub unsafe fn Hacl_Blake2b_32_blake2b_update_last(
+    len: u32,
+    wv: *mut u64,
+    hash: *mut u64,
+    prev: FStar_UInt128_uint128,
+    rem: u32,
+    d: *mut u8,
+) {
+    let (prev_low, prev_high) = split(prev);
+    EverCryptTiny_Blake2b_32_blake2b_update_last(len, wv, hash, prev_low, prev_high, rem, d);
+}
+
+/// `Hacl_Hash_SHA2_update_last_384` via [`EverCryptTiny_Hash_SHA2_update_last_384`]
+///
+/// # Safety
+/// The pointers must be valid as required by HACL*.
+pub unsafe fn Hacl_Hash_SHA2_update_last_384(
+    s: *mut u64,
+    prev_len: FStar_UInt128_uint128,
+    input: *mut u8,
+    input_len: u32,
+) {
+    let (prev_len_low, prev_len_high) = split(prev_len);
+    EverCryptTiny_Hash_SHA2_update_last_384(s, prev_len_low, prev_len_high, input, input_len);
+}
+
+/// `Hacl_Hash_SHA2_update_last_512` via [`EverCryptTiny_Hash_SHA2_update_last_512`]
+///
+/// # Safety
+/// The pointers must be valid as required by HACL*.
+pub unsafe fn Hacl_Hash_SHA2_update_last_512(
+    s: *mut u64,
+    prev_len: FStar_UInt128_uint128,
+    input: *mut u8,
+    input_len: u32,
+) {
+    let (prev_len_low, prev_len_high) = split(prev_len);
+    EverCryptTiny_Hash_SHA2_update_last_512(s, prev_len_low, prev_len_high, input, input_len);
+}
+
+/// `Hacl_Blake2b_256_blake2b_update_multi` via [`EverCryptTiny_Blake2b_256_blake2b_update_multi`]
+///
+/// # Safety
+/// The pointers must be valid as required by HACL*. The function is only available if the library has been built with
+/// 256 bit vector support.
+pub unsafe fn Hacl_Blake2b_256_blake2b_update_multi(
+    len: u32,
+    wv: *mut *mut c_void,
+    hash: *mut *mut c_void,
+    prev: FStar_UInt128_uint128,
+    blocks: *mut u8,
+    nb: u32,
+) {
+    let (prev_low, prev_high) = split(prev);
+    EverCryptTiny_Blake2b_256_blake2b_update_multi(len, wv, hash, prev_low, prev_high, blocks, nb);
+}
+
+/// `Hacl_Blake2b_256_blake2b_update_last` via [`EverCryptTiny_Blake2b_256_blake2b_update_last`]
+///
+/// # Safety
+/// The pointers must be valid as required by HACL*. The function is only available if the library has been built with
+/// 256 bit vector support.
+pub unsafe fn Hacl_Blake2b_256_blake2b_update_last(
+    len: u32,
+    wv: *mut *mut c_void,
+    hash: *mut *mut c_void,
+    prev: FStar_UInt128_uint128,
+    rem: u32,
+    d: *mut u8,
+) {
+    let (prev_low, prev_high) = split(prev);
+    EverCryptTiny_Blake2b_256_blake2b_update_last(len, wv, hash, prev_low, prev_high, rem, d);
+}
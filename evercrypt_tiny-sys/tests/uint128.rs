@@ -0,0 +1,129 @@
+use evercrypt_tiny_sys::{
+    Hacl_Blake2b_32_blake2b, Hacl_Blake2b_32_blake2b_finish, Hacl_Blake2b_32_blake2b_init,
+    Hacl_Blake2b_32_blake2b_update_key, Hacl_Blake2b_32_blake2b_update_last, Hacl_Blake2b_32_blake2b_update_multi,
+    Hacl_Hash_SHA2_hash_384, Hacl_Hash_SHA2_hash_512, Hacl_Hash_SHA2_update_last_384, Hacl_Hash_SHA2_update_last_512,
+    Hacl_Hash_SHA2_update_multi_384, Hacl_Hash_SHA2_update_multi_512,
+};
+
+/// The input lengths to test, which cover empty inputs and all block boundary cases
+const LENGTHS: &[usize] = &[0, 1, 111, 112, 127, 128, 129, 255, 256, 257, 1000];
+
+/// The SHA-384 initial hash value
+const SHA384_IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+/// The SHA-512 initial hash value
+const SHA512_IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// Hashes `data` with BLAKE2b via the incremental functions, mirroring HACL*'s internal `blake2b_update`
+fn blake2b_incremental(key: &[u8], data: &[u8], prev_offset: u128) -> [u8; 64] {
+    let (mut wv, mut hash, mut key, mut data) = ([0u64; 16], [0u64; 16], key.to_vec(), data.to_vec());
+    let (kk, ll) = (key.len() as u32, data.len() as u32);
+    unsafe {
+        // Absorb the key block
+        Hacl_Blake2b_32_blake2b_init(hash.as_mut_ptr(), kk, 64);
+        let mut prev = prev_offset;
+        if kk > 0 {
+            Hacl_Blake2b_32_blake2b_update_key(wv.as_mut_ptr(), hash.as_mut_ptr(), kk, key.as_mut_ptr(), ll);
+            prev += 128;
+        }
+
+        // Absorb the data, keeping the last full block for `update_last`
+        if kk == 0 || ll > 0 {
+            let (nb, rem) = match (ll / 128, ll % 128) {
+                (nb, 0) if nb > 0 => (nb - 1, 128),
+                (nb, rem) => (nb, rem),
+            };
+            Hacl_Blake2b_32_blake2b_update_multi(ll, wv.as_mut_ptr(), hash.as_mut_ptr(), prev, data.as_mut_ptr(), nb);
+            Hacl_Blake2b_32_blake2b_update_last(ll, wv.as_mut_ptr(), hash.as_mut_ptr(), prev, rem, data.as_mut_ptr());
+        }
+    }
+
+    let mut digest = [0; 64];
+    unsafe { Hacl_Blake2b_32_blake2b_finish(64, digest.as_mut_ptr(), hash.as_mut_ptr()) };
+    digest
+}
+
+/// Hashes `data` with SHA-384 or SHA-512 via `update_multi` and `update_last`
+fn sha2_incremental(iv: [u64; 8], data: &[u8], prev_offset: u128) -> [u8; 64] {
+    let (mut state, mut data) = (iv, data.to_vec());
+    let blocks = data.len() / 128;
+    unsafe {
+        let (update_multi, update_last): (unsafe extern "C" fn(_, _, _), unsafe fn(_, _, _, _)) = match iv {
+            SHA384_IV => (Hacl_Hash_SHA2_update_multi_384, Hacl_Hash_SHA2_update_last_384),
+            _ => (Hacl_Hash_SHA2_update_multi_512, Hacl_Hash_SHA2_update_last_512),
+        };
+        update_multi(state.as_mut_ptr(), data.as_mut_ptr(), blocks as u32);
+        let (prev_len, rest) = (prev_offset + (blocks * 128) as u128, data[blocks * 128..].as_mut_ptr());
+        update_last(state.as_mut_ptr(), prev_len, rest, (data.len() % 128) as u32);
+    }
+
+    let mut digest = [0; 64];
+    digest.chunks_exact_mut(8).zip(state).for_each(|(chunk, word)| chunk.copy_from_slice(&word.to_be_bytes()));
+    digest
+}
+
+/// Cross-checks the incremental BLAKE2b functions against the one-shot function
+#[test]
+fn blake2b_32() {
+    for &len in LENGTHS {
+        let mut data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        for mut key in [vec![], (0..64).collect::<Vec<u8>>()] {
+            let mut expected = [0; 64];
+            unsafe {
+                Hacl_Blake2b_32_blake2b(
+                    64,
+                    expected.as_mut_ptr(),
+                    len as u32,
+                    data.as_mut_ptr(),
+                    key.len() as u32,
+                    key.as_mut_ptr(),
+                )
+            };
+            assert_eq!(blake2b_incremental(&key, &data, 0), expected, "Invalid digest for length {len}");
+        }
+    }
+}
+
+/// Cross-checks the incremental SHA-384 and SHA-512 functions against the one-shot functions
+#[test]
+fn sha2_384_512() {
+    for &len in LENGTHS {
+        let mut data: Vec<u8> = (0..len).map(|i| i as u8).collect();
+
+        let mut expected = [0; 48];
+        unsafe { Hacl_Hash_SHA2_hash_384(data.as_mut_ptr(), len as u32, expected.as_mut_ptr()) };
+        assert_eq!(sha2_incremental(SHA384_IV, &data, 0)[..48], expected, "Invalid digest for length {len}");
+
+        let mut expected = [0; 64];
+        unsafe { Hacl_Hash_SHA2_hash_512(data.as_mut_ptr(), len as u32, expected.as_mut_ptr()) };
+        assert_eq!(sha2_incremental(SHA512_IV, &data, 0), expected, "Invalid digest for length {len}");
+    }
+}
+
+/// Tests that the high half of the 128 bit length reaches HACL*
+#[test]
+fn high_half() {
+    let data = [0x61; 200];
+    let high = 1u128 << 64;
+
+    assert_ne!(blake2b_incremental(&[], &data, 0), blake2b_incremental(&[], &data, high));
+    assert_ne!(sha2_incremental(SHA384_IV, &data, 0), sha2_incremental(SHA384_IV, &data, high));
+    assert_ne!(sha2_incremental(SHA512_IV, &data, 0), sha2_incremental(SHA512_IV, &data, high));
+}
@@ -0,0 +1,15 @@
+//! Forwards the vector support of `evercrypt_tiny-sys` as `cfg` flags
+
+use std::env;
+
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(evercrypt_vec128)");
+    println!("cargo:rustc-check-cfg=cfg(evercrypt_vec256)");
+
+    // The sys crate exports whether the vector sources were compiled
+    for width in ["128", "256"] {
+        if env::var(format!("DEP_EVERCRYPT_VEC{width}")).is_ok_and(|value| value == "1") {
+            println!("cargo:rustc-cfg=evercrypt_vec{width}");
+        }
+    }
+}
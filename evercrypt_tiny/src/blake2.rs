@@ -0,0 +1,433 @@
+//! BLAKE2b and BLAKE2s as specified in RFC 7693 with keys, salts, personalization and variable digest lengths
+//!
+//! HACL*'s streaming BLAKE2 states only support unkeyed hashing with the maximum digest length. The parameter block is
+//! therefore applied on top of them: the initial chaining value is recomputed with `Hacl_Blake2*_32_blake2*_init` for
+//! the requested key and digest length, the salt and personalization are XORed into it as specified in RFC 7693,
+//! section 2.8, and the zero-padded key is absorbed as the first data block.
+
+use crate::{
+    secret::{self, SecretArray},
+    Error,
+};
+use alloc::{alloc::handle_alloc_error, vec, vec::Vec};
+use core::{
+    alloc::Layout,
+    fmt::{self, Debug, Formatter},
+    mem,
+    ptr::NonNull,
+    slice,
+};
+use digest::{InvalidBufferSize, InvalidOutputSize, Update, VariableOutput};
+use evercrypt_tiny_sys::{
+    Hacl_Blake2b_32_blake2b_init, Hacl_Blake2s_32_blake2s_init, Hacl_Streaming_Blake2_blake2b_32_no_key_create_in,
+    Hacl_Streaming_Blake2_blake2b_32_no_key_finish, Hacl_Streaming_Blake2_blake2b_32_no_key_free,
+    Hacl_Streaming_Blake2_blake2b_32_no_key_init, Hacl_Streaming_Blake2_blake2b_32_no_key_update,
+    Hacl_Streaming_Blake2_blake2b_32_state, Hacl_Streaming_Blake2_blake2s_32_no_key_create_in,
+    Hacl_Streaming_Blake2_blake2s_32_no_key_finish, Hacl_Streaming_Blake2_blake2s_32_no_key_free,
+    Hacl_Streaming_Blake2_blake2s_32_no_key_init, Hacl_Streaming_Blake2_blake2s_32_no_key_update,
+    Hacl_Streaming_Blake2_blake2s_32_state,
+};
+use subtle::ConstantTimeEq;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The maximum BLAKE2b digest and key length in bytes
+pub const BLAKE2B_MAX_LEN: usize = 64;
+/// The BLAKE2b salt and personalization length in bytes
+pub const BLAKE2B_PARAM_LEN: usize = 16;
+/// The maximum BLAKE2s digest and key length in bytes
+pub const BLAKE2S_MAX_LEN: usize = 32;
+/// The BLAKE2s salt and personalization length in bytes
+pub const BLAKE2S_PARAM_LEN: usize = 8;
+/// The BLAKE2b block length in bytes
+const BLAKE2B_BLOCK_LEN: usize = 128;
+/// The BLAKE2s block length in bytes
+const BLAKE2S_BLOCK_LEN: usize = 64;
+
+/// The parameters of a BLAKE2 computation
+///
+/// Salts and personalization strings that are shorter than the parameter length are padded with zero bytes.
+#[derive(Clone)]
+pub struct Blake2Params<'a> {
+    /// The digest length in bytes
+    pub digest_len: usize,
+    /// The key for MAC mode, or an empty slice for plain hashing
+    pub key: &'a [u8],
+    /// The salt
+    pub salt: &'a [u8],
+    /// The personalization string
+    pub personalization: &'a [u8],
+}
+impl<'a> Blake2Params<'a> {
+    /// Creates new parameters for an unkeyed hash with a length of `digest_len` bytes
+    pub const fn new(digest_len: usize) -> Self {
+        Self { digest_len, key: &[], salt: &[], personalization: &[] }
+    }
+    /// Sets the key
+    pub const fn with_key(mut self, key: &'a [u8]) -> Self {
+        self.key = key;
+        self
+    }
+    /// Sets the salt
+    pub const fn with_salt(mut self, salt: &'a [u8]) -> Self {
+        self.salt = salt;
+        self
+    }
+    /// Sets the personalization string
+    pub const fn with_personalization(mut self, personalization: &'a [u8]) -> Self {
+        self.personalization = personalization;
+        self
+    }
+
+    /// Validates the parameters against the limits of a BLAKE2 variant
+    fn check(&self, max_len: usize, param_len: usize) -> Result<(), Error> {
+        if !(1..=max_len).contains(&self.digest_len) {
+            return Err(Error::InvalidLength);
+        }
+        if self.key.len() > max_len {
+            return Err(Error::InvalidKey);
+        }
+        if self.salt.len() > param_len || self.personalization.len() > param_len {
+            return Err(Error::InvalidLength);
+        }
+        Ok(())
+    }
+}
+impl Debug for Blake2Params<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Blake2Params")
+            .field("digest_len", &self.digest_len)
+            .field("salt", &self.salt)
+            .field("personalization", &self.personalization)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The parameters that are needed to reset a computation
+struct OwnedParams<const MAX_LEN: usize, const PARAM_LEN: usize> {
+    /// The digest length in bytes
+    digest_len: usize,
+    /// The key, zero-padded to the maximum key length
+    key: SecretArray<MAX_LEN>,
+    /// The key length in bytes
+    key_len: usize,
+    /// The zero-padded salt
+    salt: [u8; PARAM_LEN],
+    /// The zero-padded personalization string
+    personalization: [u8; PARAM_LEN],
+}
+impl<const MAX_LEN: usize, const PARAM_LEN: usize> OwnedParams<MAX_LEN, PARAM_LEN> {
+    /// Validates and copies `params`
+    fn new(params: &Blake2Params) -> Result<Self, Error> {
+        params.check(MAX_LEN, PARAM_LEN)?;
+        let mut this = Self {
+            digest_len: params.digest_len,
+            key: SecretArray::default(),
+            key_len: params.key.len(),
+            salt: [0; PARAM_LEN],
+            personalization: [0; PARAM_LEN],
+        };
+        this.key[..params.key.len()].copy_from_slice(params.key);
+        this.salt[..params.salt.len()].copy_from_slice(params.salt);
+        this.personalization[..params.personalization.len()].copy_from_slice(params.personalization);
+        Ok(this)
+    }
+}
+
+/// An incremental BLAKE2b computation backed by `Hacl_Streaming_Blake2_blake2b_32`
+pub struct Blake2b {
+    /// The parameters
+    params: OwnedParams<BLAKE2B_MAX_LEN, BLAKE2B_PARAM_LEN>,
+    /// The streaming state allocated by HACL*
+    state: NonNull<Hacl_Streaming_Blake2_blake2b_32_state>,
+}
+impl Blake2b {
+    /// Creates a new computation with `params`
+    ///
+    /// The digest length must be within `1..=64` bytes, the key must not be longer than 64 bytes and the salt and
+    /// personalization must not be longer than 16 bytes.
+    pub fn new(params: &Blake2Params) -> Result<Self, Error> {
+        let params = OwnedParams::new(params)?;
+        let state = unsafe { Hacl_Streaming_Blake2_blake2b_32_no_key_create_in() };
+        let state = NonNull::new(state)
+            .unwrap_or_else(|| handle_alloc_error(Layout::new::<Hacl_Streaming_Blake2_blake2b_32_state>()));
+
+        let mut this = Self { params, state };
+        this.reset();
+        Ok(this)
+    }
+
+    /// The digest length in bytes
+    pub const fn digest_len(&self) -> usize {
+        self.params.digest_len
+    }
+
+    /// Absorbs `data`
+    pub fn update(&mut self, data: &[u8]) {
+        // HACL* takes 32 bit lengths, so larger inputs are absorbed in chunks
+        for chunk in data.chunks(u32::MAX as usize) {
+            unsafe {
+                Hacl_Streaming_Blake2_blake2b_32_no_key_update(
+                    self.state.as_ptr(),
+                    chunk.as_ptr().cast_mut(),
+                    chunk.len() as u32,
+                )
+            };
+        }
+    }
+
+    /// Computes the digest over all absorbed data
+    pub fn finalize(self) -> Vec<u8> {
+        let mut digest = vec![0; self.params.digest_len];
+        self.finalize_into(&mut digest);
+        digest
+    }
+
+    /// Verifies in constant time that `tag` is the digest over all absorbed data
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        let digest = self.finalize();
+        match bool::from(digest.ct_eq(tag)) {
+            true => Ok(()),
+            false => Err(Error::VerificationFailed),
+        }
+    }
+
+    /// Resets the computation to the empty input while keeping the parameters
+    pub fn reset(&mut self) {
+        // Reinitialize the state and recompute the chaining value for our parameters
+        unsafe { Hacl_Streaming_Blake2_blake2b_32_no_key_init(self.state.as_ptr()) };
+        let hash = unsafe { self.state.as_ref().block_state.snd };
+        unsafe { Hacl_Blake2b_32_blake2b_init(hash, self.params.key_len as u32, self.params.digest_len as u32) };
+
+        // Apply the salt and personalization to the words 4 to 7 (RFC 7693, section 2.8)
+        let words = self.params.salt.chunks_exact(8).chain(self.params.personalization.chunks_exact(8));
+        for (index, word) in words.enumerate() {
+            let word = u64::from_le_bytes(word.try_into().expect("Invalid word length"));
+            unsafe { *hash.add(4 + index) ^= word };
+        }
+
+        // Absorb the padded key as first block
+        if self.params.key_len > 0 {
+            let mut key_block = SecretArray::<BLAKE2B_BLOCK_LEN>::default();
+            key_block[..BLAKE2B_MAX_LEN].copy_from_slice(&self.params.key[..]);
+            self.update(&key_block[..]);
+        }
+    }
+
+    /// Writes the digest over all absorbed data into `digest`, which must be exactly `digest_len` bytes long
+    fn finalize_into(&self, digest: &mut [u8]) {
+        let mut full = [0; BLAKE2B_MAX_LEN];
+        unsafe { Hacl_Streaming_Blake2_blake2b_32_no_key_finish(self.state.as_ptr(), full.as_mut_ptr()) };
+        digest.copy_from_slice(&full[..self.params.digest_len]);
+    }
+
+    /// Erases the working vector, the chaining value and the buffered block, which may be the key block
+    fn erase(&mut self) {
+        unsafe {
+            let state = self.state.as_ref();
+            let words_len = 16 * mem::size_of::<u64>();
+            secret::memzero(slice::from_raw_parts_mut(state.block_state.fst.cast(), words_len));
+            secret::memzero(slice::from_raw_parts_mut(state.block_state.snd.cast(), words_len));
+            secret::memzero(slice::from_raw_parts_mut(state.buf, BLAKE2B_BLOCK_LEN));
+        }
+    }
+}
+impl Debug for Blake2b {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Blake2b").field("digest_len", &self.params.digest_len).finish_non_exhaustive()
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for Blake2b {
+    fn zeroize(&mut self) {
+        // Leaves an unkeyed computation with the same digest length, salt and personalization
+        self.params.key.zeroize();
+        self.params.key_len = 0;
+        self.erase();
+        self.reset();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Blake2b {}
+impl Drop for Blake2b {
+    fn drop(&mut self) {
+        self.erase();
+        unsafe { Hacl_Streaming_Blake2_blake2b_32_no_key_free(self.state.as_ptr()) };
+    }
+}
+// The state is exclusively owned and only mutated through `&mut self`
+unsafe impl Send for Blake2b {}
+unsafe impl Sync for Blake2b {}
+impl Update for Blake2b {
+    fn update(&mut self, data: &[u8]) {
+        Blake2b::update(self, data);
+    }
+}
+impl VariableOutput for Blake2b {
+    const MAX_OUTPUT_SIZE: usize = BLAKE2B_MAX_LEN;
+
+    fn new(output_size: usize) -> Result<Self, InvalidOutputSize> {
+        Blake2b::new(&Blake2Params::new(output_size)).map_err(|_| InvalidOutputSize)
+    }
+
+    fn output_size(&self) -> usize {
+        self.params.digest_len
+    }
+
+    fn finalize_variable(self, out: &mut [u8]) -> Result<(), InvalidBufferSize> {
+        if out.len() != self.params.digest_len {
+            return Err(InvalidBufferSize);
+        }
+        self.finalize_into(out);
+        Ok(())
+    }
+}
+
+/// An incremental BLAKE2s computation backed by `Hacl_Streaming_Blake2_blake2s_32`
+pub struct Blake2s {
+    /// The parameters
+    params: OwnedParams<BLAKE2S_MAX_LEN, BLAKE2S_PARAM_LEN>,
+    /// The streaming state allocated by HACL*
+    state: NonNull<Hacl_Streaming_Blake2_blake2s_32_state>,
+}
+impl Blake2s {
+    /// Creates a new computation with `params`
+    ///
+    /// The digest length must be within `1..=32` bytes, the key must not be longer than 32 bytes and the salt and
+    /// personalization must not be longer than 8 bytes.
+    pub fn new(params: &Blake2Params) -> Result<Self, Error> {
+        let params = OwnedParams::new(params)?;
+        let state = unsafe { Hacl_Streaming_Blake2_blake2s_32_no_key_create_in() };
+        let state = NonNull::new(state)
+            .unwrap_or_else(|| handle_alloc_error(Layout::new::<Hacl_Streaming_Blake2_blake2s_32_state>()));
+
+        let mut this = Self { params, state };
+        this.reset();
+        Ok(this)
+    }
+
+    /// The digest length in bytes
+    pub const fn digest_len(&self) -> usize {
+        self.params.digest_len
+    }
+
+    /// Absorbs `data`
+    pub fn update(&mut self, data: &[u8]) {
+        // HACL* takes 32 bit lengths, so larger inputs are absorbed in chunks
+        for chunk in data.chunks(u32::MAX as usize) {
+            unsafe {
+                Hacl_Streaming_Blake2_blake2s_32_no_key_update(
+                    self.state.as_ptr(),
+                    chunk.as_ptr().cast_mut(),
+                    chunk.len() as u32,
+                )
+            };
+        }
+    }
+
+    /// Computes the digest over all absorbed data
+    pub fn finalize(self) -> Vec<u8> {
+        let mut digest = vec![0; self.params.digest_len];
+        self.finalize_into(&mut digest);
+        digest
+    }
+
+    /// Verifies in constant time that `tag` is the digest over all absorbed data
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        let digest = self.finalize();
+        match bool::from(digest.ct_eq(tag)) {
+            true => Ok(()),
+            false => Err(Error::VerificationFailed),
+        }
+    }
+
+    /// Resets the computation to the empty input while keeping the parameters
+    pub fn reset(&mut self) {
+        // Reinitialize the state and recompute the chaining value for our parameters
+        unsafe { Hacl_Streaming_Blake2_blake2s_32_no_key_init(self.state.as_ptr()) };
+        let hash = unsafe { self.state.as_ref().block_state.snd };
+        unsafe { Hacl_Blake2s_32_blake2s_init(hash, self.params.key_len as u32, self.params.digest_len as u32) };
+
+        // Apply the salt and personalization to the words 4 to 7 (RFC 7693, section 2.8)
+        let words = self.params.salt.chunks_exact(4).chain(self.params.personalization.chunks_exact(4));
+        for (index, word) in words.enumerate() {
+            let word = u32::from_le_bytes(word.try_into().expect("Invalid word length"));
+            unsafe { *hash.add(4 + index) ^= word };
+        }
+
+        // Absorb the padded key as first block
+        if self.params.key_len > 0 {
+            let mut key_block = SecretArray::<BLAKE2S_BLOCK_LEN>::default();
+            key_block[..BLAKE2S_MAX_LEN].copy_from_slice(&self.params.key[..]);
+            self.update(&key_block[..]);
+        }
+    }
+
+    /// Writes the digest over all absorbed data into `digest`, which must be exactly `digest_len` bytes long
+    fn finalize_into(&self, digest: &mut [u8]) {
+        let mut full = [0; BLAKE2S_MAX_LEN];
+        unsafe { Hacl_Streaming_Blake2_blake2s_32_no_key_finish(self.state.as_ptr(), full.as_mut_ptr()) };
+        digest.copy_from_slice(&full[..self.params.digest_len]);
+    }
+
+    /// Erases the working vector, the chaining value and the buffered block, which may be the key block
+    fn erase(&mut self) {
+        unsafe {
+            let state = self.state.as_ref();
+            let words_len = 16 * mem::size_of::<u32>();
+            secret::memzero(slice::from_raw_parts_mut(state.block_state.fst.cast(), words_len));
+            secret::memzero(slice::from_raw_parts_mut(state.block_state.snd.cast(), words_len));
+            secret::memzero(slice::from_raw_parts_mut(state.buf, BLAKE2S_BLOCK_LEN));
+        }
+    }
+}
+impl Debug for Blake2s {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Blake2s").field("digest_len", &self.params.digest_len).finish_non_exhaustive()
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for Blake2s {
+    fn zeroize(&mut self) {
+        // Leaves an unkeyed computation with the same digest length, salt and personalization
+        self.params.key.zeroize();
+        self.params.key_len = 0;
+        self.erase();
+        self.reset();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Blake2s {}
+impl Drop for Blake2s {
+    fn drop(&mut self) {
+        self.erase();
+        unsafe { Hacl_Streaming_Blake2_blake2s_32_no_key_free(self.state.as_ptr()) };
+    }
+}
+// The state is exclusively owned and only mutated through `&mut self`
+unsafe impl Send for Blake2s {}
+unsafe impl Sync for Blake2s {}
+impl Update for Blake2s {
+    fn update(&mut self, data: &[u8]) {
+        Blake2s::update(self, data);
+    }
+}
+impl VariableOutput for Blake2s {
+    const MAX_OUTPUT_SIZE: usize = BLAKE2S_MAX_LEN;
+
+    fn new(output_size: usize) -> Result<Self, InvalidOutputSize> {
+        Blake2s::new(&Blake2Params::new(output_size)).map_err(|_| InvalidOutputSize)
+    }
+
+    fn output_size(&self) -> usize {
+        self.params.digest_len
+    }
+
+    fn finalize_variable(self, out: &mut [u8]) -> Result<(), InvalidBufferSize> {
+        if out.len() != self.params.digest_len {
+            return Err(InvalidBufferSize);
+        }
+        self.finalize_into(out);
+        Ok(())
+    }
+}
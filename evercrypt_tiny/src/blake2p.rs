@@ -0,0 +1,547 @@
+//! BLAKE2bp and BLAKE2sp, the parallel tree hashing modes from the BLAKE2 specification
+//!
+//! The input is split into blocks that are distributed round-robin over 4 (BLAKE2bp) or 8 (BLAKE2sp) leaves, and a root
+//! node hashes the concatenated leaf digests. The results are identical to `b2sum -a blake2bp` and `b2sum -a blake2sp`.
+//! Since the leaves are independent, large updates are processed with one thread per leaf if the `std` feature is
+//! enabled.
+//!
+//! All blocks are compressed by HACL* except for the final blocks of the last leaf and the root: the tree mode marks
+//! them with the last node flag, which HACL*'s compression function does not support, so they are compressed by a
+//! small portable implementation of the BLAKE2 compression function instead.
+
+use crate::{
+    blake2::{BLAKE2B_MAX_LEN, BLAKE2S_MAX_LEN},
+    secret::{self, SecretArray},
+    Error,
+};
+use alloc::{vec, vec::Vec};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    slice,
+};
+use digest::{InvalidBufferSize, InvalidOutputSize, Update, VariableOutput};
+use evercrypt_tiny_sys::{
+    Hacl_Blake2b_32_blake2b_finish, Hacl_Blake2b_32_blake2b_init, Hacl_Blake2b_32_blake2b_update_last,
+    Hacl_Blake2b_32_blake2b_update_multi, Hacl_Blake2s_32_blake2s_finish, Hacl_Blake2s_32_blake2s_init,
+    Hacl_Blake2s_32_blake2s_update_last, Hacl_Blake2s_32_blake2s_update_multi,
+};
+use sealed::Word;
+use subtle::ConstantTimeEq;
+
+/// The length of a stripe, i.e. one block for every leaf, which is 512 bytes for both variants
+const STRIPE_LEN: usize = 512;
+/// The largest block length of both variants
+const MAX_BLOCK_LEN: usize = 128;
+/// The minimum update length that is processed with one thread per leaf
+#[cfg(feature = "std")]
+const PARALLEL_THRESHOLD: usize = 64 * 1024;
+/// The message schedule of the BLAKE2 compression function
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The variant-specific node operations; this module is private, so the trait cannot be implemented outside of the
+/// crate
+mod sealed {
+    use core::ops::BitXor;
+
+    /// A BLAKE2 state word
+    pub trait Word: Copy + Default + BitXor<Output = Self> + Send {
+        /// The word length in bytes
+        const BYTES: usize;
+        /// A word with all bits set
+        const MAX: Self;
+
+        /// Decodes a little-endian word from `bytes`, which must be exactly `BYTES` long
+        fn from_le(bytes: &[u8]) -> Self;
+        /// Truncates `value` to the word length
+        fn from_u64(value: u64) -> Self;
+        /// Adds `other` modulo the word length
+        fn add(self, other: Self) -> Self;
+        /// Rotates the word right by `bits`
+        fn rotr(self, bits: u32) -> Self;
+    }
+    impl Word for u32 {
+        const BYTES: usize = 4;
+        const MAX: Self = u32::MAX;
+
+        fn from_le(bytes: &[u8]) -> Self {
+            Self::from_le_bytes(bytes.try_into().expect("Invalid word length"))
+        }
+        fn from_u64(value: u64) -> Self {
+            value as u32
+        }
+        fn add(self, other: Self) -> Self {
+            self.wrapping_add(other)
+        }
+        fn rotr(self, bits: u32) -> Self {
+            self.rotate_right(bits)
+        }
+    }
+    impl Word for u64 {
+        const BYTES: usize = 8;
+        const MAX: Self = u64::MAX;
+
+        fn from_le(bytes: &[u8]) -> Self {
+            Self::from_le_bytes(bytes.try_into().expect("Invalid word length"))
+        }
+        fn from_u64(value: u64) -> Self {
+            value
+        }
+        fn add(self, other: Self) -> Self {
+            self.wrapping_add(other)
+        }
+        fn rotr(self, bits: u32) -> Self {
+            self.rotate_right(bits)
+        }
+    }
+
+    /// A BLAKE2 variant that can be used as tree node
+    pub trait Node {
+        /// The state word
+        type Word: Word;
+        /// The block length in bytes
+        const BLOCK_LEN: usize;
+        /// The maximum digest and key length in bytes, which is also the length of the leaf digests
+        const MAX_LEN: usize;
+        /// The number of leaves
+        const FANOUT: usize;
+        /// The offset of the node depth within the parameter block
+        const NODE_DEPTH_OFFSET: usize;
+        /// The number of rounds of the compression function
+        const ROUNDS: usize;
+        /// The rotation distances of the mixing function
+        const ROTATIONS: [u32; 4];
+        /// The initialization vector
+        const IV: [Self::Word; 8];
+
+        /// Initializes `hash` for a node with a key of `key_len` bytes and a digest length of `digest_len` bytes
+        fn init(hash: &mut [Self::Word; 16], key_len: usize, digest_len: usize);
+        /// Compresses a full non-final `block` after `counter` bytes have been compressed
+        fn compress(wv: &mut [Self::Word; 16], hash: &mut [Self::Word; 16], counter: u64, block: &[u8]);
+        /// Compresses the final `data` of a node that is not the last node after `counter` bytes have been compressed
+        fn compress_last(wv: &mut [Self::Word; 16], hash: &mut [Self::Word; 16], counter: u64, data: &[u8]);
+        /// Writes the first `digest.len()` bytes of the chaining value into `digest`
+        fn finish(hash: &mut [Self::Word; 16], digest: &mut [u8]);
+    }
+}
+
+/// A BLAKE2 variant with its tree parameters
+pub trait TreeVariant: sealed::Node {}
+
+/// The BLAKE2bp variant with 4 BLAKE2b leaves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bp;
+impl sealed::Node for Bp {
+    type Word = u64;
+    const BLOCK_LEN: usize = 128;
+    const MAX_LEN: usize = BLAKE2B_MAX_LEN;
+    const FANOUT: usize = 4;
+    const NODE_DEPTH_OFFSET: usize = 16;
+    const ROUNDS: usize = 12;
+    const ROTATIONS: [u32; 4] = [32, 24, 16, 63];
+    const IV: [u64; 8] = [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ];
+
+    fn init(hash: &mut [u64; 16], key_len: usize, digest_len: usize) {
+        unsafe { Hacl_Blake2b_32_blake2b_init(hash.as_mut_ptr(), key_len as u32, digest_len as u32) };
+    }
+    fn compress(wv: &mut [u64; 16], hash: &mut [u64; 16], counter: u64, block: &[u8]) {
+        let (wv, hash, block) = (wv.as_mut_ptr(), hash.as_mut_ptr(), block.as_ptr().cast_mut());
+        unsafe { Hacl_Blake2b_32_blake2b_update_multi(Self::BLOCK_LEN as u32, wv, hash, counter as u128, block, 1) };
+    }
+    fn compress_last(wv: &mut [u64; 16], hash: &mut [u64; 16], counter: u64, data: &[u8]) {
+        let (wv, hash, len) = (wv.as_mut_ptr(), hash.as_mut_ptr(), data.len() as u32);
+        unsafe { Hacl_Blake2b_32_blake2b_update_last(len, wv, hash, counter as u128, len, data.as_ptr().cast_mut()) };
+    }
+    fn finish(hash: &mut [u64; 16], digest: &mut [u8]) {
+        unsafe { Hacl_Blake2b_32_blake2b_finish(digest.len() as u32, digest.as_mut_ptr(), hash.as_mut_ptr()) };
+    }
+}
+impl TreeVariant for Bp {}
+
+/// The BLAKE2sp variant with 8 BLAKE2s leaves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sp;
+impl sealed::Node for Sp {
+    type Word = u32;
+    const BLOCK_LEN: usize = 64;
+    const MAX_LEN: usize = BLAKE2S_MAX_LEN;
+    const FANOUT: usize = 8;
+    const NODE_DEPTH_OFFSET: usize = 14;
+    const ROUNDS: usize = 10;
+    const ROTATIONS: [u32; 4] = [16, 12, 8, 7];
+    const IV: [u32; 8] =
+        [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+    fn init(hash: &mut [u32; 16], key_len: usize, digest_len: usize) {
+        unsafe { Hacl_Blake2s_32_blake2s_init(hash.as_mut_ptr(), key_len as u32, digest_len as u32) };
+    }
+    fn compress(wv: &mut [u32; 16], hash: &mut [u32; 16], counter: u64, block: &[u8]) {
+        let (wv, hash, block) = (wv.as_mut_ptr(), hash.as_mut_ptr(), block.as_ptr().cast_mut());
+        unsafe { Hacl_Blake2s_32_blake2s_update_multi(Self::BLOCK_LEN as u32, wv, hash, counter, block, 1) };
+    }
+    fn compress_last(wv: &mut [u32; 16], hash: &mut [u32; 16], counter: u64, data: &[u8]) {
+        let (wv, hash, len) = (wv.as_mut_ptr(), hash.as_mut_ptr(), data.len() as u32);
+        unsafe { Hacl_Blake2s_32_blake2s_update_last(len, wv, hash, counter, len, data.as_ptr().cast_mut()) };
+    }
+    fn finish(hash: &mut [u32; 16], digest: &mut [u8]) {
+        unsafe { Hacl_Blake2s_32_blake2s_finish(digest.len() as u32, digest.as_mut_ptr(), hash.as_mut_ptr()) };
+    }
+}
+impl TreeVariant for Sp {}
+
+/// The position of a node within the tree
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    /// The offset of the node within its level
+    offset: u64,
+    /// The depth of the node, which is `0` for leaves and `1` for the root
+    depth: u8,
+    /// Whether the node is the last node of its level
+    last: bool,
+}
+
+/// A single BLAKE2 node that holds back its last full block until it is finalized
+struct Node<V>
+where
+    V: TreeVariant,
+{
+    /// The working vector
+    wv: [V::Word; 16],
+    /// The chaining value
+    hash: [V::Word; 16],
+    /// The number of compressed bytes
+    counter: u64,
+    /// The pending block, which is the key block until the first data block arrives
+    pending: SecretArray<MAX_BLOCK_LEN>,
+    /// Whether `pending` contains a block
+    has_pending: bool,
+    /// Whether the node is the last node of its level
+    last: bool,
+}
+impl<V> Node<V>
+where
+    V: TreeVariant,
+{
+    /// Creates a new node at `position` and absorbs the zero-padded `key` block if `key` is not empty
+    ///
+    /// `key_len` is always part of the parameter block, even if the key is not absorbed.
+    fn new(key_len: usize, key: &[u8], digest_len: usize, position: Position) -> Self {
+        let mut hash = [V::Word::default(); 16];
+        V::init(&mut hash, key_len, digest_len);
+
+        // Apply the tree parameters on top of the sequential defaults of fanout 1 and depth 1
+        let mut params = [0; 32];
+        params[2] = V::FANOUT as u8 ^ 1;
+        params[3] = 2 ^ 1;
+        params[8..16].copy_from_slice(&position.offset.to_le_bytes());
+        params[V::NODE_DEPTH_OFFSET] = position.depth;
+        params[V::NODE_DEPTH_OFFSET + 1] = V::MAX_LEN as u8;
+        for (word, param) in hash.iter_mut().zip(params.chunks_exact(V::Word::BYTES)) {
+            *word = *word ^ V::Word::from_le(param);
+        }
+
+        let mut this = Self {
+            wv: [V::Word::default(); 16],
+            hash,
+            counter: 0,
+            pending: SecretArray::default(),
+            has_pending: false,
+            last: position.last,
+        };
+        if !key.is_empty() {
+            let mut key_block = SecretArray::<MAX_BLOCK_LEN>::default();
+            key_block[..key.len()].copy_from_slice(key);
+            this.update_block(&key_block[..V::BLOCK_LEN]);
+        }
+        this
+    }
+
+    /// Absorbs a full `block`
+    fn update_block(&mut self, block: &[u8]) {
+        self.flush();
+        self.pending[..V::BLOCK_LEN].copy_from_slice(block);
+        self.has_pending = true;
+    }
+
+    /// Compresses the pending block
+    fn flush(&mut self) {
+        if self.has_pending {
+            V::compress(&mut self.wv, &mut self.hash, self.counter, &self.pending[..V::BLOCK_LEN]);
+            self.counter += V::BLOCK_LEN as u64;
+            self.has_pending = false;
+        }
+    }
+
+    /// Absorbs the final `rest`, which must not be longer than a block, and writes the digest into `digest`
+    fn finalize_into(mut self, rest: &[u8], digest: &mut [u8]) {
+        // Select the final block, which is the key block for an empty keyed input
+        let mut final_block = SecretArray::<MAX_BLOCK_LEN>::default();
+        let final_len = match rest.is_empty() && self.has_pending {
+            true => {
+                final_block.copy_from_slice(&self.pending[..]);
+                V::BLOCK_LEN
+            }
+            false => {
+                self.flush();
+                final_block[..rest.len()].copy_from_slice(rest);
+                rest.len()
+            }
+        };
+
+        // Compress the final block
+        match self.last {
+            true => self.compress_last_node(&final_block[..V::BLOCK_LEN], final_len as u64),
+            false => V::compress_last(&mut self.wv, &mut self.hash, self.counter, &final_block[..final_len]),
+        }
+        V::finish(&mut self.hash, digest);
+    }
+
+    /// Compresses the zero-padded final `block` of the last node, which contains `len` data bytes
+    ///
+    /// This is the BLAKE2 compression function as specified in RFC 7693, section 3.2, with both finalization flags set.
+    fn compress_last_node(&mut self, block: &[u8], len: u64) {
+        let counter = self.counter + len;
+        let mut message: Vec<V::Word> = block.chunks_exact(V::Word::BYTES).map(V::Word::from_le).collect();
+
+        // Initialize the local work vector
+        let mut v = [V::Word::default(); 16];
+        v[..8].copy_from_slice(&self.hash[..8]);
+        v[8..].copy_from_slice(&V::IV);
+        v[12] = v[12] ^ V::Word::from_u64(counter);
+        v[13] = v[13] ^ V::Word::from_u64(((counter as u128) >> (V::Word::BYTES * 8)) as u64);
+        v[14] = v[14] ^ V::Word::MAX;
+        v[15] = v[15] ^ V::Word::MAX;
+
+        // Mix the message into the work vector
+        for round in 0..V::ROUNDS {
+            let s = &SIGMA[round % 10];
+            let m = |i: usize| message[s[i]];
+            Self::mix(&mut v, [0, 4, 8, 12], m(0), m(1));
+            Self::mix(&mut v, [1, 5, 9, 13], m(2), m(3));
+            Self::mix(&mut v, [2, 6, 10, 14], m(4), m(5));
+            Self::mix(&mut v, [3, 7, 11, 15], m(6), m(7));
+            Self::mix(&mut v, [0, 5, 10, 15], m(8), m(9));
+            Self::mix(&mut v, [1, 6, 11, 12], m(10), m(11));
+            Self::mix(&mut v, [2, 7, 8, 13], m(12), m(13));
+            Self::mix(&mut v, [3, 4, 9, 14], m(14), m(15));
+        }
+        for i in 0..8 {
+            self.hash[i] = self.hash[i] ^ v[i] ^ v[i + 8];
+        }
+
+        // Erase the decoded message, which may be the key block
+        let message_len = message.len() * V::Word::BYTES;
+        secret::memzero(unsafe { slice::from_raw_parts_mut(message.as_mut_ptr().cast(), message_len) });
+    }
+
+    /// The mixing function `G` as specified in RFC 7693, section 3.1
+    fn mix(v: &mut [V::Word; 16], [a, b, c, d]: [usize; 4], x: V::Word, y: V::Word) {
+        let [r1, r2, r3, r4] = V::ROTATIONS;
+        v[a] = v[a].add(v[b]).add(x);
+        v[d] = (v[d] ^ v[a]).rotr(r1);
+        v[c] = v[c].add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotr(r2);
+        v[a] = v[a].add(v[b]).add(y);
+        v[d] = (v[d] ^ v[a]).rotr(r3);
+        v[c] = v[c].add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotr(r4);
+    }
+}
+
+/// BLAKE2bp with 4 parallel BLAKE2b leaves
+pub type Blake2bp = Blake2Tree<Bp>;
+/// BLAKE2sp with 8 parallel BLAKE2s leaves
+pub type Blake2sp = Blake2Tree<Sp>;
+
+/// An incremental BLAKE2bp or BLAKE2sp computation
+pub struct Blake2Tree<V>
+where
+    V: TreeVariant,
+{
+    /// The digest length in bytes
+    digest_len: usize,
+    /// The key length in bytes
+    key_len: usize,
+    /// The leaves
+    leaves: Vec<Node<V>>,
+    /// The buffered input that does not fill a stripe yet
+    buffer: [u8; STRIPE_LEN],
+    /// The number of buffered bytes
+    buffered: usize,
+    /// The variant
+    _variant: PhantomData<V>,
+}
+impl<V> Blake2Tree<V>
+where
+    V: TreeVariant,
+{
+    /// Creates a new computation with a digest length of `digest_len` bytes and an optional `key`
+    ///
+    /// The digest length must be within `1..=64` bytes for BLAKE2bp and `1..=32` bytes for BLAKE2sp; the key must not
+    /// be longer than the maximum digest length.
+    pub fn new(digest_len: usize, key: &[u8]) -> Result<Self, Error> {
+        if !(1..=V::MAX_LEN).contains(&digest_len) {
+            return Err(Error::InvalidLength);
+        }
+        if key.len() > V::MAX_LEN {
+            return Err(Error::InvalidKey);
+        }
+
+        // Create the leaves with their offsets
+        let leaves = (0..V::FANOUT)
+            .map(|offset| {
+                let position = Position { offset: offset as u64, depth: 0, last: offset == V::FANOUT - 1 };
+                Node::new(key.len(), key, digest_len, position)
+            })
+            .collect();
+        Ok(Self { digest_len, key_len: key.len(), leaves, buffer: [0; STRIPE_LEN], buffered: 0, _variant: PhantomData })
+    }
+
+    /// The digest length in bytes
+    pub const fn digest_len(&self) -> usize {
+        self.digest_len
+    }
+
+    /// Absorbs `data`
+    pub fn update(&mut self, mut data: &[u8]) {
+        // Complete a partially filled stripe first
+        if self.buffered > 0 {
+            let fill = (STRIPE_LEN - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + fill].copy_from_slice(&data[..fill]);
+            self.buffered += fill;
+            data = &data[fill..];
+            if self.buffered < STRIPE_LEN {
+                return;
+            }
+
+            let stripe = self.buffer;
+            self.update_stripes(&stripe);
+            self.buffered = 0;
+        }
+
+        // Process all full stripes directly and buffer the rest
+        let full_len = data.len() - data.len() % STRIPE_LEN;
+        self.update_stripes(&data[..full_len]);
+        self.buffer[..data.len() - full_len].copy_from_slice(&data[full_len..]);
+        self.buffered = data.len() - full_len;
+    }
+
+    /// Computes the digest over all absorbed data
+    pub fn finalize(self) -> Vec<u8> {
+        let mut digest = vec![0; self.digest_len];
+        self.finalize_into(&mut digest);
+        digest
+    }
+
+    /// Verifies in constant time that `tag` is the digest over all absorbed data
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        let digest = self.finalize();
+        match bool::from(digest.ct_eq(tag)) {
+            true => Ok(()),
+            false => Err(Error::VerificationFailed),
+        }
+    }
+
+    /// Absorbs the full `stripes` into the leaves, where every leaf takes one block of every stripe
+    fn update_stripes(&mut self, stripes: &[u8]) {
+        // Process large inputs with one thread per leaf
+        #[cfg(feature = "std")]
+        if stripes.len() >= PARALLEL_THRESHOLD {
+            std::thread::scope(|scope| {
+                for (index, leaf) in self.leaves.iter_mut().enumerate() {
+                    scope.spawn(move || Self::update_leaf(leaf, index, stripes));
+                }
+            });
+            return;
+        }
+
+        for (index, leaf) in self.leaves.iter_mut().enumerate() {
+            Self::update_leaf(leaf, index, stripes);
+        }
+    }
+
+    /// Absorbs the blocks of the leaf at `index` from the full `stripes`
+    fn update_leaf(leaf: &mut Node<V>, index: usize, stripes: &[u8]) {
+        let block = index * V::BLOCK_LEN..(index + 1) * V::BLOCK_LEN;
+        for stripe in stripes.chunks_exact(STRIPE_LEN) {
+            leaf.update_block(&stripe[block.clone()]);
+        }
+    }
+
+    /// Writes the digest over all absorbed data into `digest`, which must be exactly `digest_len` bytes long
+    fn finalize_into(self, digest: &mut [u8]) {
+        // Finalize the leaves with their share of the buffered data
+        let mut leaf_digests = [0; STRIPE_LEN];
+        for (index, leaf) in self.leaves.into_iter().enumerate() {
+            let start = (index * V::BLOCK_LEN).min(self.buffered);
+            let end = ((index + 1) * V::BLOCK_LEN).min(self.buffered);
+            leaf.finalize_into(&self.buffer[start..end], &mut leaf_digests[index * V::MAX_LEN..][..V::MAX_LEN]);
+        }
+
+        // Hash the concatenated leaf digests with the root node
+        let position = Position { offset: 0, depth: 1, last: true };
+        let mut root = Node::<V>::new(self.key_len, &[], self.digest_len, position);
+        for block in leaf_digests[..V::FANOUT * V::MAX_LEN].chunks_exact(V::BLOCK_LEN) {
+            root.update_block(block);
+        }
+        root.finalize_into(&[], digest);
+    }
+}
+impl<V> Debug for Blake2Tree<V>
+where
+    V: TreeVariant,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Blake2Tree").field("digest_len", &self.digest_len).finish_non_exhaustive()
+    }
+}
+impl<V> Update for Blake2Tree<V>
+where
+    V: TreeVariant,
+{
+    fn update(&mut self, data: &[u8]) {
+        Blake2Tree::update(self, data);
+    }
+}
+impl<V> VariableOutput for Blake2Tree<V>
+where
+    V: TreeVariant,
+{
+    const MAX_OUTPUT_SIZE: usize = V::MAX_LEN;
+
+    fn new(output_size: usize) -> Result<Self, InvalidOutputSize> {
+        Blake2Tree::new(output_size, &[]).map_err(|_| InvalidOutputSize)
+    }
+
+    fn output_size(&self) -> usize {
+        self.digest_len
+    }
+
+    fn finalize_variable(self, out: &mut [u8]) -> Result<(), InvalidBufferSize> {
+        if out.len() != self.digest_len {
+            return Err(InvalidBufferSize);
+        }
+        self.finalize_into(out);
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+//! Runtime CPU feature detection via `EverCrypt_AutoConfig2`
+//!
+//! EverCrypt's own dispatchers (e.g. `EverCrypt_AEAD` or `EverCrypt_Curve25519`) consult the global feature flags of
+//! `EverCrypt_AutoConfig2`, which remain unset until [`features`] has been called once.
+
+use core::{
+    hint,
+    sync::atomic::{AtomicU8, Ordering},
+};
+use evercrypt_tiny_sys::{EverCrypt_AutoConfig2_has_avx, EverCrypt_AutoConfig2_has_avx2, EverCrypt_AutoConfig2_init};
+
+/// The flag that is set once the detection has completed
+const DETECTED: u8 = 0x01;
+/// The flag that is set while the detection is running
+const BUSY: u8 = 0x02;
+/// The flag for AVX support
+#[cfg_attr(not(evercrypt_vec128), allow(dead_code))]
+pub const AVX: u8 = 0x04;
+/// The flag for AVX2 support
+#[cfg_attr(not(evercrypt_vec256), allow(dead_code))]
+pub const AVX2: u8 = 0x08;
+
+/// The detected features
+static FEATURES: AtomicU8 = AtomicU8::new(0);
+
+/// Detects the CPU features once and returns them as flags
+pub fn features() -> u8 {
+    loop {
+        match FEATURES.compare_exchange(0, BUSY, Ordering::Acquire, Ordering::Acquire) {
+            Ok(_) => break,
+            Err(features) if features & DETECTED != 0 => return features,
+            Err(_) => hint::spin_loop(),
+        }
+    }
+
+    // `EverCrypt_AutoConfig2_init` writes global state, so it must not run concurrently
+    unsafe { EverCrypt_AutoConfig2_init() };
+    let mut features = DETECTED;
+    if unsafe { EverCrypt_AutoConfig2_has_avx() } {
+        features |= AVX;
+    }
+    if unsafe { EverCrypt_AutoConfig2_has_avx2() } {
+        features |= AVX2;
+    }
+    FEATURES.store(features, Ordering::Release);
+    features
+}
@@ -0,0 +1,123 @@
+//! A process-wide CSPRNG with one [`EverCryptDrbg`] per thread
+//!
+//! [`fill_random`] lazily instantiates a SHA2-256 DRBG for the calling thread, so threads never share DRBG state. Each
+//! DRBG remembers the process ID it was seeded in and reseeds itself if it changes, so a child process never repeats
+//! the parent's output after `fork()`, even if the entropy source is deterministic. With the `getrandom` feature,
+//! [`getrandom_backend`] can be registered as custom `getrandom` backend on targets without a native backend (e.g.
+//! enclave or bare-metal targets), so that other crates share the same CSPRNG:
+//!
+//! ```ignore
+//! getrandom::register_custom_getrandom!(evercrypt_tiny::csprng::getrandom_backend);
+//! ```
+//!
+//! The DRBGs draw their entropy via HACL*'s system randomness, so an entropy source registered with the
+//! `custom-entropy` feature must not call back into `getrandom` if this backend is registered.
+
+use crate::{
+    drbg::{rng_error, EverCryptDrbg},
+    Error, HashAlgorithm,
+};
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use rand_core::{CryptoRng, RngCore};
+use std::{process, thread_local};
+
+/// The personalization prefix for all thread DRBGs
+const PERSONALIZATION: &[u8] = b"evercrypt_tiny csprng";
+
+/// The number of thread DRBGs that have been created so far
+static INSTANCES: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// The calling thread's DRBG
+    static THREAD_DRBG: RefCell<Option<ThreadDrbg>> = const { RefCell::new(None) };
+}
+
+/// A DRBG together with the process it has been seeded in
+#[derive(Debug)]
+struct ThreadDrbg {
+    /// The DRBG
+    drbg: EverCryptDrbg,
+    /// The ID of the process that seeded the DRBG
+    pid: u32,
+}
+impl ThreadDrbg {
+    /// Creates a new DRBG that is personalized with the process ID and a unique instance counter
+    fn new() -> Result<Self, Error> {
+        let pid = process::id();
+        let instance = INSTANCES.fetch_add(1, Ordering::Relaxed);
+
+        // Personalize the DRBG, so that no two instances share a seed even with a deterministic entropy source
+        let mut personalization = [0; PERSONALIZATION.len() + 12];
+        let (prefix, suffix) = personalization.split_at_mut(PERSONALIZATION.len());
+        prefix.copy_from_slice(PERSONALIZATION);
+        suffix[..4].copy_from_slice(&pid.to_be_bytes());
+        suffix[4..].copy_from_slice(&instance.to_be_bytes());
+        let drbg = EverCryptDrbg::new(HashAlgorithm::Sha2_256, &personalization)?;
+        Ok(Self { drbg, pid })
+    }
+
+    /// Fills `buf` with random bytes and reseeds first if the process ID has changed
+    fn fill(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        let pid = process::id();
+        if pid != self.pid {
+            // The process has been forked, so the state is shared with the parent
+            self.drbg.reseed(&pid.to_be_bytes())?;
+            self.pid = pid;
+        }
+        self.drbg.generate(buf, &[])
+    }
+}
+
+/// Fills `buf` with cryptographically secure random bytes from the calling thread's DRBG
+///
+/// This function fails if the entropy source fails or if it is called recursively from the entropy source.
+pub fn fill_random(buf: &mut [u8]) -> Result<(), Error> {
+    let result = THREAD_DRBG.try_with(|drbg| {
+        let mut drbg = drbg.try_borrow_mut().map_err(|_| Error::RngFailure)?;
+        match drbg.as_mut() {
+            Some(drbg) => drbg.fill(buf),
+            None => drbg.insert(ThreadDrbg::new()?).fill(buf),
+        }
+    });
+
+    // Use a temporary DRBG if the thread-local storage has already been destroyed
+    match result {
+        Ok(result) => result,
+        Err(_) => ThreadDrbg::new()?.fill(buf),
+    }
+}
+
+/// A handle to the calling thread's DRBG that implements [`RngCore`] and [`CryptoRng`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ThreadRng;
+impl RngCore for ThreadRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_random(dest).expect("Failed to generate random bytes");
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        fill_random(dest).map_err(rng_error)
+    }
+}
+impl CryptoRng for ThreadRng {}
+
+/// A custom `getrandom` backend that fills `dest` via [`fill_random`]
+#[cfg(feature = "getrandom")]
+pub fn getrandom_backend(dest: &mut [u8]) -> Result<(), getrandom::Error> {
+    fill_random(dest).map_err(|error| {
+        // `getrandom` reserves all codes below `CUSTOM_START` for itself
+        let code = getrandom::Error::CUSTOM_START + error as u32;
+        getrandom::Error::from(core::num::NonZeroU32::new(code).expect("Invalid error code"))
+    })
+}
@@ -0,0 +1,325 @@
+//! HMAC-DRBG as specified in NIST SP 800-90A
+//!
+//! [`HmacDrbg`] is seeded from a caller-provided entropy source and reseeds itself from that source once the reseed
+//! interval is exhausted. [`EverCryptDrbg`] draws its entropy from the operating system and reseeds on every request.
+//! Both split requests that exceed the maximum output length of a single HMAC-DRBG request and implement
+//! [`RngCore`] and [`CryptoRng`].
+
+use crate::{secret::SecretArray, Error, HashAlgorithm};
+use alloc::alloc::handle_alloc_error;
+use core::{
+    alloc::Layout,
+    ffi::c_void,
+    fmt::{self, Debug, Formatter},
+    num::NonZeroU32,
+    ptr::NonNull,
+};
+use evercrypt_tiny_sys::{
+    EverCrypt_DRBG_create, EverCrypt_DRBG_generate, EverCrypt_DRBG_instantiate, EverCrypt_DRBG_reseed,
+    EverCrypt_DRBG_state_s, EverCrypt_DRBG_uninstantiate, Hacl_HMAC_DRBG_create_in, Hacl_HMAC_DRBG_generate,
+    Hacl_HMAC_DRBG_instantiate, Hacl_HMAC_DRBG_max_additional_input_length, Hacl_HMAC_DRBG_max_output_length,
+    Hacl_HMAC_DRBG_max_personalization_string_length, Hacl_HMAC_DRBG_min_length, Hacl_HMAC_DRBG_reseed,
+    Hacl_HMAC_DRBG_state, Lib_Memzero0_memzero,
+};
+use rand_core::{CryptoRng, RngCore};
+
+extern "C" {
+    /// The C allocator's `free` which is used by KaRaMeL's `KRML_HOST_FREE`
+    fn free(ptr: *mut c_void);
+}
+
+/// The largest entropy input length of all supported hash functions
+const MAX_ENTROPY_LEN: usize = 32;
+
+/// Ensures that `hash` is supported by HMAC-DRBG
+///
+/// Supported hash functions are SHA-1, SHA2-256, SHA2-384 and SHA2-512.
+fn check_hash(hash: HashAlgorithm) -> Result<(), Error> {
+    match hash {
+        HashAlgorithm::Sha1 | HashAlgorithm::Sha2_256 | HashAlgorithm::Sha2_384 | HashAlgorithm::Sha2_512 => Ok(()),
+        _ => Err(Error::UnsupportedAlgorithm),
+    }
+}
+
+/// Ensures that `input` is not longer than `max_len` bytes and returns its length
+fn check_len(input: &[u8], max_len: u32) -> Result<u32, Error> {
+    match u32::try_from(input.len()) {
+        Ok(len) if len <= max_len => Ok(len),
+        _ => Err(Error::InvalidLength),
+    }
+}
+
+/// Converts a crate error into a `rand_core` error
+pub(crate) fn rng_error(error: Error) -> rand_core::Error {
+    // `rand_core` reserves all codes below `CUSTOM_START` for itself
+    let code = rand_core::Error::CUSTOM_START + error as u32;
+    rand_core::Error::from(NonZeroU32::new(code).expect("Invalid error code"))
+}
+
+/// An HMAC-DRBG that is seeded and automatically reseeded from an entropy source `E`
+pub struct HmacDrbg<E> {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The state which is allocated by `Hacl_HMAC_DRBG_create_in`
+    state: Hacl_HMAC_DRBG_state,
+    /// The entropy source
+    entropy: E,
+}
+impl<E> HmacDrbg<E>
+where
+    E: RngCore + CryptoRng,
+{
+    /// Creates a new DRBG with `hash` that is seeded from `entropy` and bound to `personalization`
+    ///
+    /// The DRBG draws an entropy input of the hash function's security strength and a nonce of half that length from
+    /// `entropy`. Supported hash functions are SHA-1, SHA2-256, SHA2-384 and SHA2-512.
+    pub fn new(hash: HashAlgorithm, mut entropy: E, personalization: &[u8]) -> Result<Self, Error> {
+        // Validate the parameters
+        check_hash(hash)?;
+        let max_personalization_len = unsafe { Hacl_HMAC_DRBG_max_personalization_string_length };
+        let personalization_len = check_len(personalization, max_personalization_len)?;
+
+        // Draw the entropy input and the nonce
+        let entropy_len = unsafe { Hacl_HMAC_DRBG_min_length(hash.to_spec()) } as usize;
+        let mut seed = SecretArray::<{ MAX_ENTROPY_LEN + MAX_ENTROPY_LEN / 2 }>::default();
+        let (entropy_input, nonce) = seed[..entropy_len + entropy_len / 2].split_at_mut(entropy_len);
+        entropy.try_fill_bytes(entropy_input).map_err(|_| Error::RngFailure)?;
+        entropy.try_fill_bytes(nonce).map_err(|_| Error::RngFailure)?;
+
+        // Allocate and instantiate the state
+        let state = unsafe { Hacl_HMAC_DRBG_create_in(hash.to_spec()) };
+        if state.k.is_null() || state.v.is_null() {
+            handle_alloc_error(Layout::array::<u8>(hash.digest_len()).expect("Invalid digest length"));
+        }
+        let this = Self { hash, state, entropy };
+        unsafe {
+            Hacl_HMAC_DRBG_instantiate(
+                hash.to_spec(),
+                this.state,
+                entropy_input.len() as u32,
+                entropy_input.as_mut_ptr(),
+                nonce.len() as u32,
+                nonce.as_mut_ptr(),
+                personalization_len,
+                personalization.as_ptr().cast_mut(),
+            )
+        };
+        Ok(this)
+    }
+
+    /// The hash function
+    pub fn hash(&self) -> HashAlgorithm {
+        self.hash
+    }
+
+    /// Reseeds the DRBG with fresh entropy from the entropy source and `additional_input`
+    pub fn reseed(&mut self, additional_input: &[u8]) -> Result<(), Error> {
+        // Validate the parameters
+        let max_additional_input_len = unsafe { Hacl_HMAC_DRBG_max_additional_input_length };
+        let additional_input_len = check_len(additional_input, max_additional_input_len)?;
+
+        // Draw the entropy input and reseed the state
+        let mut entropy_input = SecretArray::<MAX_ENTROPY_LEN>::default();
+        let entropy_input = &mut entropy_input[..unsafe { Hacl_HMAC_DRBG_min_length(self.hash.to_spec()) } as usize];
+        self.entropy.try_fill_bytes(entropy_input).map_err(|_| Error::RngFailure)?;
+        unsafe {
+            Hacl_HMAC_DRBG_reseed(
+                self.hash.to_spec(),
+                self.state,
+                entropy_input.len() as u32,
+                entropy_input.as_mut_ptr(),
+                additional_input_len,
+                additional_input.as_ptr().cast_mut(),
+            )
+        };
+        Ok(())
+    }
+
+    /// Fills `output` with random bytes and mixes `additional_input` into every request
+    ///
+    /// Outputs that exceed the maximum request length are split into several requests, and the DRBG reseeds itself
+    /// from the entropy source whenever the reseed interval is exhausted.
+    pub fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), Error> {
+        // Validate the parameters
+        let max_additional_input_len = unsafe { Hacl_HMAC_DRBG_max_additional_input_length };
+        check_len(additional_input, max_additional_input_len)?;
+
+        // Generate the output in chunks of the maximum request length
+        let max_output_len = unsafe { Hacl_HMAC_DRBG_max_output_length } as usize;
+        for chunk in output.chunks_mut(max_output_len) {
+            if !self.generate_chunk(chunk, additional_input) {
+                // The reseed interval is exhausted, so the additional input is absorbed by the reseed instead
+                self.reseed(additional_input)?;
+                if !self.generate_chunk(chunk, &[]) {
+                    return Err(Error::OperationFailed);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Performs a single HMAC-DRBG request and returns `false` if a reseed is required
+    fn generate_chunk(&mut self, chunk: &mut [u8], additional_input: &[u8]) -> bool {
+        unsafe {
+            Hacl_HMAC_DRBG_generate(
+                self.hash.to_spec(),
+                chunk.as_mut_ptr(),
+                self.state,
+                chunk.len() as u32,
+                additional_input.len() as u32,
+                additional_input.as_ptr().cast_mut(),
+            )
+        }
+    }
+}
+impl<E> Debug for HmacDrbg<E> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("HmacDrbg").field("hash", &self.hash).finish_non_exhaustive()
+    }
+}
+impl<E> Drop for HmacDrbg<E> {
+    fn drop(&mut self) {
+        // Erase the key and the chaining value before releasing them
+        unsafe {
+            Lib_Memzero0_memzero(self.state.k.cast(), self.hash.digest_len() as u64);
+            Lib_Memzero0_memzero(self.state.v.cast(), self.hash.digest_len() as u64);
+            free(self.state.k.cast());
+            free(self.state.v.cast());
+            free(self.state.reseed_counter.cast());
+        }
+    }
+}
+impl<E> RngCore for HmacDrbg<E>
+where
+    E: RngCore + CryptoRng,
+{
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.generate(dest, &[]).expect("Failed to generate random bytes");
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.generate(dest, &[]).map_err(rng_error)
+    }
+}
+impl<E> CryptoRng for HmacDrbg<E> where E: RngCore + CryptoRng {}
+// The state is exclusively owned and only mutated through `&mut self`
+unsafe impl<E> Send for HmacDrbg<E> where E: Send {}
+unsafe impl<E> Sync for HmacDrbg<E> where E: Sync {}
+
+/// An HMAC-DRBG that is seeded from the operating system and reseeds itself on every request
+pub struct EverCryptDrbg {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The state which is allocated by `EverCrypt_DRBG_create`
+    state: NonNull<EverCrypt_DRBG_state_s>,
+}
+impl EverCryptDrbg {
+    /// Creates a new DRBG with `hash` that is bound to `personalization`
+    ///
+    /// Supported hash functions are SHA-1, SHA2-256, SHA2-384 and SHA2-512.
+    pub fn new(hash: HashAlgorithm, personalization: &[u8]) -> Result<Self, Error> {
+        // Validate the parameters
+        check_hash(hash)?;
+        let max_personalization_len = unsafe { Hacl_HMAC_DRBG_max_personalization_string_length };
+        let personalization_len = check_len(personalization, max_personalization_len)?;
+
+        // Allocate and instantiate the state
+        let state = unsafe { EverCrypt_DRBG_create(hash.to_spec()) };
+        let state = NonNull::new(state).unwrap_or_else(|| handle_alloc_error(Layout::new::<EverCrypt_DRBG_state_s>()));
+        let this = Self { hash, state };
+        let instantiated = unsafe {
+            EverCrypt_DRBG_instantiate(this.state.as_ptr(), personalization.as_ptr().cast_mut(), personalization_len)
+        };
+        match instantiated {
+            true => Ok(this),
+            false => Err(Error::RngFailure),
+        }
+    }
+
+    /// The hash function
+    pub fn hash(&self) -> HashAlgorithm {
+        self.hash
+    }
+
+    /// Reseeds the DRBG with fresh entropy from the operating system and `additional_input`
+    pub fn reseed(&mut self, additional_input: &[u8]) -> Result<(), Error> {
+        // Validate the parameters
+        let max_additional_input_len = unsafe { Hacl_HMAC_DRBG_max_additional_input_length };
+        let additional_input_len = check_len(additional_input, max_additional_input_len)?;
+
+        // Reseed the state
+        let reseeded = unsafe {
+            EverCrypt_DRBG_reseed(self.state.as_ptr(), additional_input.as_ptr().cast_mut(), additional_input_len)
+        };
+        match reseeded {
+            true => Ok(()),
+            false => Err(Error::RngFailure),
+        }
+    }
+
+    /// Fills `output` with random bytes and mixes `additional_input` into every request
+    ///
+    /// Outputs that exceed the maximum request length are split into several requests.
+    pub fn generate(&mut self, output: &mut [u8], additional_input: &[u8]) -> Result<(), Error> {
+        // Validate the parameters
+        let max_additional_input_len = unsafe { Hacl_HMAC_DRBG_max_additional_input_length };
+        let additional_input_len = check_len(additional_input, max_additional_input_len)?;
+
+        // Generate the output in chunks of the maximum request length
+        let max_output_len = unsafe { Hacl_HMAC_DRBG_max_output_length } as usize;
+        for chunk in output.chunks_mut(max_output_len) {
+            let generated = unsafe {
+                EverCrypt_DRBG_generate(
+                    chunk.as_mut_ptr(),
+                    self.state.as_ptr(),
+                    chunk.len() as u32,
+                    additional_input.as_ptr().cast_mut(),
+                    additional_input_len,
+                )
+            };
+            if !generated {
+                return Err(Error::RngFailure);
+            }
+        }
+        Ok(())
+    }
+}
+impl Debug for EverCryptDrbg {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("EverCryptDrbg").field("hash", &self.hash).finish_non_exhaustive()
+    }
+}
+impl Drop for EverCryptDrbg {
+    fn drop(&mut self) {
+        unsafe { EverCrypt_DRBG_uninstantiate(self.state.as_ptr()) };
+    }
+}
+impl RngCore for EverCryptDrbg {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.generate(dest, &[]).expect("Failed to generate random bytes");
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.generate(dest, &[]).map_err(rng_error)
+    }
+}
+impl CryptoRng for EverCryptDrbg {}
+// The state is exclusively owned and only mutated through `&mut self`
+unsafe impl Send for EverCryptDrbg {}
+unsafe impl Sync for EverCryptDrbg {}
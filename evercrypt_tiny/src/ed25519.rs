@@ -0,0 +1,112 @@
+//! Ed25519 signatures as specified in RFC 8032
+
+use crate::{secret::SecretArray, Error};
+use core::fmt::{self, Debug, Formatter};
+use evercrypt_tiny_sys::{EverCrypt_Ed25519_secret_to_public, EverCrypt_Ed25519_sign, EverCrypt_Ed25519_verify};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The length of a secret key
+pub const SECRET_KEY_LEN: usize = 32;
+/// The length of a public key
+pub const PUBLIC_KEY_LEN: usize = 32;
+/// The length of a signature
+pub const SIGNATURE_LEN: usize = 64;
+
+/// An Ed25519 secret key
+#[derive(Clone)]
+pub struct Ed25519SecretKey {
+    /// The secret seed
+    seed: SecretArray<SECRET_KEY_LEN>,
+}
+impl Ed25519SecretKey {
+    /// Generates a new random secret key
+    pub fn generate<R>(rng: &mut R) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut seed = SecretArray::default();
+        rng.try_fill_bytes(&mut seed[..]).map_err(|_| Error::RngFailure)?;
+        Ok(Self { seed })
+    }
+
+    /// Loads a secret key from its 32 byte seed
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let seed = SecretArray::from_slice(bytes).map_err(|_| Error::InvalidKey)?;
+        Ok(Self { seed })
+    }
+
+    /// Returns the 32 byte seed
+    pub fn to_bytes(&self) -> SecretArray<SECRET_KEY_LEN> {
+        self.seed.clone()
+    }
+
+    /// Computes the public key that belongs to this secret key
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        let (mut seed, mut point) = (self.seed.clone(), [0; PUBLIC_KEY_LEN]);
+        unsafe { EverCrypt_Ed25519_secret_to_public(point.as_mut_ptr(), seed.as_mut_ptr()) };
+        Ed25519PublicKey { point }
+    }
+
+    /// Signs `message`
+    pub fn sign(&self, message: &[u8]) -> Result<[u8; SIGNATURE_LEN], Error> {
+        let message_len = u32::try_from(message.len()).map_err(|_| Error::InvalidLength)?;
+        let (mut seed, mut signature) = (self.seed.clone(), [0; SIGNATURE_LEN]);
+        unsafe {
+            EverCrypt_Ed25519_sign(signature.as_mut_ptr(), seed.as_mut_ptr(), message_len, message.as_ptr().cast_mut())
+        };
+        Ok(signature)
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for Ed25519SecretKey {
+    fn zeroize(&mut self) {
+        self.seed.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Ed25519SecretKey {}
+impl Debug for Ed25519SecretKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Ed25519SecretKey").finish_non_exhaustive()
+    }
+}
+
+/// An Ed25519 public key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ed25519PublicKey {
+    /// The compressed point
+    point: [u8; PUBLIC_KEY_LEN],
+}
+impl Ed25519PublicKey {
+    /// Loads a public key from its 32 byte compressed representation
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let point = bytes.try_into().map_err(|_| Error::InvalidKey)?;
+        Ok(Self { point })
+    }
+
+    /// Returns the 32 byte compressed representation
+    pub fn to_bytes(&self) -> [u8; PUBLIC_KEY_LEN] {
+        self.point
+    }
+
+    /// Verifies a `signature` over `message`
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<(), Error> {
+        let message_len = u32::try_from(message.len()).map_err(|_| Error::VerificationFailed)?;
+        let mut signature: [u8; SIGNATURE_LEN] = signature.try_into().map_err(|_| Error::VerificationFailed)?;
+        let mut point = self.point;
+        let is_valid = unsafe {
+            EverCrypt_Ed25519_verify(
+                point.as_mut_ptr(),
+                message_len,
+                message.as_ptr().cast_mut(),
+                signature.as_mut_ptr(),
+            )
+        };
+        match is_valid {
+            true => Ok(()),
+            false => Err(Error::VerificationFailed),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! HACL*'s system randomness and custom entropy sources for it
+//!
+//! [`SystemRng`] reads directly from HACL*'s system randomness (`Lib_RandomBuffer_System_randombytes`), which also seeds
+//! and reseeds [`EverCryptDrbg`](crate::drbg::EverCryptDrbg). By default, this is the operating system's CSPRNG. With
+//! the `custom-entropy` feature, it is instead the source registered via [`set_entropy_source`], e.g. RDSEED, a
+//! hardware RNG or a fixed source for tests. Until a source is registered, all requests fail with
+//! [`Error::RngFailure`].
+
+use crate::{drbg::rng_error, Error};
+#[cfg(feature = "custom-entropy")]
+use core::{
+    mem,
+    ptr::{self, NonNull},
+    slice,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+#[cfg(feature = "custom-entropy")]
+use evercrypt_tiny_sys::EverCryptTiny_set_entropy_source;
+use evercrypt_tiny_sys::Lib_RandomBuffer_System_randombytes;
+use rand_core::{CryptoRng, RngCore};
+
+/// Fills `buf` from HACL*'s system randomness
+pub fn fill_system_random(buf: &mut [u8]) -> Result<(), Error> {
+    for chunk in buf.chunks_mut(u32::MAX as usize) {
+        let filled = unsafe { Lib_RandomBuffer_System_randombytes(chunk.as_mut_ptr(), chunk.len() as u32) };
+        if !filled {
+            return Err(Error::RngFailure);
+        }
+    }
+    Ok(())
+}
+
+/// HACL*'s system randomness as [`RngCore`] and [`CryptoRng`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SystemRng;
+impl RngCore for SystemRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_system_random(dest).expect("Failed to generate random bytes");
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        fill_system_random(dest).map_err(rng_error)
+    }
+}
+impl CryptoRng for SystemRng {}
+
+/// An entropy source that fills the buffer completely or fails
+#[cfg(feature = "custom-entropy")]
+pub type EntropySource = fn(&mut [u8]) -> Result<(), Error>;
+
+/// The registered entropy source or `NULL`
+#[cfg(feature = "custom-entropy")]
+static ENTROPY_SOURCE: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Registers `source` as process-wide entropy source for HACL*, replacing any previously registered source
+#[cfg(feature = "custom-entropy")]
+pub fn set_entropy_source(source: EntropySource) {
+    ENTROPY_SOURCE.store(source as *mut (), Ordering::Release);
+    EverCryptTiny_set_entropy_source(Some(read_entropy));
+}
+
+/// Removes the registered entropy source, so that all subsequent requests fail
+#[cfg(feature = "custom-entropy")]
+pub fn clear_entropy_source() {
+    EverCryptTiny_set_entropy_source(None);
+    ENTROPY_SOURCE.store(ptr::null_mut(), Ordering::Release);
+}
+
+/// Fills `len` bytes at `buf` from the registered entropy source
+#[cfg(feature = "custom-entropy")]
+unsafe extern "C" fn read_entropy(buf: *mut u8, len: u32) -> bool {
+    let Some(source) = NonNull::new(ENTROPY_SOURCE.load(Ordering::Acquire)) else {
+        return false;
+    };
+
+    // The pointer has been created from an `EntropySource`, and HACL* passes a buffer of `len` bytes
+    let source: EntropySource = unsafe { mem::transmute::<*mut (), EntropySource>(source.as_ptr()) };
+    let buf = unsafe { slice::from_raw_parts_mut(buf, len as usize) };
+    source(buf).is_ok()
+}
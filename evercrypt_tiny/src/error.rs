@@ -0,0 +1,41 @@
+//! The crate's error type
+
+use core::fmt::{self, Display, Formatter};
+
+/// A crate error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// A key is malformed or invalid
+    InvalidKey,
+    /// A value could not be decoded
+    InvalidEncoding,
+    /// An input or output has an invalid length
+    InvalidLength,
+    /// The algorithm is not supported by this operation
+    UnsupportedAlgorithm,
+    /// A signature or tag is invalid
+    VerificationFailed,
+    /// A ciphertext could not be decrypted
+    DecryptionFailed,
+    /// The underlying primitive reported a failure
+    OperationFailed,
+    /// The random number generator failed
+    RngFailure,
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidKey => write!(f, "The key is malformed or invalid"),
+            Self::InvalidEncoding => write!(f, "The value could not be decoded"),
+            Self::InvalidLength => write!(f, "The input or output has an invalid length"),
+            Self::UnsupportedAlgorithm => write!(f, "The algorithm is not supported by this operation"),
+            Self::VerificationFailed => write!(f, "The signature or tag is invalid"),
+            Self::DecryptionFailed => write!(f, "The ciphertext could not be decrypted"),
+            Self::OperationFailed => write!(f, "The underlying primitive reported a failure"),
+            Self::RngFailure => write!(f, "The random number generator failed"),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
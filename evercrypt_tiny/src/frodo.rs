@@ -0,0 +1,836 @@
+//! FrodoKEM key encapsulation with SHAKE128 matrix generation
+//!
+//! [`FrodoKem`] wraps HACL*'s `Hacl_Frodo64`, `Hacl_Frodo640`, `Hacl_Frodo976` and `Hacl_Frodo1344`; the parameter set
+//! is selected at compile time via [`FrodoParams`]. Keys, ciphertexts and shared secrets are arrays of the exact sizes of
+//! their parameter set. [`Frodo64`] is HACL*'s reduced parameter set for testing and offers no security.
+//!
+//! HACL* draws the coins for key generation and encapsulation internally. They are taken from the RNG that is passed to
+//! [`FrodoKem::generate`] and [`FrodoKem::encapsulate`] and handed over to HACL*'s `randombytes_`, so a deterministic RNG
+//! (e.g. the DRBG of NIST's KATs) yields deterministic results.
+//!
+//! Note that HACL* keeps the matrix `A` on the stack, i.e. about 800 KiB for FrodoKEM-640 and 3.5 MiB for
+//! FrodoKEM-1344. With the `std` feature, the calls into HACL* therefore run on a worker thread with a stack of
+//! [`FrodoParams::STACK_SIZE`] bytes, so they work on threads with a small stack as well. Each parameter set has one
+//! worker, which is spawned on first use and kept for the lifetime of the process. Without `std`, the calling thread
+//! must have at least [`FrodoParams::STACK_SIZE`] bytes of free stack.
+
+use crate::{
+    secret::{self, SecretArray},
+    Error,
+};
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use core::{
+    cell::UnsafeCell,
+    hint,
+    sync::atomic::{AtomicU8, Ordering},
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    ptr,
+};
+use evercrypt_tiny_sys::{
+    EverCryptTiny_set_frodo_randombytes, Hacl_Frodo1344_crypto_kem_dec, Hacl_Frodo1344_crypto_kem_enc,
+    Hacl_Frodo1344_crypto_kem_keypair, Hacl_Frodo640_crypto_kem_dec, Hacl_Frodo640_crypto_kem_enc,
+    Hacl_Frodo640_crypto_kem_keypair, Hacl_Frodo64_crypto_kem_dec, Hacl_Frodo64_crypto_kem_enc,
+    Hacl_Frodo64_crypto_kem_keypair, Hacl_Frodo976_crypto_kem_dec, Hacl_Frodo976_crypto_kem_enc,
+    Hacl_Frodo976_crypto_kem_keypair,
+};
+use kem::{Decapsulate, Encapsulate};
+use rand_core::{CryptoRng, CryptoRngCore, RngCore};
+#[cfg(feature = "std")]
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        mpsc::{self, Sender},
+        Mutex, PoisonError,
+    },
+    thread, thread_local,
+};
+use subtle::{Choice, ConstantTimeEq};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Seals the parameter set and array traits, since the unsafe calls into HACL* rely on their sizes
+mod sealed {
+    /// The sealing trait
+    pub trait Sealed {}
+}
+
+/// A byte array of the size of a key, ciphertext or shared secret of a parameter set
+pub trait ByteArray: AsRef<[u8]> + AsMut<[u8]> + Clone + Send + Sync + sealed::Sealed {
+    /// The length in bytes
+    const LEN: usize;
+
+    /// Creates an all-zero array
+    fn zeroed() -> Self;
+}
+impl<const N: usize> sealed::Sealed for [u8; N] {}
+impl<const N: usize> ByteArray for [u8; N] {
+    const LEN: usize = N;
+
+    fn zeroed() -> Self {
+        [0; N]
+    }
+}
+impl<const N: usize> sealed::Sealed for SecretArray<N> {}
+impl<const N: usize> ByteArray for SecretArray<N> {
+    const LEN: usize = N;
+
+    fn zeroed() -> Self {
+        SecretArray::default()
+    }
+}
+
+/// A FrodoKEM parameter set
+pub trait FrodoParams: sealed::Sealed {
+    /// The algorithm name, e.g. `FrodoKEM-640-SHAKE`
+    const NAME: &'static str;
+    /// The amount of coins drawn by the key generation, i.e. `s || seedSE || z`
+    const KEYPAIR_COINS: usize;
+    /// The amount of coins drawn by the encapsulation, i.e. `mu`
+    const ENCAPSULATE_COINS: usize;
+    /// The stack size that the calls into HACL* need, which is dominated by the matrix `A`
+    const STACK_SIZE: usize;
+
+    /// The public key
+    type PublicKey: ByteArray;
+    /// The secret key, i.e. `s || pk || S^T || pkh`
+    type SecretKey: ByteArray;
+    /// The ciphertext
+    type Ciphertext: ByteArray;
+    /// The shared secret
+    type SharedSecret: ByteArray;
+
+    /// HACL*'s `crypto_kem_keypair(pk, sk)`
+    const KEYPAIR: unsafe extern "C" fn(*mut u8, *mut u8) -> u32;
+    /// HACL*'s `crypto_kem_enc(ct, ss, pk)`
+    const ENCAPSULATE: unsafe extern "C" fn(*mut u8, *mut u8, *mut u8) -> u32;
+    /// HACL*'s `crypto_kem_dec(ss, ct, sk)`
+    const DECAPSULATE: unsafe extern "C" fn(*mut u8, *mut u8, *mut u8) -> u32;
+}
+
+/// HACL*'s FrodoKEM-64 test parameter set as [`FrodoParams`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Frodo64;
+impl sealed::Sealed for Frodo64 {}
+impl FrodoParams for Frodo64 {
+    const NAME: &'static str = "FrodoKEM-64-SHAKE";
+    const KEYPAIR_COINS: usize = 48;
+    const ENCAPSULATE_COINS: usize = 16;
+    const STACK_SIZE: usize = 256 * 1024;
+    type PublicKey = [u8; 976];
+    type SecretKey = SecretArray<2032>;
+    type Ciphertext = [u8; 1080];
+    type SharedSecret = SecretArray<16>;
+    const KEYPAIR: unsafe extern "C" fn(*mut u8, *mut u8) -> u32 = Hacl_Frodo64_crypto_kem_keypair;
+    const ENCAPSULATE: unsafe extern "C" fn(*mut u8, *mut u8, *mut u8) -> u32 = Hacl_Frodo64_crypto_kem_enc;
+    const DECAPSULATE: unsafe extern "C" fn(*mut u8, *mut u8, *mut u8) -> u32 = Hacl_Frodo64_crypto_kem_dec;
+}
+
+/// FrodoKEM-640-SHAKE as [`FrodoParams`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Frodo640;
+impl sealed::Sealed for Frodo640 {}
+impl FrodoParams for Frodo640 {
+    const NAME: &'static str = "FrodoKEM-640-SHAKE";
+    const KEYPAIR_COINS: usize = 48;
+    const ENCAPSULATE_COINS: usize = 16;
+    const STACK_SIZE: usize = 2 * 1024 * 1024;
+    type PublicKey = [u8; 9616];
+    type SecretKey = SecretArray<19888>;
+    type Ciphertext = [u8; 9720];
+    type SharedSecret = SecretArray<16>;
+    const KEYPAIR: unsafe extern "C" fn(*mut u8, *mut u8) -> u32 = Hacl_Frodo640_crypto_kem_keypair;
+    const ENCAPSULATE: unsafe extern "C" fn(*mut u8, *mut u8, *mut u8) -> u32 = Hacl_Frodo640_crypto_kem_enc;
+    const DECAPSULATE: unsafe extern "C" fn(*mut u8, *mut u8, *mut u8) -> u32 = Hacl_Frodo640_crypto_kem_dec;
+}
+
+/// FrodoKEM-976-SHAKE as [`FrodoParams`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Frodo976;
+impl sealed::Sealed for Frodo976 {}
+impl FrodoParams for Frodo976 {
+    const NAME: &'static str = "FrodoKEM-976-SHAKE";
+    const KEYPAIR_COINS: usize = 64;
+    const ENCAPSULATE_COINS: usize = 24;
+    const STACK_SIZE: usize = 4 * 1024 * 1024;
+    type PublicKey = [u8; 15632];
+    type SecretKey = SecretArray<31296>;
+    type Ciphertext = [u8; 15744];
+    type SharedSecret = SecretArray<24>;
+    const KEYPAIR: unsafe extern "C" fn(*mut u8, *mut u8) -> u32 = Hacl_Frodo976_crypto_kem_keypair;
+    const ENCAPSULATE: unsafe extern "C" fn(*mut u8, *mut u8, *mut u8) -> u32 = Hacl_Frodo976_crypto_kem_enc;
+    const DECAPSULATE: unsafe extern "C" fn(*mut u8, *mut u8, *mut u8) -> u32 = Hacl_Frodo976_crypto_kem_dec;
+}
+
+/// FrodoKEM-1344-SHAKE as [`FrodoParams`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Frodo1344;
+impl sealed::Sealed for Frodo1344 {}
+impl FrodoParams for Frodo1344 {
+    const NAME: &'static str = "FrodoKEM-1344-SHAKE";
+    const KEYPAIR_COINS: usize = 80;
+    const ENCAPSULATE_COINS: usize = 32;
+    const STACK_SIZE: usize = 8 * 1024 * 1024;
+    type PublicKey = [u8; 21520];
+    type SecretKey = SecretArray<43088>;
+    type Ciphertext = [u8; 21632];
+    type SharedSecret = SecretArray<32>;
+    const KEYPAIR: unsafe extern "C" fn(*mut u8, *mut u8) -> u32 = Hacl_Frodo1344_crypto_kem_keypair;
+    const ENCAPSULATE: unsafe extern "C" fn(*mut u8, *mut u8, *mut u8) -> u32 = Hacl_Frodo1344_crypto_kem_enc;
+    const DECAPSULATE: unsafe extern "C" fn(*mut u8, *mut u8, *mut u8) -> u32 = Hacl_Frodo1344_crypto_kem_dec;
+}
+
+/// FrodoKEM-64 (for testing only)
+pub type FrodoKem64 = FrodoKem<Frodo64>;
+/// FrodoKEM-640-SHAKE
+pub type FrodoKem640 = FrodoKem<Frodo640>;
+/// FrodoKEM-976-SHAKE
+pub type FrodoKem976 = FrodoKem<Frodo976>;
+/// FrodoKEM-1344-SHAKE
+pub type FrodoKem1344 = FrodoKem<Frodo1344>;
+
+/// The FrodoKEM operations of a parameter set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrodoKem<P> {
+    /// The parameter set
+    _params: PhantomData<P>,
+}
+impl<P> FrodoKem<P>
+where
+    P: FrodoParams,
+{
+    /// Generates a new key pair
+    ///
+    /// # Stack
+    /// With the `std` feature, HACL* runs on a worker thread with a stack of [`FrodoParams::STACK_SIZE`] bytes.
+    /// Without it, the calling thread must have at least [`FrodoParams::STACK_SIZE`] bytes of free stack.
+    ///
+    /// # Panics
+    /// With the `std` feature, this function panics if the worker thread cannot be spawned.
+    pub fn generate<R>(rng: &mut R) -> Result<(FrodoSecretKey<P>, FrodoPublicKey<P>), Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut public_key = Box::new(P::PublicKey::zeroed());
+        let mut secret_key = Box::new(P::SecretKey::zeroed());
+        let coins = draw_coins(rng, P::KEYPAIR_COINS)?;
+        let result = with_stack(P::NAME, P::STACK_SIZE, || {
+            with_coins(&coins[..P::KEYPAIR_COINS], || unsafe {
+                (P::KEYPAIR)((*public_key).as_mut().as_mut_ptr(), (*secret_key).as_mut().as_mut_ptr())
+            })
+        })?;
+
+        match result {
+            0 => Ok((FrodoSecretKey { bytes: secret_key }, FrodoPublicKey { bytes: public_key })),
+            _ => Err(Error::OperationFailed),
+        }
+    }
+
+    /// Encapsulates a fresh shared secret to `public_key` and returns the ciphertext and the shared secret
+    ///
+    /// # Stack
+    /// With the `std` feature, HACL* runs on a worker thread with a stack of [`FrodoParams::STACK_SIZE`] bytes.
+    /// Without it, the calling thread must have at least [`FrodoParams::STACK_SIZE`] bytes of free stack.
+    ///
+    /// # Panics
+    /// With the `std` feature, this function panics if the worker thread cannot be spawned.
+    pub fn encapsulate<R>(
+        public_key: &FrodoPublicKey<P>,
+        rng: &mut R,
+    ) -> Result<(FrodoCiphertext<P>, FrodoSharedSecret<P>), Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut ciphertext = Box::new(P::Ciphertext::zeroed());
+        let mut shared_secret = P::SharedSecret::zeroed();
+        let coins = draw_coins(rng, P::ENCAPSULATE_COINS)?;
+        let result = with_stack(P::NAME, P::STACK_SIZE, || {
+            with_coins(&coins[..P::ENCAPSULATE_COINS], || unsafe {
+                (P::ENCAPSULATE)(
+                    (*ciphertext).as_mut().as_mut_ptr(),
+                    shared_secret.as_mut().as_mut_ptr(),
+                    (*public_key.bytes).as_ref().as_ptr().cast_mut(),
+                )
+            })
+        })?;
+
+        match result {
+            0 => Ok((FrodoCiphertext { bytes: ciphertext }, FrodoSharedSecret { bytes: shared_secret })),
+            _ => Err(Error::OperationFailed),
+        }
+    }
+
+    /// Decapsulates the shared secret from `ciphertext`
+    ///
+    /// FrodoKEM rejects implicitly: a ciphertext that has not been created for `secret_key` yields a pseudorandom shared
+    /// secret instead of an error.
+    ///
+    /// # Stack
+    /// With the `std` feature, HACL* runs on a worker thread with a stack of [`FrodoParams::STACK_SIZE`] bytes.
+    /// Without it, the calling thread must have at least [`FrodoParams::STACK_SIZE`] bytes of free stack.
+    ///
+    /// # Panics
+    /// With the `std` feature, this function panics if the worker thread cannot be spawned.
+    pub fn decapsulate(
+        secret_key: &FrodoSecretKey<P>,
+        ciphertext: &FrodoCiphertext<P>,
+    ) -> Result<FrodoSharedSecret<P>, Error> {
+        let mut shared_secret = P::SharedSecret::zeroed();
+        let result = with_stack(P::NAME, P::STACK_SIZE, || unsafe {
+            (P::DECAPSULATE)(
+                shared_secret.as_mut().as_mut_ptr(),
+                (*ciphertext.bytes).as_ref().as_ptr().cast_mut(),
+                (*secret_key.bytes).as_ref().as_ptr().cast_mut(),
+            )
+        });
+
+        match result {
+            0 => Ok(FrodoSharedSecret { bytes: shared_secret }),
+            _ => Err(Error::OperationFailed),
+        }
+    }
+}
+
+/// A call that is performed by a worker thread
+#[cfg(feature = "std")]
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The job queues of the worker threads by parameter set
+#[cfg(feature = "std")]
+static WORKERS: Mutex<BTreeMap<&'static str, Sender<Job>>> = Mutex::new(BTreeMap::new());
+
+/// Performs `call` on the worker thread of the parameter set `name`, which has a stack of `stack_size` bytes
+///
+/// The worker is spawned on first use and serves all later calls of its parameter set, so a call does not pay for a
+/// thread with a stack of up to 8 MiB.
+#[cfg(feature = "std")]
+fn with_stack<F, T>(name: &'static str, stack_size: usize, call: F) -> T
+where
+    F: FnOnce() -> T + Send,
+    T: Send,
+{
+    // The job reports its result or panic, so the worker survives panics
+    let (result_tx, result_rx) = mpsc::sync_channel(1);
+    let job: Box<dyn FnOnce() + Send + '_> = Box::new(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(call));
+        _ = result_tx.send(result);
+    });
+
+    // Safety: the job may borrow from the caller, who blocks below until the job has been performed or dropped. The
+    // result channel disconnects only after the job has been dropped.
+    let job: Job = unsafe { mem::transmute::<Box<dyn FnOnce() + Send + '_>, Job>(job) };
+    worker(name, stack_size).send(job).expect("FrodoKEM worker has terminated");
+    match result_rx.recv().expect("FrodoKEM worker has terminated") {
+        Ok(result) => result,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+/// Returns the job queue of the worker thread of the parameter set `name` and spawns the worker if necessary
+#[cfg(feature = "std")]
+fn worker(name: &'static str, stack_size: usize) -> Sender<Job> {
+    let mut workers = WORKERS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(worker) = workers.get(name) {
+        return worker.clone();
+    }
+
+    // Spawn a detached worker that performs the jobs one after another
+    let (job_tx, job_rx) = mpsc::channel::<Job>();
+    let thread = thread::Builder::new().name("frodokem".into()).stack_size(stack_size);
+    thread.spawn(move || job_rx.into_iter().for_each(|job| job())).expect("Failed to spawn FrodoKEM thread");
+    workers.insert(name, job_tx.clone());
+    job_tx
+}
+/// Performs `call` on the calling thread, which must have at least `stack_size` bytes of free stack
+#[cfg(not(feature = "std"))]
+fn with_stack<F, T>(_name: &'static str, _stack_size: usize, call: F) -> T
+where
+    F: FnOnce() -> T + Send,
+    T: Send,
+{
+    call()
+}
+
+/// A FrodoKEM secret key
+pub struct FrodoSecretKey<P>
+where
+    P: FrodoParams,
+{
+    /// The encoded secret key
+    bytes: Box<P::SecretKey>,
+}
+impl<P> FrodoSecretKey<P>
+where
+    P: FrodoParams,
+{
+    /// Loads a secret key from its encoding
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != P::SecretKey::LEN {
+            return Err(Error::InvalidKey);
+        }
+
+        let mut secret_key = Box::new(P::SecretKey::zeroed());
+        (*secret_key).as_mut().copy_from_slice(bytes);
+        Ok(Self { bytes: secret_key })
+    }
+
+    /// The encoded secret key
+    pub fn as_bytes(&self) -> &P::SecretKey {
+        &self.bytes
+    }
+
+    /// The public key that is embedded in the secret key after the rejection secret `s`
+    pub fn public_key(&self) -> FrodoPublicKey<P> {
+        let offset = P::SharedSecret::LEN;
+        let mut public_key = Box::new(P::PublicKey::zeroed());
+        (*public_key).as_mut().copy_from_slice(&(*self.bytes).as_ref()[offset..][..P::PublicKey::LEN]);
+        FrodoPublicKey { bytes: public_key }
+    }
+}
+impl<P> Decapsulate<FrodoCiphertext<P>, FrodoSharedSecret<P>> for FrodoSecretKey<P>
+where
+    P: FrodoParams,
+{
+    type Error = Error;
+
+    fn decapsulate(&self, encapsulated_key: &FrodoCiphertext<P>) -> Result<FrodoSharedSecret<P>, Self::Error> {
+        FrodoKem::decapsulate(self, encapsulated_key)
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<P> Zeroize for FrodoSecretKey<P>
+where
+    P: FrodoParams,
+{
+    fn zeroize(&mut self) {
+        secret::memzero((*self.bytes).as_mut());
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<P> ZeroizeOnDrop for FrodoSecretKey<P> where P: FrodoParams {}
+impl<P> Clone for FrodoSecretKey<P>
+where
+    P: FrodoParams,
+{
+    fn clone(&self) -> Self {
+        Self { bytes: self.bytes.clone() }
+    }
+}
+impl<P> Debug for FrodoSecretKey<P>
+where
+    P: FrodoParams,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("FrodoSecretKey").field("params", &P::NAME).finish_non_exhaustive()
+    }
+}
+
+/// A FrodoKEM public key
+pub struct FrodoPublicKey<P>
+where
+    P: FrodoParams,
+{
+    /// The encoded public key, i.e. `seedA || b`
+    bytes: Box<P::PublicKey>,
+}
+impl<P> FrodoPublicKey<P>
+where
+    P: FrodoParams,
+{
+    /// Loads a public key from its encoding
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != P::PublicKey::LEN {
+            return Err(Error::InvalidKey);
+        }
+
+        let mut public_key = Box::new(P::PublicKey::zeroed());
+        (*public_key).as_mut().copy_from_slice(bytes);
+        Ok(Self { bytes: public_key })
+    }
+
+    /// The encoded public key
+    pub fn as_bytes(&self) -> &P::PublicKey {
+        &self.bytes
+    }
+}
+impl<P> Encapsulate<FrodoCiphertext<P>, FrodoSharedSecret<P>> for FrodoPublicKey<P>
+where
+    P: FrodoParams,
+{
+    type Error = Error;
+
+    fn encapsulate(
+        &self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<(FrodoCiphertext<P>, FrodoSharedSecret<P>), Self::Error> {
+        FrodoKem::encapsulate(self, rng)
+    }
+}
+impl<P> PartialEq for FrodoPublicKey<P>
+where
+    P: FrodoParams,
+{
+    fn eq(&self, other: &Self) -> bool {
+        (*self.bytes).as_ref() == (*other.bytes).as_ref()
+    }
+}
+impl<P> Eq for FrodoPublicKey<P> where P: FrodoParams {}
+impl<P> Clone for FrodoPublicKey<P>
+where
+    P: FrodoParams,
+{
+    fn clone(&self) -> Self {
+        Self { bytes: self.bytes.clone() }
+    }
+}
+impl<P> Debug for FrodoPublicKey<P>
+where
+    P: FrodoParams,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("FrodoPublicKey").field("params", &P::NAME).finish_non_exhaustive()
+    }
+}
+
+/// A FrodoKEM ciphertext
+pub struct FrodoCiphertext<P>
+where
+    P: FrodoParams,
+{
+    /// The encoded ciphertext, i.e. `c1 || c2`
+    bytes: Box<P::Ciphertext>,
+}
+impl<P> FrodoCiphertext<P>
+where
+    P: FrodoParams,
+{
+    /// Loads a ciphertext from its encoding
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != P::Ciphertext::LEN {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut ciphertext = Box::new(P::Ciphertext::zeroed());
+        (*ciphertext).as_mut().copy_from_slice(bytes);
+        Ok(Self { bytes: ciphertext })
+    }
+
+    /// The encoded ciphertext
+    pub fn as_bytes(&self) -> &P::Ciphertext {
+        &self.bytes
+    }
+}
+impl<P> PartialEq for FrodoCiphertext<P>
+where
+    P: FrodoParams,
+{
+    fn eq(&self, other: &Self) -> bool {
+        (*self.bytes).as_ref() == (*other.bytes).as_ref()
+    }
+}
+impl<P> Eq for FrodoCiphertext<P> where P: FrodoParams {}
+impl<P> Clone for FrodoCiphertext<P>
+where
+    P: FrodoParams,
+{
+    fn clone(&self) -> Self {
+        Self { bytes: self.bytes.clone() }
+    }
+}
+impl<P> Debug for FrodoCiphertext<P>
+where
+    P: FrodoParams,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("FrodoCiphertext").field("params", &P::NAME).finish_non_exhaustive()
+    }
+}
+
+/// A FrodoKEM shared secret
+pub struct FrodoSharedSecret<P>
+where
+    P: FrodoParams,
+{
+    /// The shared secret
+    bytes: P::SharedSecret,
+}
+impl<P> FrodoSharedSecret<P>
+where
+    P: FrodoParams,
+{
+    /// The shared secret
+    pub fn as_bytes(&self) -> &P::SharedSecret {
+        &self.bytes
+    }
+}
+impl<P> ConstantTimeEq for FrodoSharedSecret<P>
+where
+    P: FrodoParams,
+{
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.bytes.as_ref().ct_eq(other.bytes.as_ref())
+    }
+}
+impl<P> PartialEq for FrodoSharedSecret<P>
+where
+    P: FrodoParams,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+impl<P> Eq for FrodoSharedSecret<P> where P: FrodoParams {}
+#[cfg(feature = "zeroize")]
+impl<P> Zeroize for FrodoSharedSecret<P>
+where
+    P: FrodoParams,
+{
+    fn zeroize(&mut self) {
+        secret::memzero(self.bytes.as_mut());
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<P> ZeroizeOnDrop for FrodoSharedSecret<P> where P: FrodoParams {}
+impl<P> Clone for FrodoSharedSecret<P>
+where
+    P: FrodoParams,
+{
+    fn clone(&self) -> Self {
+        Self { bytes: self.bytes.clone() }
+    }
+}
+impl<P> Debug for FrodoSharedSecret<P>
+where
+    P: FrodoParams,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("FrodoSharedSecret").field("params", &P::NAME).finish_non_exhaustive()
+    }
+}
+
+/// The largest amount of coins drawn by any parameter set
+const MAX_COINS: usize = 80;
+
+/// Draws `len` coins from `rng`
+///
+/// The coins are drawn before they are armed, since the RNG may be slow.
+fn draw_coins<R>(rng: &mut R, len: usize) -> Result<SecretArray<MAX_COINS>, Error>
+where
+    R: RngCore + CryptoRng,
+{
+    let mut coins = SecretArray::default();
+    rng.try_fill_bytes(&mut coins[..len]).map_err(|_| Error::RngFailure)?;
+    Ok(coins)
+}
+
+/// Arms `coins` and performs `call`, which must draw exactly `coins.len()` coins once
+fn with_coins<F, T>(coins: &[u8], call: F) -> Result<T, Error>
+where
+    F: FnOnce() -> T,
+{
+    // All keypair and encapsulation functions call `randombytes_` exactly once
+    let slot = arm(coins);
+    let result = call();
+    match slot.is_taken() {
+        true => Ok(result),
+        false => Err(Error::OperationFailed),
+    }
+}
+
+/// Copies the `armed_len` armed `coins` into the `len` bytes at `buf` if the lengths match and erases them
+///
+/// # Safety
+/// `buf` must be valid for `len` bytes.
+unsafe fn hand_over(coins: &mut [u8; MAX_COINS], armed_len: usize, buf: *mut u8, len: u32) -> bool {
+    let is_ok = armed_len == len as usize;
+    if is_ok {
+        unsafe { ptr::copy_nonoverlapping(coins.as_ptr(), buf, armed_len) };
+    }
+    secret::memzero(coins);
+    is_ok
+}
+
+/// The slot is free
+const IDLE: u8 = 0;
+/// The slot is being written or read
+#[cfg(not(feature = "std"))]
+const BUSY: u8 = 1;
+/// The slot holds the coins for the next call of `randombytes_`
+const ARMED: u8 = 2;
+/// The coins have been taken by a request of the expected length
+const TAKEN: u8 = 3;
+/// The coins have been requested with an unexpected length or more than once
+const FAILED: u8 = 4;
+
+/// A slot for the coins of a single FrodoKEM call on the current thread
+///
+/// The coins are armed on the thread that calls into HACL*, so concurrent calls do not wait for each other, and calls
+/// into HACL* on other threads cannot take them but fall back to the system randomness. Every request for coins while
+/// the slot is armed must match the armed length and may only happen once; otherwise the slot is marked as failed and
+/// the call fails.
+#[cfg(feature = "std")]
+struct CoinSlot {
+    /// The state of the slot
+    state: Cell<u8>,
+    /// The amount of armed coins
+    len: Cell<usize>,
+    /// The armed coins
+    coins: RefCell<[u8; MAX_COINS]>,
+}
+#[cfg(feature = "std")]
+thread_local! {
+    /// The coins that are handed over from the caller's RNG to HACL*'s `randombytes_` on this thread
+    static COINS: CoinSlot =
+        const { CoinSlot { state: Cell::new(IDLE), len: Cell::new(0), coins: RefCell::new([0; MAX_COINS]) } };
+}
+
+/// Arms `coins` in the slot of the current thread
+#[cfg(feature = "std")]
+fn arm(coins: &[u8]) -> ArmedSlot {
+    COINS.with(|slot| {
+        slot.len.set(coins.len());
+        slot.coins.borrow_mut()[..coins.len()].copy_from_slice(coins);
+        slot.state.set(ARMED);
+    });
+    EverCryptTiny_set_frodo_randombytes(Some(take_coins));
+    ArmedSlot { _thread: PhantomData }
+}
+
+/// The armed [`CoinSlot`] of the current thread, which erases the coins and releases the slot on drop
+#[cfg(feature = "std")]
+struct ArmedSlot {
+    /// Binds the guard to the thread of the slot
+    _thread: PhantomData<*const ()>,
+}
+#[cfg(feature = "std")]
+impl ArmedSlot {
+    /// Whether the coins have been taken exactly once with the armed length
+    fn is_taken(&self) -> bool {
+        COINS.with(|slot| slot.state.get() == TAKEN)
+    }
+}
+#[cfg(feature = "std")]
+impl Drop for ArmedSlot {
+    fn drop(&mut self) {
+        COINS.with(|slot| {
+            secret::memzero(&mut *slot.coins.borrow_mut());
+            slot.state.set(IDLE);
+        });
+    }
+}
+
+/// Takes `len` coins that are armed on the current thread into `buf`
+#[cfg(feature = "std")]
+unsafe extern "C" fn take_coins(buf: *mut u8, len: u32) -> bool {
+    let take = |slot: &CoinSlot| {
+        if slot.state.get() != ARMED {
+            // A second request while the slot is armed means that the coins cannot be attributed to a single call
+            if slot.state.get() == TAKEN {
+                slot.state.set(FAILED);
+            }
+            return false;
+        }
+
+        // HACL* passes a buffer of `len` bytes
+        let Ok(mut coins) = slot.coins.try_borrow_mut() else { return false };
+        let is_ok = unsafe { hand_over(&mut coins, slot.len.get(), buf, len) };
+        slot.state.set(if is_ok { TAKEN } else { FAILED });
+        is_ok
+    };
+    COINS.try_with(take).unwrap_or(false)
+}
+
+/// A global slot for the coins of a single FrodoKEM call
+///
+/// Without `std`, there are no thread-locals, so all calls share this slot: it is reserved for the whole call, and
+/// concurrent calls spin until it is released, without any fairness. A call into HACL* that bypasses this module on
+/// another thread while the slot is armed may take the coins, which makes the armed call fail. Every request for coins
+/// while the slot is reserved must match the armed length and may only happen once; otherwise the slot is marked as
+/// failed and the call fails. Calls into HACL* that bypass this module while no coins are armed fall back to the
+/// system randomness.
+#[cfg(not(feature = "std"))]
+struct CoinSlot {
+    /// The state of the slot
+    state: AtomicU8,
+    /// The amount of armed coins
+    len: UnsafeCell<usize>,
+    /// The armed coins
+    coins: UnsafeCell<[u8; MAX_COINS]>,
+}
+#[cfg(not(feature = "std"))]
+unsafe impl Sync for CoinSlot {}
+
+/// The coins that are handed over from the caller's RNG to HACL*'s `randombytes_`
+#[cfg(not(feature = "std"))]
+static COINS: CoinSlot =
+    CoinSlot { state: AtomicU8::new(IDLE), len: UnsafeCell::new(0), coins: UnsafeCell::new([0; MAX_COINS]) };
+
+/// Reserves the global slot and arms `coins`
+#[cfg(not(feature = "std"))]
+fn arm(coins: &[u8]) -> ArmedSlot {
+    while COINS.state.compare_exchange_weak(IDLE, BUSY, Ordering::Acquire, Ordering::Relaxed).is_err() {
+        hint::spin_loop();
+    }
+
+    // The slot is reserved
+    unsafe {
+        *COINS.len.get() = coins.len();
+        (&mut *COINS.coins.get())[..coins.len()].copy_from_slice(coins);
+    }
+    EverCryptTiny_set_frodo_randombytes(Some(take_coins));
+    COINS.state.store(ARMED, Ordering::Release);
+    ArmedSlot { _private: () }
+}
+
+/// The reserved global [`CoinSlot`], which erases the coins and releases the slot on drop
+#[cfg(not(feature = "std"))]
+struct ArmedSlot {
+    /// Prevents construction outside of [`arm`]
+    _private: (),
+}
+#[cfg(not(feature = "std"))]
+impl ArmedSlot {
+    /// Whether the coins have been taken exactly once with the armed length
+    fn is_taken(&self) -> bool {
+        COINS.state.load(Ordering::Acquire) == TAKEN
+    }
+}
+#[cfg(not(feature = "std"))]
+impl Drop for ArmedSlot {
+    fn drop(&mut self) {
+        // Wait until no request is reading the coins
+        loop {
+            let state = COINS.state.load(Ordering::Relaxed);
+            if state != BUSY
+                && COINS.state.compare_exchange_weak(state, BUSY, Ordering::Acquire, Ordering::Relaxed).is_ok()
+            {
+                break;
+            }
+            hint::spin_loop();
+        }
+
+        // Erase coins that have not been taken and release the slot
+        secret::memzero(unsafe { &mut *COINS.coins.get() });
+        COINS.state.store(IDLE, Ordering::Release);
+    }
+}
+
+/// Takes `len` armed coins into `buf`
+#[cfg(not(feature = "std"))]
+unsafe extern "C" fn take_coins(buf: *mut u8, len: u32) -> bool {
+    if COINS.state.compare_exchange(ARMED, BUSY, Ordering::Acquire, Ordering::Relaxed).is_err() {
+        // A second request while the slot is reserved means that the coins cannot be attributed to a single call
+        _ = COINS.state.compare_exchange(TAKEN, FAILED, Ordering::Relaxed, Ordering::Relaxed);
+        return false;
+    }
+
+    // The slot is reserved, and HACL* passes a buffer of `len` bytes
+    let is_ok = unsafe { hand_over(&mut *COINS.coins.get(), *COINS.len.get(), buf, len) };
+    COINS.state.store(if is_ok { TAKEN } else { FAILED }, Ordering::Release);
+    is_ok
+}
@@ -0,0 +1,163 @@
+//! Hash algorithm identifiers and streaming hash states
+
+use crate::{secret, Error};
+use alloc::{alloc::handle_alloc_error, vec, vec::Vec};
+use core::{alloc::Layout, ptr::NonNull, slice};
+use evercrypt_tiny_sys::{
+    EverCrypt_Hash_Incremental_create_in, EverCrypt_Hash_Incremental_finish, EverCrypt_Hash_Incremental_free,
+    EverCrypt_Hash_Incremental_init, EverCrypt_Hash_Incremental_update, EverCrypt_Hash_hash,
+    Hacl_Streaming_Functor_state_s___EverCrypt_Hash_state_s____ as StreamingState, Spec_Hash_Definitions_Blake2B,
+    Spec_Hash_Definitions_Blake2S, Spec_Hash_Definitions_MD5, Spec_Hash_Definitions_SHA1,
+    Spec_Hash_Definitions_SHA2_224, Spec_Hash_Definitions_SHA2_256, Spec_Hash_Definitions_SHA2_384,
+    Spec_Hash_Definitions_SHA2_512, Spec_Hash_Definitions_hash_alg,
+};
+
+/// A hash algorithm supported by EverCrypt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HashAlgorithm {
+    /// MD5 (broken, only for legacy protocols)
+    Md5,
+    /// SHA-1 (broken, only for legacy protocols)
+    Sha1,
+    /// SHA2-224
+    Sha2_224,
+    /// SHA2-256
+    Sha2_256,
+    /// SHA2-384
+    Sha2_384,
+    /// SHA2-512
+    Sha2_512,
+    /// BLAKE2s with a 32 byte digest
+    Blake2s,
+    /// BLAKE2b with a 64 byte digest
+    Blake2b,
+}
+impl HashAlgorithm {
+    /// The digest length in bytes
+    pub const fn digest_len(self) -> usize {
+        match self {
+            Self::Md5 => 16,
+            Self::Sha1 => 20,
+            Self::Sha2_224 => 28,
+            Self::Sha2_256 => 32,
+            Self::Sha2_384 => 48,
+            Self::Sha2_512 => 64,
+            Self::Blake2s => 32,
+            Self::Blake2b => 64,
+        }
+    }
+
+    /// The block length in bytes
+    pub const fn block_len(self) -> usize {
+        match self {
+            Self::Md5 | Self::Sha1 | Self::Sha2_224 | Self::Sha2_256 | Self::Blake2s => 64,
+            Self::Sha2_384 | Self::Sha2_512 | Self::Blake2b => 128,
+        }
+    }
+
+    /// Computes the digest of `input`
+    pub(crate) fn digest(self, input: &[u8]) -> Result<Vec<u8>, Error> {
+        let input_len = u32::try_from(input.len()).map_err(|_| Error::InvalidLength)?;
+        let mut digest = vec![0; self.digest_len()];
+        unsafe { EverCrypt_Hash_hash(self.to_spec(), digest.as_mut_ptr(), input.as_ptr().cast_mut(), input_len) };
+        Ok(digest)
+    }
+
+    /// The EverCrypt algorithm identifier
+    pub(crate) const fn to_spec(self) -> Spec_Hash_Definitions_hash_alg {
+        let spec = match self {
+            Self::Md5 => Spec_Hash_Definitions_MD5,
+            Self::Sha1 => Spec_Hash_Definitions_SHA1,
+            Self::Sha2_224 => Spec_Hash_Definitions_SHA2_224,
+            Self::Sha2_256 => Spec_Hash_Definitions_SHA2_256,
+            Self::Sha2_384 => Spec_Hash_Definitions_SHA2_384,
+            Self::Sha2_512 => Spec_Hash_Definitions_SHA2_512,
+            Self::Blake2s => Spec_Hash_Definitions_Blake2S,
+            Self::Blake2b => Spec_Hash_Definitions_Blake2B,
+        };
+        spec as Spec_Hash_Definitions_hash_alg
+    }
+}
+
+/// An incremental hash computation backed by `EverCrypt_Hash_Incremental`
+#[derive(Debug)]
+pub(crate) struct HashState {
+    /// The hash algorithm
+    hash: HashAlgorithm,
+    /// The streaming state allocated by HACL*
+    state: NonNull<StreamingState>,
+    /// Whether the state absorbs key material and must be erased before it is freed
+    secret: bool,
+}
+impl HashState {
+    /// Allocates a new, initialized hash state for `hash`
+    pub fn new(hash: HashAlgorithm) -> Self {
+        Self::with_secret(hash, false)
+    }
+
+    /// Allocates a new, initialized hash state for `hash` that is erased on drop
+    pub fn new_secret(hash: HashAlgorithm) -> Self {
+        Self::with_secret(hash, true)
+    }
+
+    /// Allocates a new, initialized hash state for `hash`
+    fn with_secret(hash: HashAlgorithm, secret: bool) -> Self {
+        let state = unsafe { EverCrypt_Hash_Incremental_create_in(hash.to_spec()) };
+        let state = NonNull::new(state).unwrap_or_else(|| handle_alloc_error(Layout::new::<StreamingState>()));
+        Self { hash, state, secret }
+    }
+
+    /// Resets the state to the empty input
+    pub fn reset(&mut self) {
+        unsafe { EverCrypt_Hash_Incremental_init(self.state.as_ptr()) };
+    }
+
+    /// Absorbs `data`
+    pub fn update(&mut self, data: &[u8]) {
+        // HACL* takes 32 bit lengths, so larger inputs are absorbed in chunks
+        for chunk in data.chunks(u32::MAX as usize) {
+            unsafe {
+                EverCrypt_Hash_Incremental_update(self.state.as_ptr(), chunk.as_ptr().cast_mut(), chunk.len() as u32)
+            };
+        }
+    }
+
+    /// Writes the digest of all absorbed data into `digest`, which must be exactly `digest_len` bytes long
+    ///
+    /// The state is left unchanged, so more data can be absorbed afterwards.
+    pub fn finish_into(&self, digest: &mut [u8]) {
+        assert_eq!(digest.len(), self.hash.digest_len(), "Invalid digest length");
+        unsafe { EverCrypt_Hash_Incremental_finish(self.state.as_ptr(), digest.as_mut_ptr()) };
+    }
+
+    /// Erases the chaining values and the buffered partial block; the state must be reset before it is used again
+    pub fn erase(&mut self) {
+        // The length of the chaining values as allocated by `EverCrypt_Hash_create_in`
+        let state_len = match self.hash {
+            HashAlgorithm::Md5 => 16,
+            HashAlgorithm::Sha1 => 20,
+            HashAlgorithm::Sha2_224 | HashAlgorithm::Sha2_256 => 32,
+            HashAlgorithm::Sha2_384 | HashAlgorithm::Sha2_512 | HashAlgorithm::Blake2s => 64,
+            HashAlgorithm::Blake2b => 128,
+        };
+
+        // All variants of the block state union are pointers to the chaining values
+        unsafe {
+            let state = self.state.as_ref();
+            let block_state = (*state.block_state).val.case_MD5_s.cast::<u8>();
+            secret::memzero(slice::from_raw_parts_mut(block_state, state_len));
+            secret::memzero(slice::from_raw_parts_mut(state.buf, self.hash.block_len()));
+        }
+    }
+}
+impl Drop for HashState {
+    fn drop(&mut self) {
+        if self.secret {
+            self.erase();
+        }
+        unsafe { EverCrypt_Hash_Incremental_free(self.state.as_ptr()) };
+    }
+}
+// The state is exclusively owned and only mutated through `&mut self`
+unsafe impl Send for HashState {}
+unsafe impl Sync for HashState {}
@@ -0,0 +1,145 @@
+//! HKDF as specified in RFC 5869
+//!
+//! Both steps are computed with the streaming [`Hmac`], so multi-part `info` values are absorbed without
+//! concatenating them first.
+
+use crate::{hmac::Hmac, secret::SecretArray, Error, HashAlgorithm};
+use core::fmt::{self, Debug, Formatter};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The maximum amount of HMAC blocks that can be expanded from a PRK
+pub const MAX_OUTPUT_BLOCKS: usize = 255;
+/// The largest digest length of all supported hash functions
+const MAX_DIGEST_LEN: usize = 64;
+
+/// An HKDF instance for a specific hash function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hkdf {
+    /// The hash function
+    hash: HashAlgorithm,
+}
+impl Hkdf {
+    /// Creates a new HKDF instance with `hash`
+    ///
+    /// Supported hash functions are SHA-1, SHA2-256, SHA2-384, SHA2-512, BLAKE2s and BLAKE2b.
+    pub fn new(hash: HashAlgorithm) -> Result<Self, Error> {
+        Hmac::check_hash(hash)?;
+        Ok(Self { hash })
+    }
+
+    /// Creates a new HKDF instance with an already validated `hash`
+    pub(crate) const fn new_unchecked(hash: HashAlgorithm) -> Self {
+        Self { hash }
+    }
+
+    /// The hash function
+    pub const fn hash(&self) -> HashAlgorithm {
+        self.hash
+    }
+
+    /// The maximum output length of [`Prk::expand`] in bytes
+    pub const fn max_output_len(&self) -> usize {
+        MAX_OUTPUT_BLOCKS * self.hash.digest_len()
+    }
+
+    /// Extracts a pseudorandom key from the input keying material `ikm` and an optional `salt`
+    ///
+    /// An empty salt is equivalent to a salt of `HashLen` zero bytes (RFC 5869, section 2.2).
+    pub fn extract(&self, salt: &[u8], ikm: &[u8]) -> Prk {
+        let mut hmac = Hmac::new_unchecked(self.hash, salt);
+        hmac.update(ikm);
+
+        let mut prk = Prk { hash: self.hash, bytes: SecretArray::default() };
+        hmac.finalize_into(&mut prk.bytes[..self.hash.digest_len()]);
+        prk
+    }
+
+    /// Loads an existing pseudorandom key, e.g. one that has been extracted elsewhere
+    ///
+    /// The key must be exactly `HashLen` bytes long.
+    pub fn from_prk(&self, prk: &[u8]) -> Result<Prk, Error> {
+        if prk.len() != self.hash.digest_len() {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut bytes = SecretArray::default();
+        bytes[..prk.len()].copy_from_slice(prk);
+        Ok(Prk { hash: self.hash, bytes })
+    }
+}
+
+/// A pseudorandom key as produced by [`Hkdf::extract`]
+#[derive(Clone)]
+pub struct Prk {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The key, zero-padded to the largest digest length
+    bytes: SecretArray<MAX_DIGEST_LEN>,
+}
+impl Prk {
+    /// The hash function
+    pub const fn hash(&self) -> HashAlgorithm {
+        self.hash
+    }
+
+    /// The raw key bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.hash.digest_len()]
+    }
+
+    /// Expands the key with `info` to fill `okm`
+    ///
+    /// `okm` must not be longer than `255 * HashLen` bytes (RFC 5869, section 2.3).
+    pub fn expand(&self, info: &[u8], okm: &mut [u8]) -> Result<(), Error> {
+        self.expand_multi_info(&[info], okm)
+    }
+
+    /// Expands the key with the concatenation of all `info` parts to fill `okm`
+    ///
+    /// `okm` must not be longer than `255 * HashLen` bytes (RFC 5869, section 2.3).
+    pub fn expand_multi_info(&self, info: &[&[u8]], okm: &mut [u8]) -> Result<(), Error> {
+        // Validate the output length
+        let digest_len = self.hash.digest_len();
+        if okm.len() > MAX_OUTPUT_BLOCKS * digest_len {
+            return Err(Error::InvalidLength);
+        }
+
+        // Compute `T(i) = HMAC(PRK, T(i - 1) || info || i)` and append it to the output
+        let mut hmac = Hmac::new_unchecked(self.hash, self.as_bytes());
+        let mut block = SecretArray::<MAX_DIGEST_LEN>::default();
+        for (index, chunk) in okm.chunks_mut(digest_len).enumerate() {
+            if index > 0 {
+                hmac.reset();
+                hmac.update(&block[..digest_len]);
+            }
+            info.iter().for_each(|part| hmac.update(part));
+            hmac.update(&[index as u8 + 1]);
+
+            hmac.finalize_into(&mut block[..digest_len]);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+        Ok(())
+    }
+
+    /// Expands the key with the concatenation of all `info` parts into a new key of `HashLen` bytes
+    pub(crate) fn expand_prk(&self, info: &[&[u8]]) -> Prk {
+        let mut prk = Prk { hash: self.hash, bytes: SecretArray::default() };
+        self.expand_multi_info(info, &mut prk.bytes[..self.hash.digest_len()])
+            .expect("A single block never exceeds the maximum output length");
+        prk
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for Prk {
+    fn zeroize(&mut self) {
+        self.bytes.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Prk {}
+impl Debug for Prk {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Prk").field("hash", &self.hash).finish_non_exhaustive()
+    }
+}
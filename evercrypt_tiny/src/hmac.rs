@@ -0,0 +1,320 @@
+//! HMAC as specified in RFC 2104
+//!
+//! [`Hmac`] selects the hash function at runtime. The `digest` crate's [`Mac`](digest::Mac) trait requires the
+//! output length as a type, so it is implemented by the statically typed [`TypedHmac`] instead.
+
+use crate::{hash::HashState, secret::SecretArray, Error, HashAlgorithm};
+use alloc::{vec, vec::Vec};
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+use digest::{
+    consts::{U128, U20, U32, U48, U64},
+    crypto_common::KeySizeUser,
+    generic_array::ArrayLength,
+    FixedOutput, FixedOutputReset, InvalidLength, Key, KeyInit, MacMarker, Output, OutputSizeUser, Reset, Update,
+};
+use evercrypt_tiny_sys::{EverCrypt_HMAC_compute, EverCrypt_HMAC_is_supported_alg};
+use subtle::ConstantTimeEq;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The largest block length of all supported hash functions
+const MAX_BLOCK_LEN: usize = 128;
+/// The inner padding byte
+const IPAD: u8 = 0x36;
+/// The outer padding byte
+const OPAD: u8 = 0x5c;
+
+/// An incremental HMAC computation
+pub struct Hmac {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The key, hashed if it is longer than a block and zero-padded to the block length
+    key: SecretArray<MAX_BLOCK_LEN>,
+    /// The inner hash state which has already absorbed the inner padded key
+    inner: HashState,
+}
+impl Hmac {
+    /// Creates a new HMAC computation with `hash` and `key`
+    ///
+    /// Supported hash functions are SHA-1, SHA2-256, SHA2-384, SHA2-512, BLAKE2s and BLAKE2b.
+    pub fn new(hash: HashAlgorithm, key: &[u8]) -> Result<Self, Error> {
+        Self::check_hash(hash)?;
+        Ok(Self::new_unchecked(hash, key))
+    }
+
+    /// Computes the HMAC of `data` with `hash` and `key` in one shot
+    pub fn compute(hash: HashAlgorithm, key: &[u8], data: &[u8]) -> Result<Vec<u8>, Error> {
+        // Validate the parameters
+        Self::check_hash(hash)?;
+        let key_len = u32::try_from(key.len()).map_err(|_| Error::InvalidLength)?;
+        let data_len = u32::try_from(data.len()).map_err(|_| Error::InvalidLength)?;
+
+        // Compute the MAC
+        let mut mac = vec![0; hash.digest_len()];
+        unsafe {
+            EverCrypt_HMAC_compute(
+                hash.to_spec(),
+                mac.as_mut_ptr(),
+                key.as_ptr().cast_mut(),
+                key_len,
+                data.as_ptr().cast_mut(),
+                data_len,
+            )
+        };
+        Ok(mac)
+    }
+
+    /// The hash function
+    pub fn hash(&self) -> HashAlgorithm {
+        self.hash
+    }
+
+    /// Absorbs `data`
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Computes the MAC over all absorbed data
+    pub fn finalize(self) -> Vec<u8> {
+        let mut mac = vec![0; self.hash.digest_len()];
+        self.finalize_into(&mut mac);
+        mac
+    }
+
+    /// Verifies in constant time that `tag` is the MAC over all absorbed data
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        let mac = self.finalize();
+        match bool::from(mac.ct_eq(tag)) {
+            true => Ok(()),
+            false => Err(Error::VerificationFailed),
+        }
+    }
+
+    /// Resets the computation to the empty input while keeping the key
+    pub fn reset(&mut self) {
+        self.inner.reset();
+        self.inner.update(&self.padded_key(IPAD)[..self.hash.block_len()]);
+    }
+
+    /// Ensures that `hash` is supported by EverCrypt's HMAC
+    pub(crate) fn check_hash(hash: HashAlgorithm) -> Result<(), Error> {
+        match unsafe { EverCrypt_HMAC_is_supported_alg(hash.to_spec()) } {
+            true => Ok(()),
+            false => Err(Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// Creates a new HMAC computation with an already validated `hash`
+    pub(crate) fn new_unchecked(hash: HashAlgorithm, key: &[u8]) -> Self {
+        // Hash the key if it exceeds the block length
+        let mut key_block = SecretArray::default();
+        match key.len() > hash.block_len() {
+            true => {
+                let mut state = HashState::new_secret(hash);
+                state.update(key);
+                state.finish_into(&mut key_block[..hash.digest_len()]);
+            }
+            false => key_block[..key.len()].copy_from_slice(key),
+        }
+
+        // Absorb the inner padded key
+        let mut this = Self { hash, key: key_block, inner: HashState::new_secret(hash) };
+        this.inner.update(&this.padded_key(IPAD)[..hash.block_len()]);
+        this
+    }
+
+    /// Returns the key block XORed with `pad`; only the first `block_len` bytes are meaningful
+    fn padded_key(&self, pad: u8) -> SecretArray<MAX_BLOCK_LEN> {
+        let mut padded = self.key.clone();
+        padded.iter_mut().for_each(|byte| *byte ^= pad);
+        padded
+    }
+
+    /// Writes the MAC over all absorbed data into `mac`, which must be exactly `digest_len` bytes long
+    pub(crate) fn finalize_into(&self, mac: &mut [u8]) {
+        // Compute `H((K ^ opad) || H((K ^ ipad) || data))`
+        self.inner.finish_into(mac);
+        let mut outer = HashState::new_secret(self.hash);
+        outer.update(&self.padded_key(OPAD)[..self.hash.block_len()]);
+        outer.update(mac);
+        outer.finish_into(mac);
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for Hmac {
+    fn zeroize(&mut self) {
+        // Leaves a computation with the all-zero key
+        self.key.zeroize();
+        self.inner.erase();
+        self.reset();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Hmac {}
+impl Debug for Hmac {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Hmac").field("hash", &self.hash).finish_non_exhaustive()
+    }
+}
+
+/// A hash function that is known at compile time
+pub trait HmacHash {
+    /// The hash function
+    const HASH: HashAlgorithm;
+    /// The digest length as type
+    type OutputSize: ArrayLength<u8> + 'static;
+    /// The block length as type
+    type BlockSize: ArrayLength<u8> + 'static;
+}
+
+/// SHA-1 as [`HmacHash`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sha1;
+impl HmacHash for Sha1 {
+    const HASH: HashAlgorithm = HashAlgorithm::Sha1;
+    type OutputSize = U20;
+    type BlockSize = U64;
+}
+
+/// SHA2-256 as [`HmacHash`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sha2_256;
+impl HmacHash for Sha2_256 {
+    const HASH: HashAlgorithm = HashAlgorithm::Sha2_256;
+    type OutputSize = U32;
+    type BlockSize = U64;
+}
+
+/// SHA2-384 as [`HmacHash`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sha2_384;
+impl HmacHash for Sha2_384 {
+    const HASH: HashAlgorithm = HashAlgorithm::Sha2_384;
+    type OutputSize = U48;
+    type BlockSize = U128;
+}
+
+/// SHA2-512 as [`HmacHash`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sha2_512;
+impl HmacHash for Sha2_512 {
+    const HASH: HashAlgorithm = HashAlgorithm::Sha2_512;
+    type OutputSize = U64;
+    type BlockSize = U128;
+}
+
+/// BLAKE2s as [`HmacHash`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Blake2s;
+impl HmacHash for Blake2s {
+    const HASH: HashAlgorithm = HashAlgorithm::Blake2s;
+    type OutputSize = U32;
+    type BlockSize = U64;
+}
+
+/// BLAKE2b as [`HmacHash`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Blake2b;
+impl HmacHash for Blake2b {
+    const HASH: HashAlgorithm = HashAlgorithm::Blake2b;
+    type OutputSize = U64;
+    type BlockSize = U128;
+}
+
+/// HMAC-SHA-1
+pub type HmacSha1 = TypedHmac<Sha1>;
+/// HMAC-SHA2-256
+pub type HmacSha2_256 = TypedHmac<Sha2_256>;
+/// HMAC-SHA2-384
+pub type HmacSha2_384 = TypedHmac<Sha2_384>;
+/// HMAC-SHA2-512
+pub type HmacSha2_512 = TypedHmac<Sha2_512>;
+/// HMAC-BLAKE2s
+pub type HmacBlake2s = TypedHmac<Blake2s>;
+/// HMAC-BLAKE2b
+pub type HmacBlake2b = TypedHmac<Blake2b>;
+
+/// An incremental HMAC computation with a hash function that is known at compile time
+///
+/// This type implements the traits required by [`digest::Mac`]; keys of any length are accepted by
+/// [`KeyInit::new_from_slice`].
+pub struct TypedHmac<H> {
+    /// The underlying computation
+    hmac: Hmac,
+    /// The hash function
+    _hash: PhantomData<H>,
+}
+impl<H> TypedHmac<H> {
+    /// Converts the computation into its runtime typed equivalent
+    pub fn into_inner(self) -> Hmac {
+        self.hmac
+    }
+}
+impl<H> Debug for TypedHmac<H> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("TypedHmac").field("hmac", &self.hmac).finish()
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<H> Zeroize for TypedHmac<H> {
+    fn zeroize(&mut self) {
+        self.hmac.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<H> ZeroizeOnDrop for TypedHmac<H> {}
+impl<H> MacMarker for TypedHmac<H> {}
+impl<H> KeySizeUser for TypedHmac<H>
+where
+    H: HmacHash,
+{
+    type KeySize = H::BlockSize;
+}
+impl<H> KeyInit for TypedHmac<H>
+where
+    H: HmacHash,
+{
+    fn new(key: &Key<Self>) -> Self {
+        Self { hmac: Hmac::new_unchecked(H::HASH, key), _hash: PhantomData }
+    }
+
+    fn new_from_slice(key: &[u8]) -> Result<Self, InvalidLength> {
+        Ok(Self { hmac: Hmac::new_unchecked(H::HASH, key), _hash: PhantomData })
+    }
+}
+impl<H> OutputSizeUser for TypedHmac<H>
+where
+    H: HmacHash,
+{
+    type OutputSize = H::OutputSize;
+}
+impl<H> Update for TypedHmac<H> {
+    fn update(&mut self, data: &[u8]) {
+        self.hmac.update(data);
+    }
+}
+impl<H> FixedOutput for TypedHmac<H>
+where
+    H: HmacHash,
+{
+    fn finalize_into(self, out: &mut Output<Self>) {
+        self.hmac.finalize_into(out);
+    }
+}
+impl<H> Reset for TypedHmac<H> {
+    fn reset(&mut self) {
+        self.hmac.reset();
+    }
+}
+impl<H> FixedOutputReset for TypedHmac<H>
+where
+    H: HmacHash,
+{
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        self.hmac.finalize_into(out);
+        self.hmac.reset();
+    }
+}
@@ -0,0 +1,939 @@
+//! Hybrid Public Key Encryption (HPKE) as specified in RFC 9180
+//!
+//! An [`Hpke`] instance is keyed by the `(Kem, Kdf, Aead)` identifiers of a cipher suite. The sender encapsulates a
+//! shared secret to the recipient's [`HpkePublicKey`] and obtains a [`Sender`] context, the recipient decapsulates it
+//! with its [`HpkeSecretKey`] and obtains the matching [`Receiver`] context. Both contexts seal and open messages in the
+//! same order and derive secrets via [`Sender::export`] and [`Receiver::export`]; [`Hpke::seal_base`] and
+//! [`Hpke::open_base`] cover the common case of a single message.
+//!
+//! All four modes are supported: the base mode, the PSK mode which additionally authenticates the sender via a
+//! pre-shared key, the auth mode which authenticates the sender via its static key pair, and the auth PSK mode which
+//! combines both. With [`Aead::ExportOnly`], contexts can only be used to export secrets.
+//!
+//! The vendored `Hacl_HPKE_{Curve51,Curve64,P256}_CP{32,128,256}_SHA{256,512}` modules implement a pre-standard draft
+//! with different labels and key schedule inputs, so they cannot interoperate with RFC 9180. This module composes the
+//! same primitives instead, which EverCrypt dispatches to the fastest compiled implementation for the current CPU:
+//! `EverCrypt_Curve25519` picks `Curve64` over `Curve51` if BMI2 and ADX are available, and
+//! `EverCrypt_Chacha20Poly1305` picks the 256 or 128 bit vectorized implementation over the portable one. AES-GCM is
+//! only provided by Vale, so suites with AES-GCM are rejected by [`Hpke::new`] if Vale was not compiled in or is not
+//! supported by the CPU.
+
+use crate::{
+    cpu,
+    hkdf::{Hkdf, Prk},
+    p256::{P256PublicKey, P256SecretKey, UNCOMPRESSED_POINT_LEN},
+    secret::{SecretArray, SecretBytes},
+    x25519::{X25519PublicKey, X25519SecretKey},
+    Error, HashAlgorithm,
+};
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Formatter};
+use evercrypt_tiny_sys::{
+    EverCrypt_AEAD_decrypt_expand, EverCrypt_AEAD_encrypt_expand, EverCrypt_Error_AuthenticationFailure,
+    EverCrypt_Error_Success, EverCrypt_Error_UnsupportedAlgorithm, Spec_Agile_AEAD_AES128_GCM,
+    Spec_Agile_AEAD_AES256_GCM, Spec_Agile_AEAD_CHACHA20_POLY1305, Spec_Agile_AEAD_alg,
+};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The length of an AEAD nonce in bytes
+pub const NONCE_LEN: usize = 12;
+/// The length of an AEAD tag in bytes
+pub const TAG_LEN: usize = 16;
+
+/// The version label that prefixes all labeled KDF inputs
+const VERSION_LABEL: &[u8] = b"HPKE-v1";
+/// The largest AEAD key length of all supported AEADs
+const MAX_KEY_LEN: usize = 32;
+/// The length of the shared secrets of all supported KEMs
+const SHARED_SECRET_LEN: usize = 32;
+/// The length of the secret keys of all supported KEMs
+const SECRET_KEY_LEN: usize = 32;
+/// The largest digest length of all supported KDFs
+const MAX_DIGEST_LEN: usize = 64;
+/// The mode identifier of the base mode
+const MODE_BASE: u8 = 0x00;
+/// The mode identifier of the PSK mode
+const MODE_PSK: u8 = 0x01;
+/// The mode identifier of the auth mode
+const MODE_AUTH: u8 = 0x02;
+/// The mode identifier of the auth PSK mode
+const MODE_AUTH_PSK: u8 = 0x03;
+
+/// A key encapsulation mechanism
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kem {
+    /// DHKEM(P-256, HKDF-SHA256)
+    DhKemP256HkdfSha256,
+    /// DHKEM(X25519, HKDF-SHA256)
+    DhKemX25519HkdfSha256,
+}
+impl Kem {
+    /// Looks up a KEM by its IANA identifier
+    pub const fn from_id(id: u16) -> Result<Self, Error> {
+        match id {
+            0x0010 => Ok(Self::DhKemP256HkdfSha256),
+            0x0020 => Ok(Self::DhKemX25519HkdfSha256),
+            _ => Err(Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// The IANA identifier
+    pub const fn id(self) -> u16 {
+        match self {
+            Self::DhKemP256HkdfSha256 => 0x0010,
+            Self::DhKemX25519HkdfSha256 => 0x0020,
+        }
+    }
+
+    /// The length of a serialized secret key (`Nsk`)
+    pub const fn secret_key_len(self) -> usize {
+        SECRET_KEY_LEN
+    }
+
+    /// The length of a serialized public key and of an encapsulated key (`Npk` and `Nenc`)
+    pub const fn public_key_len(self) -> usize {
+        match self {
+            Self::DhKemP256HkdfSha256 => UNCOMPRESSED_POINT_LEN,
+            Self::DhKemX25519HkdfSha256 => 32,
+        }
+    }
+
+    /// The suite ID for the KEM's labeled KDF calls (`"KEM" || I2OSP(kem_id, 2)`)
+    fn suite_id(self) -> [u8; 5] {
+        let [id0, id1] = self.id().to_be_bytes();
+        [b'K', b'E', b'M', id0, id1]
+    }
+
+    /// The KEM's KDF, which is HKDF-SHA256 for all supported KEMs
+    const fn hkdf(self) -> Hkdf {
+        Hkdf::new_unchecked(HashAlgorithm::Sha2_256)
+    }
+
+    /// Derives the shared secret from the Diffie-Hellman output(s) `dh` and the KEM context (RFC 9180, section 4.1)
+    fn extract_and_expand(self, dh: &[u8], kem_context: &[&[u8]]) -> SecretArray<SHARED_SECRET_LEN> {
+        let suite_id = self.suite_id();
+        let eae_prk = labeled_extract(self.hkdf(), &suite_id, b"", b"eae_prk", dh);
+
+        let mut shared_secret = SecretArray::default();
+        labeled_expand(&eae_prk, &suite_id, b"shared_secret", kem_context, &mut shared_secret[..])
+            .expect("A single block never exceeds the maximum output length");
+        shared_secret
+    }
+}
+
+/// A key derivation function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kdf {
+    /// HKDF-SHA256
+    HkdfSha256,
+    /// HKDF-SHA384
+    HkdfSha384,
+    /// HKDF-SHA512
+    HkdfSha512,
+}
+impl Kdf {
+    /// Looks up a KDF by its IANA identifier
+    pub const fn from_id(id: u16) -> Result<Self, Error> {
+        match id {
+            0x0001 => Ok(Self::HkdfSha256),
+            0x0002 => Ok(Self::HkdfSha384),
+            0x0003 => Ok(Self::HkdfSha512),
+            _ => Err(Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// The IANA identifier
+    pub const fn id(self) -> u16 {
+        match self {
+            Self::HkdfSha256 => 0x0001,
+            Self::HkdfSha384 => 0x0002,
+            Self::HkdfSha512 => 0x0003,
+        }
+    }
+
+    /// The underlying hash function
+    pub const fn hash(self) -> HashAlgorithm {
+        match self {
+            Self::HkdfSha256 => HashAlgorithm::Sha2_256,
+            Self::HkdfSha384 => HashAlgorithm::Sha2_384,
+            Self::HkdfSha512 => HashAlgorithm::Sha2_512,
+        }
+    }
+}
+
+/// An authenticated encryption algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Aead {
+    /// AES-128-GCM
+    Aes128Gcm,
+    /// AES-256-GCM
+    Aes256Gcm,
+    /// ChaCha20-Poly1305
+    ChaCha20Poly1305,
+    /// No AEAD; the contexts can only export secrets
+    ExportOnly,
+}
+impl Aead {
+    /// Looks up an AEAD by its IANA identifier
+    pub const fn from_id(id: u16) -> Result<Self, Error> {
+        match id {
+            0x0001 => Ok(Self::Aes128Gcm),
+            0x0002 => Ok(Self::Aes256Gcm),
+            0x0003 => Ok(Self::ChaCha20Poly1305),
+            0xffff => Ok(Self::ExportOnly),
+            _ => Err(Error::UnsupportedAlgorithm),
+        }
+    }
+
+    /// The IANA identifier
+    pub const fn id(self) -> u16 {
+        match self {
+            Self::Aes128Gcm => 0x0001,
+            Self::Aes256Gcm => 0x0002,
+            Self::ChaCha20Poly1305 => 0x0003,
+            Self::ExportOnly => 0xffff,
+        }
+    }
+
+    /// The key length in bytes (`Nk`)
+    pub const fn key_len(self) -> usize {
+        match self {
+            Self::Aes128Gcm => 16,
+            Self::Aes256Gcm | Self::ChaCha20Poly1305 => 32,
+            Self::ExportOnly => 0,
+        }
+    }
+
+    /// The EverCrypt algorithm identifier, or `None` for [`Aead::ExportOnly`]
+    const fn to_spec(self) -> Option<Spec_Agile_AEAD_alg> {
+        let spec = match self {
+            Self::Aes128Gcm => Spec_Agile_AEAD_AES128_GCM,
+            Self::Aes256Gcm => Spec_Agile_AEAD_AES256_GCM,
+            Self::ChaCha20Poly1305 => Spec_Agile_AEAD_CHACHA20_POLY1305,
+            Self::ExportOnly => return None,
+        };
+        Some(spec as Spec_Agile_AEAD_alg)
+    }
+}
+
+/// An HPKE secret key of a specific KEM
+#[derive(Clone)]
+pub struct HpkeSecretKey {
+    /// The underlying key
+    key: SecretKey,
+}
+/// The underlying secret key of an [`HpkeSecretKey`]
+#[derive(Clone)]
+enum SecretKey {
+    /// A P-256 secret key
+    P256(P256SecretKey),
+    /// An X25519 secret key
+    X25519(X25519SecretKey),
+}
+impl HpkeSecretKey {
+    /// Generates a new random secret key
+    ///
+    /// This derives the key from `Nsk` random bytes as suggested in RFC 9180, section 7.1.3, so an RNG that replays the
+    /// `ikmE` of a test vector reproduces its ephemeral key.
+    pub fn generate<R>(kem: Kem, rng: &mut R) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut ikm = SecretArray::<SECRET_KEY_LEN>::default();
+        rng.try_fill_bytes(&mut ikm[..kem.secret_key_len()]).map_err(|_| Error::RngFailure)?;
+        Self::derive(kem, &ikm[..kem.secret_key_len()])
+    }
+
+    /// Deterministically derives a secret key from the input keying material `ikm` (`DeriveKeyPair`)
+    ///
+    /// `ikm` should contain at least `Nsk` bytes of entropy.
+    pub fn derive(kem: Kem, ikm: &[u8]) -> Result<Self, Error> {
+        let key = match kem {
+            Kem::DhKemP256HkdfSha256 => SecretKey::P256(P256SecretKey::derive(ikm)?),
+            Kem::DhKemX25519HkdfSha256 => {
+                // `LabeledExpand(LabeledExtract("", "dkp_prk", ikm), "sk", "", Nsk)`
+                let suite_id = kem.suite_id();
+                let dkp_prk = labeled_extract(kem.hkdf(), &suite_id, b"", b"dkp_prk", ikm);
+                let mut scalar = SecretArray::<SECRET_KEY_LEN>::default();
+                labeled_expand(&dkp_prk, &suite_id, b"sk", &[], &mut scalar[..])?;
+                SecretKey::X25519(X25519SecretKey::from_bytes(&scalar[..])?)
+            }
+        };
+        Ok(Self { key })
+    }
+
+    /// Loads a serialized secret key of `kem` (`DeserializePrivateKey`)
+    pub fn from_bytes(kem: Kem, bytes: &[u8]) -> Result<Self, Error> {
+        let key = match kem {
+            Kem::DhKemP256HkdfSha256 => SecretKey::P256(P256SecretKey::from_bytes(bytes)?),
+            Kem::DhKemX25519HkdfSha256 => SecretKey::X25519(X25519SecretKey::from_bytes(bytes)?),
+        };
+        Ok(Self { key })
+    }
+
+    /// Serializes the secret key (`SerializePrivateKey`)
+    pub fn to_bytes(&self) -> SecretBytes {
+        match &self.key {
+            SecretKey::P256(key) => SecretBytes::from_slice(&key.to_bytes()[..]),
+            SecretKey::X25519(key) => SecretBytes::from_slice(&key.to_bytes()[..]),
+        }
+    }
+
+    /// The KEM
+    pub const fn kem(&self) -> Kem {
+        match self.key {
+            SecretKey::P256(_) => Kem::DhKemP256HkdfSha256,
+            SecretKey::X25519(_) => Kem::DhKemX25519HkdfSha256,
+        }
+    }
+
+    /// Computes the public key that belongs to this secret key
+    pub fn public_key(&self) -> HpkePublicKey {
+        let key = match &self.key {
+            SecretKey::P256(key) => PublicKey::P256(key.public_key()),
+            SecretKey::X25519(key) => PublicKey::X25519(key.public_key()),
+        };
+        HpkePublicKey { key }
+    }
+
+    /// Performs a Diffie-Hellman key agreement with `public_key` of the same KEM
+    fn dh(&self, public_key: &HpkePublicKey) -> Result<SecretArray<SHARED_SECRET_LEN>, Error> {
+        match (&self.key, &public_key.key) {
+            (SecretKey::P256(secret_key), PublicKey::P256(public_key)) => secret_key.ecdh(public_key),
+            (SecretKey::X25519(secret_key), PublicKey::X25519(public_key)) => secret_key.diffie_hellman(public_key),
+            _ => Err(Error::InvalidKey),
+        }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for HpkeSecretKey {
+    fn zeroize(&mut self) {
+        match &mut self.key {
+            SecretKey::P256(key) => key.zeroize(),
+            SecretKey::X25519(key) => key.zeroize(),
+        }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for HpkeSecretKey {}
+impl Debug for HpkeSecretKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("HpkeSecretKey").field("kem", &self.kem()).finish_non_exhaustive()
+    }
+}
+
+/// An HPKE public key of a specific KEM
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HpkePublicKey {
+    /// The underlying key
+    key: PublicKey,
+}
+/// The underlying public key of an [`HpkePublicKey`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PublicKey {
+    /// A validated P-256 public key
+    P256(P256PublicKey),
+    /// An X25519 public key
+    X25519(X25519PublicKey),
+}
+impl HpkePublicKey {
+    /// Loads a serialized public key of `kem` (`DeserializePublicKey`)
+    ///
+    /// P-256 public keys must be uncompressed SEC1 points and are validated.
+    pub fn from_bytes(kem: Kem, bytes: &[u8]) -> Result<Self, Error> {
+        let key = match kem {
+            Kem::DhKemP256HkdfSha256 if bytes.len() != UNCOMPRESSED_POINT_LEN => return Err(Error::InvalidKey),
+            Kem::DhKemP256HkdfSha256 => PublicKey::P256(P256PublicKey::from_sec1(bytes)?),
+            Kem::DhKemX25519HkdfSha256 => PublicKey::X25519(X25519PublicKey::from_bytes(bytes)?),
+        };
+        Ok(Self { key })
+    }
+
+    /// Serializes the public key (`SerializePublicKey`)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match &self.key {
+            PublicKey::P256(key) => key.to_uncompressed().to_vec(),
+            PublicKey::X25519(key) => key.to_bytes().to_vec(),
+        }
+    }
+
+    /// The KEM
+    pub const fn kem(&self) -> Kem {
+        match self.key {
+            PublicKey::P256(_) => Kem::DhKemP256HkdfSha256,
+            PublicKey::X25519(_) => Kem::DhKemX25519HkdfSha256,
+        }
+    }
+}
+
+/// An HPKE cipher suite
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hpke {
+    /// The KEM
+    kem: Kem,
+    /// The KDF
+    kdf: Kdf,
+    /// The AEAD
+    aead: Aead,
+}
+impl Hpke {
+    /// Creates a new HPKE instance for the cipher suite `(kem, kdf, aead)`
+    ///
+    /// This fails with [`Error::UnsupportedAlgorithm`] if `aead` is not available on this build or CPU.
+    pub fn new(kem: Kem, kdf: Kdf, aead: Aead) -> Result<Self, Error> {
+        // Detect the CPU features once, so that EverCrypt's dispatchers select the fastest implementations
+        cpu::features();
+        let Some(spec) = aead.to_spec() else {
+            return Ok(Self { kem, kdf, aead });
+        };
+
+        // Probe the AEAD, since only EverCrypt knows whether Vale's AES-GCM was compiled in and is supported
+        let (mut key, mut nonce, mut tag, mut empty) = ([0; MAX_KEY_LEN], [0; NONCE_LEN], [0; TAG_LEN], [0; 0]);
+        let result = unsafe {
+            EverCrypt_AEAD_encrypt_expand(
+                spec,
+                key.as_mut_ptr(),
+                nonce.as_mut_ptr(),
+                NONCE_LEN as u32,
+                empty.as_mut_ptr(),
+                0,
+                empty.as_mut_ptr(),
+                0,
+                empty.as_mut_ptr(),
+                tag.as_mut_ptr(),
+            )
+        };
+        match result as u32 {
+            code if code == EverCrypt_Error_Success => Ok(Self { kem, kdf, aead }),
+            code if code == EverCrypt_Error_UnsupportedAlgorithm => Err(Error::UnsupportedAlgorithm),
+            _ => Err(Error::OperationFailed),
+        }
+    }
+
+    /// The KEM
+    pub const fn kem(&self) -> Kem {
+        self.kem
+    }
+
+    /// The KDF
+    pub const fn kdf(&self) -> Kdf {
+        self.kdf
+    }
+
+    /// The AEAD
+    pub const fn aead(&self) -> Aead {
+        self.aead
+    }
+
+    /// Encapsulates a fresh shared secret to `public_key` and sets up the sender context for `info`
+    ///
+    /// Returns the encapsulated key `enc`, which must be transmitted to the recipient, and the sender context.
+    pub fn setup_base_sender<R>(
+        &self,
+        public_key: &HpkePublicKey,
+        info: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Sender), Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        self.setup_sender(MODE_BASE, public_key, info, b"", b"", None, rng)
+    }
+
+    /// Decapsulates the shared secret from the encapsulated key `enc` and sets up the receiver context for `info`
+    pub fn setup_base_receiver(&self, enc: &[u8], secret_key: &HpkeSecretKey, info: &[u8]) -> Result<Receiver, Error> {
+        self.setup_receiver(MODE_BASE, enc, secret_key, info, b"", b"", None)
+    }
+
+    /// Like [`Hpke::setup_base_sender`], but additionally binds the context to the pre-shared key `psk` with the
+    /// identifier `psk_id`
+    ///
+    /// Neither `psk` nor `psk_id` may be empty, and `psk` should contain at least 32 bytes of entropy.
+    pub fn setup_psk_sender<R>(
+        &self,
+        public_key: &HpkePublicKey,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Sender), Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        self.setup_sender(MODE_PSK, public_key, info, psk, psk_id, None, rng)
+    }
+
+    /// Like [`Hpke::setup_base_receiver`], but additionally binds the context to the pre-shared key `psk` with the
+    /// identifier `psk_id`
+    pub fn setup_psk_receiver(
+        &self,
+        enc: &[u8],
+        secret_key: &HpkeSecretKey,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Result<Receiver, Error> {
+        self.setup_receiver(MODE_PSK, enc, secret_key, info, psk, psk_id, None)
+    }
+
+    /// Like [`Hpke::setup_base_sender`], but additionally authenticates the sender via its static `sender_key`
+    pub fn setup_auth_sender<R>(
+        &self,
+        public_key: &HpkePublicKey,
+        info: &[u8],
+        sender_key: &HpkeSecretKey,
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Sender), Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        self.setup_sender(MODE_AUTH, public_key, info, b"", b"", Some(sender_key), rng)
+    }
+
+    /// Like [`Hpke::setup_base_receiver`], but additionally authenticates the sender's static `sender_public_key`
+    pub fn setup_auth_receiver(
+        &self,
+        enc: &[u8],
+        secret_key: &HpkeSecretKey,
+        info: &[u8],
+        sender_public_key: &HpkePublicKey,
+    ) -> Result<Receiver, Error> {
+        self.setup_receiver(MODE_AUTH, enc, secret_key, info, b"", b"", Some(sender_public_key))
+    }
+
+    /// Combines [`Hpke::setup_psk_sender`] and [`Hpke::setup_auth_sender`]
+    pub fn setup_auth_psk_sender<R>(
+        &self,
+        public_key: &HpkePublicKey,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        sender_key: &HpkeSecretKey,
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Sender), Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        self.setup_sender(MODE_AUTH_PSK, public_key, info, psk, psk_id, Some(sender_key), rng)
+    }
+
+    /// Combines [`Hpke::setup_psk_receiver`] and [`Hpke::setup_auth_receiver`]
+    pub fn setup_auth_psk_receiver(
+        &self,
+        enc: &[u8],
+        secret_key: &HpkeSecretKey,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        sender_public_key: &HpkePublicKey,
+    ) -> Result<Receiver, Error> {
+        self.setup_receiver(MODE_AUTH_PSK, enc, secret_key, info, psk, psk_id, Some(sender_public_key))
+    }
+
+    /// Encrypts a single `plaintext` with the associated data `aad` to `public_key`
+    ///
+    /// Returns the encapsulated key `enc` and the ciphertext.
+    pub fn seal_base<R>(
+        &self,
+        public_key: &HpkePublicKey,
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Vec<u8>), Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let (enc, mut sender) = self.setup_base_sender(public_key, info, rng)?;
+        let ciphertext = sender.seal(aad, plaintext)?;
+        Ok((enc, ciphertext))
+    }
+
+    /// Decrypts a single `ciphertext` with the associated data `aad` and the encapsulated key `enc`
+    pub fn open_base(
+        &self,
+        enc: &[u8],
+        secret_key: &HpkeSecretKey,
+        info: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let mut receiver = self.setup_base_receiver(enc, secret_key, info)?;
+        receiver.open(aad, ciphertext)
+    }
+
+    /// The suite ID for the labeled KDF calls of the key schedule (`"HPKE" || kem_id || kdf_id || aead_id`)
+    fn suite_id(&self) -> [u8; 10] {
+        let mut suite_id = *b"HPKE\0\0\0\0\0\0";
+        suite_id[4..6].copy_from_slice(&self.kem.id().to_be_bytes());
+        suite_id[6..8].copy_from_slice(&self.kdf.id().to_be_bytes());
+        suite_id[8..10].copy_from_slice(&self.aead.id().to_be_bytes());
+        suite_id
+    }
+
+    /// Sets up the sender context for `mode`
+    #[allow(clippy::too_many_arguments)]
+    fn setup_sender<R>(
+        &self,
+        mode: u8,
+        public_key: &HpkePublicKey,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        sender_key: Option<&HpkeSecretKey>,
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, Sender), Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        Self::verify_psk_inputs(mode, psk, psk_id)?;
+        let (enc, shared_secret) = self.encap(public_key, sender_key, rng)?;
+        let context = self.key_schedule(mode, &shared_secret[..], info, psk, psk_id)?;
+        Ok((enc, Sender { context }))
+    }
+
+    /// Sets up the receiver context for `mode`
+    #[allow(clippy::too_many_arguments)]
+    fn setup_receiver(
+        &self,
+        mode: u8,
+        enc: &[u8],
+        secret_key: &HpkeSecretKey,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        sender_public_key: Option<&HpkePublicKey>,
+    ) -> Result<Receiver, Error> {
+        Self::verify_psk_inputs(mode, psk, psk_id)?;
+        let shared_secret = self.decap(enc, secret_key, sender_public_key)?;
+        let context = self.key_schedule(mode, &shared_secret[..], info, psk, psk_id)?;
+        Ok(Receiver { context })
+    }
+
+    /// Ensures that a PSK is given if and only if `mode` uses one (RFC 9180, section 5.1)
+    fn verify_psk_inputs(mode: u8, psk: &[u8], psk_id: &[u8]) -> Result<(), Error> {
+        let uses_psk = matches!(mode, MODE_PSK | MODE_AUTH_PSK);
+        match (uses_psk, psk.is_empty(), psk_id.is_empty()) {
+            (true, false, false) | (false, true, true) => Ok(()),
+            _ => Err(Error::InvalidKey),
+        }
+    }
+
+    /// Generates an ephemeral key pair and encapsulates a shared secret to `public_key`, authenticated with
+    /// `sender_key` if given (`Encap` and `AuthEncap`, RFC 9180, section 4.1)
+    fn encap<R>(
+        &self,
+        public_key: &HpkePublicKey,
+        sender_key: Option<&HpkeSecretKey>,
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, SecretArray<SHARED_SECRET_LEN>), Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        if public_key.kem() != self.kem || sender_key.is_some_and(|key| key.kem() != self.kem) {
+            return Err(Error::InvalidKey);
+        }
+
+        // Perform the key agreement with an ephemeral key
+        let ephemeral_key = HpkeSecretKey::generate(self.kem, rng)?;
+        let (enc, recipient) = (ephemeral_key.public_key().to_bytes(), public_key.to_bytes());
+        let dh = ephemeral_key.dh(public_key)?;
+        let shared_secret = match sender_key {
+            None => self.kem.extract_and_expand(&dh[..], &[&enc, &recipient]),
+            Some(sender_key) => {
+                // Perform the additional key agreement with the sender's static key
+                let static_dh = sender_key.dh(public_key)?;
+                let sender = sender_key.public_key().to_bytes();
+                self.kem.extract_and_expand(&concat_dh(&dh, &static_dh)[..], &[&enc, &recipient, &sender])
+            }
+        };
+        Ok((enc, shared_secret))
+    }
+
+    /// Decapsulates the shared secret from the encapsulated key `enc`, authenticated with `sender_public_key` if given
+    /// (`Decap` and `AuthDecap`, RFC 9180, section 4.1)
+    fn decap(
+        &self,
+        enc: &[u8],
+        secret_key: &HpkeSecretKey,
+        sender_public_key: Option<&HpkePublicKey>,
+    ) -> Result<SecretArray<SHARED_SECRET_LEN>, Error> {
+        if secret_key.kem() != self.kem || sender_public_key.is_some_and(|key| key.kem() != self.kem) {
+            return Err(Error::InvalidKey);
+        }
+
+        // Perform the key agreement with the ephemeral public key
+        let ephemeral_key = HpkePublicKey::from_bytes(self.kem, enc)?;
+        let recipient = secret_key.public_key().to_bytes();
+        let dh = secret_key.dh(&ephemeral_key)?;
+        let shared_secret = match sender_public_key {
+            None => self.kem.extract_and_expand(&dh[..], &[enc, &recipient]),
+            Some(sender_public_key) => {
+                // Perform the additional key agreement with the sender's static key
+                let static_dh = secret_key.dh(sender_public_key)?;
+                let sender = sender_public_key.to_bytes();
+                self.kem.extract_and_expand(&concat_dh(&dh, &static_dh)[..], &[enc, &recipient, &sender])
+            }
+        };
+        Ok(shared_secret)
+    }
+
+    /// Derives the AEAD key, base nonce and exporter secret from the shared secret (RFC 9180, section 5.1)
+    fn key_schedule(
+        &self,
+        mode: u8,
+        shared_secret: &[u8],
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Result<Context, Error> {
+        let (hkdf, suite_id) = (Hkdf::new_unchecked(self.kdf.hash()), self.suite_id());
+        let psk_id_hash = labeled_extract(hkdf, &suite_id, b"", b"psk_id_hash", psk_id);
+        let info_hash = labeled_extract(hkdf, &suite_id, b"", b"info_hash", info);
+        let key_schedule_context: &[&[u8]] = &[&[mode], psk_id_hash.as_bytes(), info_hash.as_bytes()];
+
+        // Derive the key and base nonce from the secret; export-only contexts have neither
+        let secret = labeled_extract(hkdf, &suite_id, shared_secret, b"secret", psk);
+        let (mut key, mut base_nonce) = (SecretArray::default(), [0; NONCE_LEN]);
+        if self.aead != Aead::ExportOnly {
+            labeled_expand(&secret, &suite_id, b"key", key_schedule_context, &mut key[..self.aead.key_len()])?;
+            labeled_expand(&secret, &suite_id, b"base_nonce", key_schedule_context, &mut base_nonce)?;
+        }
+
+        // Derive the exporter secret
+        let mut exporter_secret = SecretArray::<MAX_DIGEST_LEN>::default();
+        let exporter_secret = &mut exporter_secret[..self.kdf.hash().digest_len()];
+        labeled_expand(&secret, &suite_id, b"exp", key_schedule_context, exporter_secret)?;
+        let exporter_secret = hkdf.from_prk(exporter_secret)?;
+        Ok(Context { aead: self.aead, suite_id, key, base_nonce, exporter_secret, sequence: 0 })
+    }
+}
+
+/// The sender context of an HPKE session
+///
+/// Messages must be opened by the [`Receiver`] in the order in which they were sealed.
+pub struct Sender {
+    /// The encryption context
+    context: Context,
+}
+impl Sender {
+    /// Encrypts `plaintext` with the associated data `aad` under the next sequence number
+    ///
+    /// The ciphertext is the encrypted plaintext followed by the tag.
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        self.context.seal(aad, plaintext)
+    }
+
+    /// Exports a secret for `exporter_context` that fills `okm`
+    ///
+    /// `okm` must not be longer than `255 * Nh` bytes.
+    pub fn export(&self, exporter_context: &[u8], okm: &mut [u8]) -> Result<(), Error> {
+        self.context.export(exporter_context, okm)
+    }
+
+    /// The sequence number of the next message, i.e. the amount of messages sealed so far
+    pub const fn sequence_number(&self) -> u64 {
+        self.context.sequence
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for Sender {
+    fn zeroize(&mut self) {
+        self.context.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Sender {}
+impl Debug for Sender {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Sender").field("aead", &self.context.aead).finish_non_exhaustive()
+    }
+}
+
+/// The receiver context of an HPKE session
+pub struct Receiver {
+    /// The encryption context
+    context: Context,
+}
+impl Receiver {
+    /// Decrypts `ciphertext` with the associated data `aad` under the next sequence number
+    ///
+    /// The sequence number only advances if the ciphertext is authentic.
+    pub fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        self.context.open(aad, ciphertext)
+    }
+
+    /// Exports a secret for `exporter_context` that fills `okm`
+    ///
+    /// `okm` must not be longer than `255 * Nh` bytes.
+    pub fn export(&self, exporter_context: &[u8], okm: &mut [u8]) -> Result<(), Error> {
+        self.context.export(exporter_context, okm)
+    }
+
+    /// The sequence number of the next message, i.e. the amount of messages opened so far
+    pub const fn sequence_number(&self) -> u64 {
+        self.context.sequence
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for Receiver {
+    fn zeroize(&mut self) {
+        self.context.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Receiver {}
+impl Debug for Receiver {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Receiver").field("aead", &self.context.aead).finish_non_exhaustive()
+    }
+}
+
+/// The encryption context shared by [`Sender`] and [`Receiver`] (RFC 9180, section 5.2)
+struct Context {
+    /// The AEAD
+    aead: Aead,
+    /// The AEAD key, zero-padded to the largest key length
+    key: SecretArray<MAX_KEY_LEN>,
+    /// The suite ID of the key schedule
+    suite_id: [u8; 10],
+    /// The base nonce
+    base_nonce: [u8; NONCE_LEN],
+    /// The exporter secret
+    exporter_secret: Prk,
+    /// The sequence number of the next message
+    sequence: u64,
+}
+impl Context {
+    /// Encrypts `plaintext` under the current nonce and advances the sequence number
+    fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        // Validate the AEAD and the lengths
+        let spec = self.aead.to_spec().ok_or(Error::UnsupportedAlgorithm)?;
+        let mut nonce = self.nonce()?;
+        let aad_len = u32::try_from(aad.len()).map_err(|_| Error::InvalidLength)?;
+        let plaintext_len = u32::try_from(plaintext.len()).map_err(|_| Error::InvalidLength)?;
+
+        // Encrypt the plaintext and append the tag
+        let mut ciphertext = alloc::vec![0; plaintext.len() + TAG_LEN];
+        let (cipher, tag) = ciphertext.split_at_mut(plaintext.len());
+        let mut key = self.key.clone();
+        let result = unsafe {
+            EverCrypt_AEAD_encrypt_expand(
+                spec,
+                key.as_mut_ptr(),
+                nonce.as_mut_ptr(),
+                NONCE_LEN as u32,
+                aad.as_ptr().cast_mut(),
+                aad_len,
+                plaintext.as_ptr().cast_mut(),
+                plaintext_len,
+                cipher.as_mut_ptr(),
+                tag.as_mut_ptr(),
+            )
+        };
+        if result as u32 != EverCrypt_Error_Success {
+            return Err(Error::OperationFailed);
+        }
+
+        self.sequence += 1;
+        Ok(ciphertext)
+    }
+
+    /// Decrypts `ciphertext` under the current nonce and advances the sequence number on success
+    fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        // Validate the AEAD and the lengths
+        let spec = self.aead.to_spec().ok_or(Error::UnsupportedAlgorithm)?;
+        let mut nonce = self.nonce()?;
+        let aad_len = u32::try_from(aad.len()).map_err(|_| Error::InvalidLength)?;
+        let plaintext_len = ciphertext.len().checked_sub(TAG_LEN).ok_or(Error::InvalidLength)?;
+        let cipher_len = u32::try_from(plaintext_len).map_err(|_| Error::InvalidLength)?;
+
+        // Verify the tag and decrypt the ciphertext
+        let (cipher, tag) = ciphertext.split_at(plaintext_len);
+        let mut plaintext = alloc::vec![0; plaintext_len];
+        let mut key = self.key.clone();
+        let result = unsafe {
+            EverCrypt_AEAD_decrypt_expand(
+                spec,
+                key.as_mut_ptr(),
+                nonce.as_mut_ptr(),
+                NONCE_LEN as u32,
+                aad.as_ptr().cast_mut(),
+                aad_len,
+                cipher.as_ptr().cast_mut(),
+                cipher_len,
+                tag.as_ptr().cast_mut(),
+                plaintext.as_mut_ptr(),
+            )
+        };
+        match result as u32 {
+            code if code == EverCrypt_Error_Success => (),
+            code if code == EverCrypt_Error_AuthenticationFailure => return Err(Error::DecryptionFailed),
+            _ => return Err(Error::OperationFailed),
+        }
+
+        self.sequence += 1;
+        Ok(plaintext)
+    }
+
+    /// Computes `LabeledExpand(exporter_secret, "sec", exporter_context, L)` into `okm` (RFC 9180, section 5.3)
+    fn export(&self, exporter_context: &[u8], okm: &mut [u8]) -> Result<(), Error> {
+        labeled_expand(&self.exporter_secret, &self.suite_id, b"sec", &[exporter_context], okm)
+    }
+
+    /// Computes the nonce for the current sequence number (`base_nonce XOR I2OSP(seq, Nn)`)
+    ///
+    /// This fails with [`Error::OperationFailed`] once the sequence number is exhausted.
+    fn nonce(&self) -> Result<[u8; NONCE_LEN], Error> {
+        if self.sequence == u64::MAX {
+            return Err(Error::OperationFailed);
+        }
+
+        let mut nonce = self.base_nonce;
+        let sequence = self.sequence.to_be_bytes();
+        nonce[NONCE_LEN - sequence.len()..].iter_mut().zip(sequence).for_each(|(byte, sequence)| *byte ^= sequence);
+        Ok(nonce)
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for Context {
+    fn zeroize(&mut self) {
+        self.key.zeroize();
+        self.base_nonce.zeroize();
+        self.exporter_secret.zeroize();
+    }
+}
+
+/// Concatenates the ephemeral and the static Diffie-Hellman output (`dh = concat(DH(skE, pkR), DH(skS, pkR))`)
+fn concat_dh(
+    dh: &SecretArray<SHARED_SECRET_LEN>,
+    static_dh: &SecretArray<SHARED_SECRET_LEN>,
+) -> SecretArray<{ 2 * SHARED_SECRET_LEN }> {
+    let mut concatenated = SecretArray::default();
+    concatenated[..SHARED_SECRET_LEN].copy_from_slice(&dh[..]);
+    concatenated[SHARED_SECRET_LEN..].copy_from_slice(&static_dh[..]);
+    concatenated
+}
+
+/// Computes `LabeledExtract(salt, label, ikm)` for `suite_id` (RFC 9180, section 4)
+fn labeled_extract(hkdf: Hkdf, suite_id: &[u8], salt: &[u8], label: &[u8], ikm: &[u8]) -> Prk {
+    let mut labeled_ikm = SecretBytes::new(VERSION_LABEL.len() + suite_id.len() + label.len() + ikm.len());
+    let (version_label, rest) = labeled_ikm.split_at_mut(VERSION_LABEL.len());
+    let (suite_id_, rest) = rest.split_at_mut(suite_id.len());
+    let (label_, ikm_) = rest.split_at_mut(label.len());
+    version_label.copy_from_slice(VERSION_LABEL);
+    suite_id_.copy_from_slice(suite_id);
+    label_.copy_from_slice(label);
+    ikm_.copy_from_slice(ikm);
+    hkdf.extract(salt, &labeled_ikm)
+}
+
+/// Computes `LabeledExpand(prk, label, info, L)` for `suite_id` into `okm`, where `info` is given in parts
+fn labeled_expand(prk: &Prk, suite_id: &[u8], label: &[u8], info: &[&[u8]], okm: &mut [u8]) -> Result<(), Error> {
+    let okm_len = u16::try_from(okm.len()).map_err(|_| Error::InvalidLength)?.to_be_bytes();
+    let mut labeled_info: Vec<&[u8]> = alloc::vec![&okm_len, VERSION_LABEL, suite_id, label];
+    labeled_info.extend_from_slice(info);
+    prk.expand_multi_info(&labeled_info, okm)
+}
@@ -0,0 +1,36 @@
+#![doc = include_str!("../README.md")]
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod blake2;
+pub mod blake2p;
+mod cpu;
+#[cfg(feature = "std")]
+pub mod csprng;
+pub mod drbg;
+pub mod ed25519;
+pub mod entropy;
+mod error;
+pub mod frodo;
+mod hash;
+pub mod hkdf;
+pub mod hmac;
+pub mod hpke;
+pub mod merkle;
+pub mod nacl;
+pub mod p256;
+pub mod pkcs;
+pub mod rsa;
+pub mod secret;
+pub mod sha2;
+pub mod sha3;
+pub mod sp800_185;
+pub mod tls13;
+pub mod x25519;
+
+pub use crate::{error::Error, hash::HashAlgorithm};
+/// RustCrypto's `kem` crate, whose `Encapsulate` and `Decapsulate` traits are implemented by the KEMs
+pub use kem;
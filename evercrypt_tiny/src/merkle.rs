@@ -0,0 +1,393 @@
+//! Merkle trees and hash chains
+//!
+//! [`MerkleTree`] implements the append-only Merkle tree of RFC 6962 and RFC 9162 with inclusion and consistency
+//! proofs. Leaves and interior nodes are domain separated with the prefixes `0x00` and `0x01`, so a leaf can never be
+//! confused with an interior node. SHA-2 leaves and nodes are hashed in batches with [`crate::sha2::hash_many`].
+
+use crate::{hash::HashState, sha2, Error, HashAlgorithm};
+use alloc::{vec, vec::Vec};
+
+/// The domain separation prefix of leaf hashes
+const LEAF_PREFIX: u8 = 0x00;
+/// The domain separation prefix of interior node hashes
+const NODE_PREFIX: u8 = 0x01;
+
+/// Computes the leaf hash `HASH(0x00 || data)`
+pub fn leaf_hash(hash: HashAlgorithm, data: &[u8]) -> Vec<u8> {
+    digest_parts(hash, &[&[LEAF_PREFIX], data])
+}
+
+/// Computes the interior node hash `HASH(0x01 || left || right)`
+pub fn node_hash(hash: HashAlgorithm, left: &[u8], right: &[u8]) -> Vec<u8> {
+    digest_parts(hash, &[&[NODE_PREFIX], left, right])
+}
+
+/// Hashes the concatenation of `parts`
+fn digest_parts(hash: HashAlgorithm, parts: &[&[u8]]) -> Vec<u8> {
+    let mut state = HashState::new(hash);
+    parts.iter().for_each(|part| state.update(part));
+
+    let mut digest = vec![0; hash.digest_len()];
+    state.finish_into(&mut digest);
+    digest
+}
+
+/// Hashes all `inputs`, using the multi-buffer kernels for SHA-2
+fn digest_many(hash: HashAlgorithm, inputs: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    // Fall back to the streaming hash for other hash functions or inputs that exceed the 32 bit length limit
+    let mut digests = vec![vec![0; hash.digest_len()]; inputs.len()];
+    let inputs_ref: Vec<&[u8]> = inputs.iter().map(Vec::as_slice).collect();
+    if sha2::hash_many(hash, &inputs_ref, &mut digests).is_err() {
+        digests = inputs.iter().map(|input| digest_parts(hash, &[input])).collect();
+    }
+    digests
+}
+
+/// The size of the left subtree of a tree with `size` leaves, i.e. the largest power of two smaller than `size`
+fn split(size: u64) -> u64 {
+    debug_assert!(size > 1, "Cannot split a tree with less than two leaves");
+    1 << (u64::BITS - 1 - (size - 1).leading_zeros())
+}
+
+/// An append-only Merkle tree as specified in RFC 6962 and RFC 9162
+///
+/// The tree keeps all complete subtree hashes, so roots and proofs for the current and all previous tree sizes can be
+/// computed without rehashing the leaves.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The complete subtree hashes, where `levels[k][i]` covers the leaves `i * 2^k .. (i + 1) * 2^k`
+    levels: Vec<Vec<Vec<u8>>>,
+}
+impl MerkleTree {
+    /// Creates a new empty tree with `hash`
+    pub fn new(hash: HashAlgorithm) -> Self {
+        Self { hash, levels: vec![Vec::new()] }
+    }
+
+    /// The hash function
+    pub fn hash(&self) -> HashAlgorithm {
+        self.hash
+    }
+
+    /// The number of leaves
+    pub fn len(&self) -> u64 {
+        self.levels[0].len() as u64
+    }
+
+    /// Whether the tree has no leaves
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// The leaf hash at `index`
+    pub fn leaf(&self, index: u64) -> Option<&[u8]> {
+        let index = usize::try_from(index).ok()?;
+        self.levels[0].get(index).map(Vec::as_slice)
+    }
+
+    /// Appends a leaf with `data` and returns its index
+    pub fn append(&mut self, data: &[u8]) -> u64 {
+        let index = self.len();
+        self.levels[0].push(leaf_hash(self.hash, data));
+        self.update_levels();
+        index
+    }
+
+    /// Appends a leaf for every entry in `entries`
+    ///
+    /// The leaves and the new interior nodes are hashed in batches, which is considerably faster for SHA-2 than
+    /// appending the leaves one by one.
+    pub fn append_many(&mut self, entries: &[&[u8]]) {
+        let inputs: Vec<Vec<u8>> = entries.iter().map(|entry| [&[LEAF_PREFIX], *entry].concat()).collect();
+        let leaves = digest_many(self.hash, &inputs);
+        self.levels[0].extend(leaves);
+        self.update_levels();
+    }
+
+    /// The root hash of the current tree
+    ///
+    /// The root of the empty tree is the hash of the empty string.
+    pub fn root(&self) -> Vec<u8> {
+        match self.len() {
+            0 => digest_parts(self.hash, &[]),
+            size => self.subtree_root(0, size),
+        }
+    }
+
+    /// The root hash of the tree as it was with the first `tree_size` leaves
+    pub fn root_at(&self, tree_size: u64) -> Result<Vec<u8>, Error> {
+        match tree_size {
+            0 => Ok(digest_parts(self.hash, &[])),
+            size if size <= self.len() => Ok(self.subtree_root(0, size)),
+            _ => Err(Error::InvalidLength),
+        }
+    }
+
+    /// Creates the proof that the leaf at `index` is included in the tree with the first `tree_size` leaves
+    pub fn inclusion_proof(&self, index: u64, tree_size: u64) -> Result<Vec<Vec<u8>>, Error> {
+        if index >= tree_size || tree_size > self.len() {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut proof = Vec::new();
+        self.path(index, 0, tree_size, &mut proof);
+        Ok(proof)
+    }
+
+    /// Creates the proof that the tree with the first `old_size` leaves is a prefix of the tree with the first
+    /// `new_size` leaves
+    pub fn consistency_proof(&self, old_size: u64, new_size: u64) -> Result<Vec<Vec<u8>>, Error> {
+        if old_size == 0 || old_size > new_size || new_size > self.len() {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut proof = Vec::new();
+        self.subproof(old_size, 0, new_size, true, &mut proof);
+        Ok(proof)
+    }
+
+    /// Hashes all interior nodes that have been completed by new leaves
+    fn update_levels(&mut self) {
+        let mut level = 0;
+        loop {
+            // Collect the newly completed pairs
+            let (complete, hashed) = (self.levels[level].len() / 2, self.levels.get(level + 1).map_or(0, Vec::len));
+            if complete == hashed {
+                return;
+            }
+            let inputs: Vec<Vec<u8>> = (hashed..complete)
+                .map(|i| {
+                    [&[NODE_PREFIX], self.levels[level][2 * i].as_slice(), &self.levels[level][2 * i + 1]].concat()
+                })
+                .collect();
+
+            // Hash the pairs into the next level
+            let nodes = digest_many(self.hash, &inputs);
+            match self.levels.get_mut(level + 1) {
+                Some(parents) => parents.extend(nodes),
+                None => self.levels.push(nodes),
+            }
+            level += 1;
+        }
+    }
+
+    /// Computes the root of the subtree over the leaves `start..end`
+    fn subtree_root(&self, start: u64, end: u64) -> Vec<u8> {
+        // Complete subtrees are always aligned to their size and can be looked up directly
+        let size = end - start;
+        if size.is_power_of_two() {
+            let level = size.trailing_zeros() as usize;
+            return self.levels[level][(start / size) as usize].clone();
+        }
+
+        let k = split(size);
+        node_hash(self.hash, &self.subtree_root(start, start + k), &self.subtree_root(start + k, end))
+    }
+
+    /// Computes `PATH(index, D[start:end])` from RFC 6962 into `proof`
+    fn path(&self, index: u64, start: u64, end: u64, proof: &mut Vec<Vec<u8>>) {
+        let size = end - start;
+        if size <= 1 {
+            return;
+        }
+
+        let k = split(size);
+        match index < k {
+            true => {
+                self.path(index, start, start + k, proof);
+                proof.push(self.subtree_root(start + k, end));
+            }
+            false => {
+                self.path(index - k, start + k, end, proof);
+                proof.push(self.subtree_root(start, start + k));
+            }
+        }
+    }
+
+    /// Computes `SUBPROOF(old_size, D[start:end], complete)` from RFC 6962 into `proof`
+    fn subproof(&self, old_size: u64, start: u64, end: u64, complete: bool, proof: &mut Vec<Vec<u8>>) {
+        let size = end - start;
+        if old_size == size {
+            if !complete {
+                proof.push(self.subtree_root(start, end));
+            }
+            return;
+        }
+
+        let k = split(size);
+        match old_size <= k {
+            true => {
+                self.subproof(old_size, start, start + k, complete, proof);
+                proof.push(self.subtree_root(start + k, end));
+            }
+            false => {
+                self.subproof(old_size - k, start + k, end, false, proof);
+                proof.push(self.subtree_root(start, start + k));
+            }
+        }
+    }
+}
+
+/// Verifies that `leaf_hash` is the leaf at `index` in the tree with `tree_size` leaves and `root`
+///
+/// This implements the verification algorithm from RFC 9162, section 2.1.3.2.
+pub fn verify_inclusion<P>(
+    hash: HashAlgorithm,
+    index: u64,
+    tree_size: u64,
+    leaf_hash: &[u8],
+    proof: &[P],
+    root: &[u8],
+) -> Result<(), Error>
+where
+    P: AsRef<[u8]>,
+{
+    if index >= tree_size {
+        return Err(Error::VerificationFailed);
+    }
+
+    // Walk up the tree from the leaf
+    let (mut node, mut last_node, mut r) = (index, tree_size - 1, leaf_hash.to_vec());
+    for p in proof {
+        let p = p.as_ref();
+        if last_node == 0 {
+            return Err(Error::VerificationFailed);
+        }
+        match node & 1 == 1 || node == last_node {
+            true => {
+                r = node_hash(hash, p, &r);
+                while node & 1 == 0 && node != 0 {
+                    (node, last_node) = (node >> 1, last_node >> 1);
+                }
+            }
+            false => r = node_hash(hash, &r, p),
+        }
+        (node, last_node) = (node >> 1, last_node >> 1);
+    }
+
+    match last_node == 0 && r == root {
+        true => Ok(()),
+        false => Err(Error::VerificationFailed),
+    }
+}
+
+/// Verifies that the tree with `old_size` leaves and `old_root` is a prefix of the tree with `new_size` leaves and
+/// `new_root`
+///
+/// This implements the verification algorithm from RFC 9162, section 2.1.4.2.
+pub fn verify_consistency<P>(
+    hash: HashAlgorithm,
+    old_size: u64,
+    new_size: u64,
+    old_root: &[u8],
+    new_root: &[u8],
+    proof: &[P],
+) -> Result<(), Error>
+where
+    P: AsRef<[u8]>,
+{
+    if old_size == 0 || old_size > new_size {
+        return Err(Error::VerificationFailed);
+    }
+
+    // Equal trees have an empty proof
+    if old_size == new_size {
+        return match proof.is_empty() && old_root == new_root {
+            true => Ok(()),
+            false => Err(Error::VerificationFailed),
+        };
+    }
+
+    // A complete old tree is the first node of the path
+    let mut path: Vec<&[u8]> = proof.iter().map(AsRef::as_ref).collect();
+    if old_size.is_power_of_two() {
+        path.insert(0, old_root);
+    }
+    let Some((first, path)) = path.split_first() else {
+        return Err(Error::VerificationFailed);
+    };
+
+    // Walk up both trees from the last leaf of the old tree
+    let (mut old_node, mut new_node) = (old_size - 1, new_size - 1);
+    while old_node & 1 == 1 {
+        (old_node, new_node) = (old_node >> 1, new_node >> 1);
+    }
+    let (mut fr, mut sr) = (first.to_vec(), first.to_vec());
+    for c in path {
+        if new_node == 0 {
+            return Err(Error::VerificationFailed);
+        }
+        match old_node & 1 == 1 || old_node == new_node {
+            true => {
+                fr = node_hash(hash, c, &fr);
+                sr = node_hash(hash, c, &sr);
+                while old_node & 1 == 0 && old_node != 0 {
+                    (old_node, new_node) = (old_node >> 1, new_node >> 1);
+                }
+            }
+            false => sr = node_hash(hash, &sr, c),
+        }
+        (old_node, new_node) = (old_node >> 1, new_node >> 1);
+    }
+
+    match new_node == 0 && fr == old_root && sr == new_root {
+        true => Ok(()),
+        false => Err(Error::VerificationFailed),
+    }
+}
+
+/// An append-only hash chain where every head commits to the previous head and the new entry
+///
+/// The initial head is all zeros; appending `entry` sets the head to `HASH(head || entry)`.
+#[derive(Debug, Clone)]
+pub struct HashChain {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The current head
+    head: Vec<u8>,
+    /// The number of entries
+    len: u64,
+}
+impl HashChain {
+    /// Creates a new empty chain with `hash`
+    pub fn new(hash: HashAlgorithm) -> Self {
+        Self { hash, head: vec![0; hash.digest_len()], len: 0 }
+    }
+
+    /// Resumes a chain with `len` entries from its `head`
+    pub fn from_head(hash: HashAlgorithm, head: &[u8], len: u64) -> Result<Self, Error> {
+        if head.len() != hash.digest_len() {
+            return Err(Error::InvalidLength);
+        }
+        Ok(Self { hash, head: head.to_vec(), len })
+    }
+
+    /// The hash function
+    pub fn hash(&self) -> HashAlgorithm {
+        self.hash
+    }
+
+    /// The current head
+    pub fn head(&self) -> &[u8] {
+        &self.head
+    }
+
+    /// The number of entries
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether the chain has no entries
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends `entry` and returns the new head
+    pub fn append(&mut self, entry: &[u8]) -> &[u8] {
+        self.head = digest_parts(self.hash, &[&self.head, entry]);
+        self.len += 1;
+        &self.head
+    }
+}
@@ -0,0 +1,423 @@
+//! NaCl `crypto_secretbox` and `crypto_box` (XSalsa20-Poly1305) in libsodium's formats
+//!
+//! The combined ("easy") format is `tag || ciphertext` as produced by libsodium's `crypto_secretbox_easy` and
+//! `crypto_box_easy`; the detached format returns the 16 byte tag separately. [`Box`] computes the shared key once on
+//! construction like `crypto_box_beforenm`, so sealing and opening only run the symmetric part like the `_afternm`
+//! functions. Nonces must never be reused with the same key; random [`Nonce`]s are safe to use for any amount of
+//! messages.
+//!
+//! [`box_seal`] and [`box_seal_open`] implement libsodium's sealed boxes (`crypto_box_seal`), which encrypt anonymously
+//! to a public key with an ephemeral sender key and a nonce derived from both public keys.
+
+use crate::{
+    secret::SecretArray,
+    x25519::{X25519PublicKey, X25519SecretKey, PUBLIC_KEY_LEN, SECRET_KEY_LEN},
+    Error,
+};
+use alloc::{vec, vec::Vec};
+use core::{
+    fmt::{self, Debug, Formatter},
+    ptr,
+};
+use evercrypt_tiny_sys::{
+    EverCrypt_Curve25519_secret_to_public, Hacl_Blake2b_32_blake2b, Hacl_NaCl_crypto_box_beforenm,
+    Hacl_NaCl_crypto_box_detached_afternm, Hacl_NaCl_crypto_box_easy, Hacl_NaCl_crypto_box_easy_afternm,
+    Hacl_NaCl_crypto_box_open_detached_afternm, Hacl_NaCl_crypto_box_open_easy, Hacl_NaCl_crypto_box_open_easy_afternm,
+    Hacl_NaCl_crypto_secretbox_detached, Hacl_NaCl_crypto_secretbox_easy, Hacl_NaCl_crypto_secretbox_open_detached,
+    Hacl_NaCl_crypto_secretbox_open_easy,
+};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The length of a secret box key or a precomputed box key
+pub const KEY_LEN: usize = 32;
+/// The length of a nonce
+pub const NONCE_LEN: usize = 24;
+/// The length of an authentication tag
+pub const TAG_LEN: usize = 16;
+/// The length that a sealed box adds to the plaintext, i.e. the ephemeral public key and the tag
+pub const SEAL_OVERHEAD: usize = PUBLIC_KEY_LEN + TAG_LEN;
+
+/// A 24 byte nonce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Nonce {
+    /// The nonce bytes
+    bytes: [u8; NONCE_LEN],
+}
+impl Nonce {
+    /// Generates a new random nonce
+    pub fn generate<R>(rng: &mut R) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut bytes = [0; NONCE_LEN];
+        rng.try_fill_bytes(&mut bytes).map_err(|_| Error::RngFailure)?;
+        Ok(Self { bytes })
+    }
+
+    /// Loads a nonce from its 24 byte representation
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes = bytes.try_into().map_err(|_| Error::InvalidLength)?;
+        Ok(Self { bytes })
+    }
+
+    /// Returns the 24 byte representation
+    pub const fn to_bytes(&self) -> [u8; NONCE_LEN] {
+        self.bytes
+    }
+
+    /// Increments the nonce as a little-endian integer like libsodium's `sodium_increment`, wrapping on overflow
+    pub fn increment(&mut self) {
+        for byte in self.bytes.iter_mut() {
+            let (incremented, carry) = byte.overflowing_add(1);
+            *byte = incremented;
+            if !carry {
+                break;
+            }
+        }
+    }
+}
+impl From<[u8; NONCE_LEN]> for Nonce {
+    fn from(bytes: [u8; NONCE_LEN]) -> Self {
+        Self { bytes }
+    }
+}
+
+/// Ensures that a message and its tag fit into a HACL* length and returns the message length
+fn message_len(message: &[u8]) -> Result<u32, Error> {
+    u32::try_from(message.len() + TAG_LEN).map_err(|_| Error::InvalidLength)?;
+    Ok(message.len() as u32)
+}
+
+/// A `crypto_secretbox` key
+#[derive(Clone)]
+pub struct SecretBox {
+    /// The key
+    key: SecretArray<KEY_LEN>,
+}
+impl SecretBox {
+    /// Generates a new random key
+    pub fn generate<R>(rng: &mut R) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut key = SecretArray::default();
+        rng.try_fill_bytes(&mut key[..]).map_err(|_| Error::RngFailure)?;
+        Ok(Self { key })
+    }
+
+    /// Loads a key from its 32 byte representation
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let key = SecretArray::from_slice(bytes).map_err(|_| Error::InvalidKey)?;
+        Ok(Self { key })
+    }
+
+    /// Returns the 32 byte representation
+    pub fn to_bytes(&self) -> SecretArray<KEY_LEN> {
+        self.key.clone()
+    }
+
+    /// Encrypts and authenticates `plaintext` into `tag || ciphertext` like `crypto_secretbox_easy`
+    pub fn seal(&self, nonce: &Nonce, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let plaintext_len = message_len(plaintext)?;
+        let (mut key, mut nonce, mut ciphertext) = (self.key.clone(), nonce.bytes, vec![0; plaintext.len() + TAG_LEN]);
+        unsafe {
+            Hacl_NaCl_crypto_secretbox_easy(
+                ciphertext.as_mut_ptr(),
+                plaintext.as_ptr().cast_mut(),
+                plaintext_len,
+                nonce.as_mut_ptr(),
+                key.as_mut_ptr(),
+            )
+        };
+        Ok(ciphertext)
+    }
+
+    /// Verifies and decrypts `tag || ciphertext` like `crypto_secretbox_open_easy`
+    pub fn open(&self, nonce: &Nonce, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        let ciphertext_len = u32::try_from(ciphertext.len()).map_err(|_| Error::InvalidLength)?;
+        let plaintext_len = ciphertext.len().checked_sub(TAG_LEN).ok_or(Error::InvalidLength)?;
+
+        let (mut key, mut nonce, mut plaintext) = (self.key.clone(), nonce.bytes, vec![0; plaintext_len]);
+        let result = unsafe {
+            Hacl_NaCl_crypto_secretbox_open_easy(
+                plaintext.as_mut_ptr(),
+                ciphertext.as_ptr().cast_mut(),
+                ciphertext_len,
+                nonce.as_mut_ptr(),
+                key.as_mut_ptr(),
+            )
+        };
+        match result {
+            0 => Ok(plaintext),
+            _ => Err(Error::DecryptionFailed),
+        }
+    }
+
+    /// Encrypts and authenticates `plaintext` into a ciphertext and a separate tag like `crypto_secretbox_detached`
+    pub fn seal_detached(&self, nonce: &Nonce, plaintext: &[u8]) -> Result<(Vec<u8>, [u8; TAG_LEN]), Error> {
+        let plaintext_len = message_len(plaintext)?;
+        let (mut key, mut nonce) = (self.key.clone(), nonce.bytes);
+        let (mut ciphertext, mut tag) = (vec![0; plaintext.len()], [0; TAG_LEN]);
+        unsafe {
+            Hacl_NaCl_crypto_secretbox_detached(
+                ciphertext.as_mut_ptr(),
+                tag.as_mut_ptr(),
+                plaintext.as_ptr().cast_mut(),
+                plaintext_len,
+                nonce.as_mut_ptr(),
+                key.as_mut_ptr(),
+            )
+        };
+        Ok((ciphertext, tag))
+    }
+
+    /// Verifies and decrypts `ciphertext` with a separate `tag` like `crypto_secretbox_open_detached`
+    pub fn open_detached(&self, nonce: &Nonce, ciphertext: &[u8], tag: &[u8; TAG_LEN]) -> Result<Vec<u8>, Error> {
+        let ciphertext_len = message_len(ciphertext)?;
+        let (mut key, mut nonce, mut tag) = (self.key.clone(), nonce.bytes, *tag);
+        let mut plaintext = vec![0; ciphertext.len()];
+        let result = unsafe {
+            Hacl_NaCl_crypto_secretbox_open_detached(
+                plaintext.as_mut_ptr(),
+                ciphertext.as_ptr().cast_mut(),
+                tag.as_mut_ptr(),
+                ciphertext_len,
+                nonce.as_mut_ptr(),
+                key.as_mut_ptr(),
+            )
+        };
+        match result {
+            0 => Ok(plaintext),
+            _ => Err(Error::DecryptionFailed),
+        }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for SecretBox {
+    fn zeroize(&mut self) {
+        self.key.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for SecretBox {}
+impl Debug for SecretBox {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("SecretBox").finish_non_exhaustive()
+    }
+}
+
+/// A `crypto_box` between a secret key and a peer's public key with a precomputed shared key
+#[derive(Clone)]
+pub struct Box {
+    /// The shared key as computed by `crypto_box_beforenm`
+    key: SecretArray<KEY_LEN>,
+}
+impl Box {
+    /// Precomputes the shared key between `secret_key` and `public_key` like `crypto_box_beforenm`
+    ///
+    /// This fails with [`Error::InvalidKey`] if the X25519 shared secret is all-zero, which happens for low-order
+    /// public keys.
+    pub fn new(secret_key: &X25519SecretKey, public_key: &X25519PublicKey) -> Result<Self, Error> {
+        let (mut secret_key, mut public_key) = (secret_key.to_bytes(), public_key.to_bytes());
+        let mut key = SecretArray::default();
+        let result = unsafe {
+            Hacl_NaCl_crypto_box_beforenm(key.as_mut_ptr(), public_key.as_mut_ptr(), secret_key.as_mut_ptr())
+        };
+        match result {
+            0 => Ok(Self { key }),
+            _ => Err(Error::InvalidKey),
+        }
+    }
+
+    /// Loads a precomputed shared key from its 32 byte representation
+    pub fn from_shared_key(bytes: &[u8]) -> Result<Self, Error> {
+        let key = SecretArray::from_slice(bytes).map_err(|_| Error::InvalidKey)?;
+        Ok(Self { key })
+    }
+
+    /// Returns the 32 byte representation of the precomputed shared key
+    pub fn to_shared_key(&self) -> SecretArray<KEY_LEN> {
+        self.key.clone()
+    }
+
+    /// Encrypts and authenticates `plaintext` into `tag || ciphertext` like `crypto_box_easy_afternm`
+    pub fn seal(&self, nonce: &Nonce, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let plaintext_len = message_len(plaintext)?;
+        let (mut key, mut nonce, mut ciphertext) = (self.key.clone(), nonce.bytes, vec![0; plaintext.len() + TAG_LEN]);
+        unsafe {
+            Hacl_NaCl_crypto_box_easy_afternm(
+                ciphertext.as_mut_ptr(),
+                plaintext.as_ptr().cast_mut(),
+                plaintext_len,
+                nonce.as_mut_ptr(),
+                key.as_mut_ptr(),
+            )
+        };
+        Ok(ciphertext)
+    }
+
+    /// Verifies and decrypts `tag || ciphertext` like `crypto_box_open_easy_afternm`
+    pub fn open(&self, nonce: &Nonce, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        let ciphertext_len = u32::try_from(ciphertext.len()).map_err(|_| Error::InvalidLength)?;
+        let plaintext_len = ciphertext.len().checked_sub(TAG_LEN).ok_or(Error::InvalidLength)?;
+
+        let (mut key, mut nonce, mut plaintext) = (self.key.clone(), nonce.bytes, vec![0; plaintext_len]);
+        let result = unsafe {
+            Hacl_NaCl_crypto_box_open_easy_afternm(
+                plaintext.as_mut_ptr(),
+                ciphertext.as_ptr().cast_mut(),
+                ciphertext_len,
+                nonce.as_mut_ptr(),
+                key.as_mut_ptr(),
+            )
+        };
+        match result {
+            0 => Ok(plaintext),
+            _ => Err(Error::DecryptionFailed),
+        }
+    }
+
+    /// Encrypts and authenticates `plaintext` into a ciphertext and a separate tag like `crypto_box_detached_afternm`
+    pub fn seal_detached(&self, nonce: &Nonce, plaintext: &[u8]) -> Result<(Vec<u8>, [u8; TAG_LEN]), Error> {
+        let plaintext_len = message_len(plaintext)?;
+        let (mut key, mut nonce) = (self.key.clone(), nonce.bytes);
+        let (mut ciphertext, mut tag) = (vec![0; plaintext.len()], [0; TAG_LEN]);
+        unsafe {
+            Hacl_NaCl_crypto_box_detached_afternm(
+                ciphertext.as_mut_ptr(),
+                tag.as_mut_ptr(),
+                plaintext.as_ptr().cast_mut(),
+                plaintext_len,
+                nonce.as_mut_ptr(),
+                key.as_mut_ptr(),
+            )
+        };
+        Ok((ciphertext, tag))
+    }
+
+    /// Verifies and decrypts `ciphertext` with a separate `tag` like `crypto_box_open_detached_afternm`
+    pub fn open_detached(&self, nonce: &Nonce, ciphertext: &[u8], tag: &[u8; TAG_LEN]) -> Result<Vec<u8>, Error> {
+        let ciphertext_len = message_len(ciphertext)?;
+        let (mut key, mut nonce, mut tag) = (self.key.clone(), nonce.bytes, *tag);
+        let mut plaintext = vec![0; ciphertext.len()];
+        let result = unsafe {
+            Hacl_NaCl_crypto_box_open_detached_afternm(
+                plaintext.as_mut_ptr(),
+                ciphertext.as_ptr().cast_mut(),
+                tag.as_mut_ptr(),
+                ciphertext_len,
+                nonce.as_mut_ptr(),
+                key.as_mut_ptr(),
+            )
+        };
+        match result {
+            0 => Ok(plaintext),
+            _ => Err(Error::DecryptionFailed),
+        }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for Box {
+    fn zeroize(&mut self) {
+        self.key.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for Box {}
+impl Debug for Box {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Box").finish_non_exhaustive()
+    }
+}
+
+/// Derives the sealed box nonce `BLAKE2b-192(ephemeral_public_key || recipient_public_key)`
+fn seal_nonce(ephemeral_public_key: &[u8; PUBLIC_KEY_LEN], recipient_public_key: &[u8; PUBLIC_KEY_LEN]) -> Nonce {
+    let mut input = [0; 2 * PUBLIC_KEY_LEN];
+    input[..PUBLIC_KEY_LEN].copy_from_slice(ephemeral_public_key);
+    input[PUBLIC_KEY_LEN..].copy_from_slice(recipient_public_key);
+
+    let mut bytes = [0; NONCE_LEN];
+    unsafe {
+        Hacl_Blake2b_32_blake2b(
+            bytes.len() as u32,
+            bytes.as_mut_ptr(),
+            input.len() as u32,
+            input.as_mut_ptr(),
+            0,
+            ptr::null_mut(),
+        )
+    };
+    Nonce { bytes }
+}
+
+/// Encrypts `plaintext` anonymously to `public_key` like `crypto_box_seal`
+///
+/// The sealed box is `ephemeral_public_key || tag || ciphertext`, i.e. [`SEAL_OVERHEAD`] bytes longer than
+/// `plaintext`. The ephemeral secret key is drawn from `rng` and erased afterwards, so not even the sender can open
+/// the box.
+///
+/// This fails with [`Error::InvalidKey`] if `public_key` is a low-order point.
+pub fn box_seal<R>(public_key: &X25519PublicKey, plaintext: &[u8], rng: &mut R) -> Result<Vec<u8>, Error>
+where
+    R: RngCore + CryptoRng,
+{
+    // Validate the length and generate the ephemeral key pair
+    u32::try_from(plaintext.len() + SEAL_OVERHEAD).map_err(|_| Error::InvalidLength)?;
+    let mut ephemeral_secret_key = SecretArray::<SECRET_KEY_LEN>::default();
+    rng.try_fill_bytes(&mut ephemeral_secret_key[..]).map_err(|_| Error::RngFailure)?;
+    let mut ephemeral_public_key = [0; PUBLIC_KEY_LEN];
+    unsafe {
+        EverCrypt_Curve25519_secret_to_public(ephemeral_public_key.as_mut_ptr(), ephemeral_secret_key.as_mut_ptr())
+    };
+
+    // Encrypt to the recipient
+    let mut public_key = public_key.to_bytes();
+    let mut nonce = seal_nonce(&ephemeral_public_key, &public_key).bytes;
+    let mut sealed = vec![0; plaintext.len() + SEAL_OVERHEAD];
+    let (header, ciphertext) = sealed.split_at_mut(PUBLIC_KEY_LEN);
+    header.copy_from_slice(&ephemeral_public_key);
+    let result = unsafe {
+        Hacl_NaCl_crypto_box_easy(
+            ciphertext.as_mut_ptr(),
+            plaintext.as_ptr().cast_mut(),
+            plaintext.len() as u32,
+            nonce.as_mut_ptr(),
+            public_key.as_mut_ptr(),
+            ephemeral_secret_key.as_mut_ptr(),
+        )
+    };
+    match result {
+        0 => Ok(sealed),
+        _ => Err(Error::InvalidKey),
+    }
+}
+
+/// Decrypts a sealed box that has been encrypted to the public key of `secret_key` like `crypto_box_seal_open`
+pub fn box_seal_open(secret_key: &X25519SecretKey, sealed: &[u8]) -> Result<Vec<u8>, Error> {
+    // Validate the length and split the ephemeral public key
+    let sealed_len = u32::try_from(sealed.len()).map_err(|_| Error::InvalidLength)?;
+    let plaintext_len = sealed.len().checked_sub(SEAL_OVERHEAD).ok_or(Error::InvalidLength)?;
+    let (ephemeral_public_key, ciphertext) = sealed.split_at(PUBLIC_KEY_LEN);
+    let mut ephemeral_public_key: [u8; PUBLIC_KEY_LEN] = ephemeral_public_key.try_into().expect("Invalid key length");
+
+    // Decrypt from the ephemeral public key
+    let mut nonce = seal_nonce(&ephemeral_public_key, &secret_key.public_key().to_bytes()).bytes;
+    let mut secret_key = secret_key.to_bytes();
+    let mut plaintext = vec![0; plaintext_len];
+    let result = unsafe {
+        Hacl_NaCl_crypto_box_open_easy(
+            plaintext.as_mut_ptr(),
+            ciphertext.as_ptr().cast_mut(),
+            sealed_len - PUBLIC_KEY_LEN as u32,
+            nonce.as_mut_ptr(),
+            ephemeral_public_key.as_mut_ptr(),
+            secret_key.as_mut_ptr(),
+        )
+    };
+    match result {
+        0 => Ok(plaintext),
+        _ => Err(Error::DecryptionFailed),
+    }
+}
@@ -0,0 +1,220 @@
+//! P-256 (secp256r1) key generation and key agreement
+//!
+//! Public keys are validated via `Hacl_P256_verify_q` whenever they are decoded, so every [`P256PublicKey`] is
+//! guaranteed to be a point on the curve that is not the point at infinity.
+
+use crate::{
+    secret::{self, SecretArray},
+    Error,
+};
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Formatter};
+use evercrypt_tiny_sys::{
+    EverCrypt_HKDF_expand_sha2_256, EverCrypt_HKDF_extract_sha2_256, Hacl_P256_compression_compressed_form,
+    Hacl_P256_compression_not_compressed_form, Hacl_P256_decompression_compressed_form,
+    Hacl_P256_decompression_not_compressed_form, Hacl_P256_ecp256dh_i, Hacl_P256_ecp256dh_r,
+    Hacl_P256_is_more_than_zero_less_than_order, Hacl_P256_verify_q,
+};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The length of a serialized secret key
+pub const SECRET_KEY_LEN: usize = 32;
+/// The length of a SEC1 compressed point
+pub const COMPRESSED_POINT_LEN: usize = 33;
+/// The length of a SEC1 uncompressed point
+pub const UNCOMPRESSED_POINT_LEN: usize = 65;
+/// The length of a shared secret
+pub const SHARED_SECRET_LEN: usize = 32;
+
+/// The length of the internal `x || y` point representation
+const RAW_POINT_LEN: usize = 64;
+/// The maximum amount of candidates drawn from an RNG before key generation is aborted
+///
+/// The probability that a uniformly random 256 bit string is not a valid scalar is below `2^-32`, so hitting this
+/// limit indicates a broken RNG.
+const MAX_GENERATE_ATTEMPTS: usize = 64;
+/// The HPKE suite ID of DHKEM(P-256, HKDF-SHA256) (`"KEM" || I2OSP(0x0010, 2)`)
+const DHKEM_SUITE_ID: &[u8] = b"KEM\x00\x10";
+
+/// A P-256 secret key
+#[derive(Clone)]
+pub struct P256SecretKey {
+    /// The big-endian scalar
+    scalar: SecretArray<SECRET_KEY_LEN>,
+}
+impl P256SecretKey {
+    /// Generates a new random secret key by rejection sampling from `rng`
+    pub fn generate<R>(rng: &mut R) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        for _ in 0..MAX_GENERATE_ATTEMPTS {
+            // Draw a candidate and retry if it is not within `[1, n)`
+            let mut candidate = SecretArray::<SECRET_KEY_LEN>::default();
+            rng.try_fill_bytes(&mut candidate[..]).map_err(|_| Error::RngFailure)?;
+            if let Ok(secret_key) = Self::from_bytes(&candidate[..]) {
+                return Ok(secret_key);
+            }
+        }
+        Err(Error::RngFailure)
+    }
+
+    /// Deterministically derives a secret key from the input keying material `ikm`
+    ///
+    /// This implements `DeriveKeyPair` of DHKEM(P-256, HKDF-SHA256) as specified in RFC 9180, section 7.1.3; `ikm`
+    /// should contain at least 32 bytes of entropy.
+    pub fn derive(ikm: &[u8]) -> Result<Self, Error> {
+        // Extract the pseudorandom key (`LabeledExtract("", "dkp_prk", ikm)`)
+        let mut labeled_ikm =
+            Vec::with_capacity(b"HPKE-v1".len() + DHKEM_SUITE_ID.len() + b"dkp_prk".len() + ikm.len());
+        labeled_ikm.extend_from_slice(b"HPKE-v1");
+        labeled_ikm.extend_from_slice(DHKEM_SUITE_ID);
+        labeled_ikm.extend_from_slice(b"dkp_prk");
+        labeled_ikm.extend_from_slice(ikm);
+        let ikm_len = u32::try_from(labeled_ikm.len()).map_err(|_| Error::InvalidKey)?;
+
+        let (mut salt, mut prk) = ([0; 0], SecretArray::<32>::default());
+        unsafe {
+            EverCrypt_HKDF_extract_sha2_256(prk.as_mut_ptr(), salt.as_mut_ptr(), 0, labeled_ikm.as_mut_ptr(), ikm_len)
+        };
+        secret::memzero(&mut labeled_ikm);
+
+        for counter in 0..=u8::MAX {
+            // Expand the next candidate (`LabeledExpand(dkp_prk, "candidate", I2OSP(counter, 1), 32)`)
+            let mut labeled_info = [0; 2 + 7 + 5 + 9 + 1];
+            labeled_info[..2].copy_from_slice(&(SECRET_KEY_LEN as u16).to_be_bytes());
+            labeled_info[2..9].copy_from_slice(b"HPKE-v1");
+            labeled_info[9..14].copy_from_slice(DHKEM_SUITE_ID);
+            labeled_info[14..23].copy_from_slice(b"candidate");
+            labeled_info[23] = counter;
+
+            let mut candidate = SecretArray::<SECRET_KEY_LEN>::default();
+            unsafe {
+                EverCrypt_HKDF_expand_sha2_256(
+                    candidate.as_mut_ptr(),
+                    prk.as_mut_ptr(),
+                    prk.len() as u32,
+                    labeled_info.as_mut_ptr(),
+                    labeled_info.len() as u32,
+                    candidate.len() as u32,
+                )
+            };
+
+            // Retry if the candidate is not within `[1, n)`
+            if let Ok(secret_key) = Self::from_bytes(&candidate[..]) {
+                return Ok(secret_key);
+            }
+        }
+        Err(Error::OperationFailed)
+    }
+
+    /// Loads a secret key from its big-endian scalar representation
+    ///
+    /// The scalar must be within `[1, n)` where `n` is the order of the curve.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        // Validate the length and range
+        let mut scalar = SecretArray::<SECRET_KEY_LEN>::from_slice(bytes).map_err(|_| Error::InvalidKey)?;
+        let is_valid = unsafe { Hacl_P256_is_more_than_zero_less_than_order(scalar.as_mut_ptr()) };
+        match is_valid {
+            true => Ok(Self { scalar }),
+            false => Err(Error::InvalidKey),
+        }
+    }
+
+    /// Returns the big-endian scalar representation
+    pub fn to_bytes(&self) -> SecretArray<SECRET_KEY_LEN> {
+        self.scalar.clone()
+    }
+
+    /// Computes the public key that belongs to this secret key
+    pub fn public_key(&self) -> P256PublicKey {
+        // Multiply the base point with the scalar
+        let (mut scalar, mut point) = (self.scalar.clone(), [0; RAW_POINT_LEN]);
+        let is_ok = unsafe { Hacl_P256_ecp256dh_i(point.as_mut_ptr(), scalar.as_mut_ptr()) };
+        debug_assert!(is_ok, "A scalar within [1, n) cannot yield the point at infinity");
+        P256PublicKey { point }
+    }
+
+    /// Performs an ECDH key agreement with `public_key` and returns the x-coordinate of the shared point
+    pub fn ecdh(&self, public_key: &P256PublicKey) -> Result<SecretArray<SHARED_SECRET_LEN>, Error> {
+        // Perform the scalar multiplication
+        let (mut scalar, mut point) = (self.scalar.clone(), public_key.point);
+        let mut shared = SecretArray::<RAW_POINT_LEN>::default();
+        let is_ok = unsafe { Hacl_P256_ecp256dh_r(shared.as_mut_ptr(), point.as_mut_ptr(), scalar.as_mut_ptr()) };
+        if !is_ok {
+            return Err(Error::OperationFailed);
+        }
+
+        // Return the x-coordinate
+        SecretArray::from_slice(&shared[..SHARED_SECRET_LEN])
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for P256SecretKey {
+    fn zeroize(&mut self) {
+        self.scalar.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for P256SecretKey {}
+impl Debug for P256SecretKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("P256SecretKey").finish_non_exhaustive()
+    }
+}
+
+/// A validated P-256 public key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct P256PublicKey {
+    /// The point in the internal `x || y` representation
+    point: [u8; RAW_POINT_LEN],
+}
+impl P256PublicKey {
+    /// Decodes and validates a SEC1 encoded point in either compressed or uncompressed form
+    pub fn from_sec1(bytes: &[u8]) -> Result<Self, Error> {
+        // Decode the point
+        let mut point = [0; RAW_POINT_LEN];
+        let is_ok = match bytes {
+            [0x02 | 0x03, ..] if bytes.len() == COMPRESSED_POINT_LEN => {
+                let mut compressed = [0; COMPRESSED_POINT_LEN];
+                compressed.copy_from_slice(bytes);
+                unsafe { Hacl_P256_decompression_compressed_form(compressed.as_mut_ptr(), point.as_mut_ptr()) }
+            }
+            [0x04, ..] if bytes.len() == UNCOMPRESSED_POINT_LEN => {
+                let mut uncompressed = [0; UNCOMPRESSED_POINT_LEN];
+                uncompressed.copy_from_slice(bytes);
+                unsafe { Hacl_P256_decompression_not_compressed_form(uncompressed.as_mut_ptr(), point.as_mut_ptr()) }
+            }
+            _ => return Err(Error::InvalidEncoding),
+        };
+        if !is_ok {
+            return Err(Error::InvalidEncoding);
+        }
+        Self::from_raw(point)
+    }
+
+    /// Validates a point in the internal `x || y` representation
+    pub(crate) fn from_raw(mut point: [u8; RAW_POINT_LEN]) -> Result<Self, Error> {
+        let is_valid = unsafe { Hacl_P256_verify_q(point.as_mut_ptr()) };
+        match is_valid {
+            true => Ok(Self { point }),
+            false => Err(Error::InvalidKey),
+        }
+    }
+
+    /// Encodes the point in SEC1 compressed form
+    pub fn to_compressed(&self) -> [u8; COMPRESSED_POINT_LEN] {
+        let (mut point, mut compressed) = (self.point, [0; COMPRESSED_POINT_LEN]);
+        unsafe { Hacl_P256_compression_compressed_form(point.as_mut_ptr(), compressed.as_mut_ptr()) };
+        compressed
+    }
+
+    /// Encodes the point in SEC1 uncompressed form
+    pub fn to_uncompressed(&self) -> [u8; UNCOMPRESSED_POINT_LEN] {
+        let (mut point, mut uncompressed) = (self.point, [0; UNCOMPRESSED_POINT_LEN]);
+        unsafe { Hacl_P256_compression_not_compressed_form(point.as_mut_ptr(), uncompressed.as_mut_ptr()) };
+        uncompressed
+    }
+}
@@ -0,0 +1,93 @@
+//! Ed25519 and X25519 keys as specified in RFC 8410
+
+use crate::{
+    ed25519::{Ed25519PublicKey, Ed25519SecretKey},
+    pkcs::{
+        decode_spki,
+        der::{self, Reader, Writer},
+        encode_spki, DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey, PrivateKeyInfo,
+    },
+    secret::SecretBytes,
+    x25519::{X25519PublicKey, X25519SecretKey},
+    Error,
+};
+use alloc::vec::Vec;
+
+/// The `AlgorithmIdentifier` contents of `id-Ed25519` (1.3.101.112) without parameters
+const ED25519_ALGORITHM: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x70];
+/// The `AlgorithmIdentifier` contents of `id-X25519` (1.3.101.110) without parameters
+const X25519_ALGORITHM: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x6e];
+
+/// Decodes a PKCS#8 private key and returns the contents of the `CurvePrivateKey` and the optional public key
+fn decode_private_key<'a>(der: &'a [u8], algorithm: &[u8]) -> Result<(&'a [u8], Option<&'a [u8]>), Error> {
+    let info = PrivateKeyInfo::decode(der, algorithm)?;
+    let mut outer = Reader::new(info.private_key);
+    let private_key = outer.read(der::OCTET_STRING)?;
+    outer.finish()?;
+    Ok((private_key, info.public_key))
+}
+
+/// Encodes a `CurvePrivateKey` as PKCS#8 private key
+fn encode_private_key(algorithm: &[u8], private_key: &[u8]) -> SecretBytes {
+    let mut curve_private_key = Writer::new();
+    curve_private_key.write(der::OCTET_STRING, private_key);
+    PrivateKeyInfo::encode(algorithm, &curve_private_key.into_secret())
+}
+
+impl DecodePrivateKey for Ed25519SecretKey {
+    fn from_pkcs8_der(der: &[u8]) -> Result<Self, Error> {
+        // Decode the key and ensure that an embedded public key belongs to it
+        let (private_key, public_key) = decode_private_key(der, ED25519_ALGORITHM)?;
+        let secret_key = Self::from_bytes(private_key)?;
+        match public_key {
+            Some(public_key) if public_key != secret_key.public_key().to_bytes() => Err(Error::InvalidKey),
+            _ => Ok(secret_key),
+        }
+    }
+}
+impl EncodePrivateKey for Ed25519SecretKey {
+    fn to_pkcs8_der(&self) -> Result<SecretBytes, Error> {
+        let private_key = self.to_bytes();
+        Ok(encode_private_key(ED25519_ALGORITHM, &private_key[..]))
+    }
+}
+impl DecodePublicKey for Ed25519PublicKey {
+    fn from_public_key_der(der: &[u8]) -> Result<Self, Error> {
+        let public_key = decode_spki(der, ED25519_ALGORITHM)?;
+        Self::from_bytes(public_key)
+    }
+}
+impl EncodePublicKey for Ed25519PublicKey {
+    fn to_public_key_der(&self) -> Result<Vec<u8>, Error> {
+        Ok(encode_spki(ED25519_ALGORITHM, &self.to_bytes()))
+    }
+}
+
+impl DecodePrivateKey for X25519SecretKey {
+    fn from_pkcs8_der(der: &[u8]) -> Result<Self, Error> {
+        // Decode the key and ensure that an embedded public key belongs to it
+        let (private_key, public_key) = decode_private_key(der, X25519_ALGORITHM)?;
+        let secret_key = Self::from_bytes(private_key)?;
+        match public_key {
+            Some(public_key) if public_key != secret_key.public_key().to_bytes() => Err(Error::InvalidKey),
+            _ => Ok(secret_key),
+        }
+    }
+}
+impl EncodePrivateKey for X25519SecretKey {
+    fn to_pkcs8_der(&self) -> Result<SecretBytes, Error> {
+        let private_key = self.to_bytes();
+        Ok(encode_private_key(X25519_ALGORITHM, &private_key[..]))
+    }
+}
+impl DecodePublicKey for X25519PublicKey {
+    fn from_public_key_der(der: &[u8]) -> Result<Self, Error> {
+        let public_key = decode_spki(der, X25519_ALGORITHM)?;
+        Self::from_bytes(public_key)
+    }
+}
+impl EncodePublicKey for X25519PublicKey {
+    fn to_public_key_der(&self) -> Result<Vec<u8>, Error> {
+        Ok(encode_spki(X25519_ALGORITHM, &self.to_bytes()))
+    }
+}
@@ -0,0 +1,218 @@
+//! A minimal DER reader and writer covering the subset of ASN.1 used by key containers
+
+use crate::{
+    secret::{self, SecretBytes},
+    Error,
+};
+use alloc::vec::Vec;
+use core::mem;
+
+/// The `INTEGER` tag
+pub const INTEGER: u8 = 0x02;
+/// The `BIT STRING` tag
+pub const BIT_STRING: u8 = 0x03;
+/// The `OCTET STRING` tag
+pub const OCTET_STRING: u8 = 0x04;
+/// The `SEQUENCE` tag
+pub const SEQUENCE: u8 = 0x30;
+/// The constructed context-specific tag `[0]`
+pub const CONTEXT_0: u8 = 0xa0;
+/// The constructed context-specific tag `[1]`
+pub const CONTEXT_1: u8 = 0xa1;
+/// The primitive context-specific tag `[1]`
+pub const CONTEXT_1_PRIMITIVE: u8 = 0x81;
+
+/// Strips the unused-bits prefix from the value of a `BIT STRING` and ensures that all bits are used
+pub fn bits(value: &[u8]) -> Result<&[u8], Error> {
+    match value {
+        [0x00, bits @ ..] => Ok(bits),
+        _ => Err(Error::InvalidEncoding),
+    }
+}
+
+/// A DER reader
+#[derive(Debug, Clone, Copy)]
+pub struct Reader<'a> {
+    /// The remaining data
+    data: &'a [u8],
+}
+impl<'a> Reader<'a> {
+    /// Creates a new reader over `data`
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Reads an element with the given tag and returns its value
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8], Error> {
+        self.read_optional(tag)?.ok_or(Error::InvalidEncoding)
+    }
+    /// Reads an element with the given tag if it is the next element and returns its value
+    pub fn read_optional(&mut self, tag: u8) -> Result<Option<&'a [u8]>, Error> {
+        // Peek at the tag
+        match self.data.first() {
+            Some(next) if *next == tag => (),
+            _ => return Ok(None),
+        }
+
+        // Decode the length
+        let (len, header_len) = match self.data.get(1).copied().ok_or(Error::InvalidEncoding)? {
+            len @ 0x00..=0x7f => (len as usize, 2),
+            len_len @ 0x81..=0x84 => {
+                let len_len = (len_len & 0x7f) as usize;
+                let len_bytes = self.data.get(2..2 + len_len).ok_or(Error::InvalidEncoding)?;
+                let len = len_bytes.iter().fold(0, |len, byte| (len << 8) | *byte as usize);
+
+                // Reject non-minimal length encodings
+                if len < 0x80 || len_bytes[0] == 0 {
+                    return Err(Error::InvalidEncoding);
+                }
+                (len, 2 + len_len)
+            }
+            _ => return Err(Error::InvalidEncoding),
+        };
+
+        // Split the value
+        let end = header_len.checked_add(len).ok_or(Error::InvalidEncoding)?;
+        let value = self.data.get(header_len..end).ok_or(Error::InvalidEncoding)?;
+        self.data = &self.data[end..];
+        Ok(Some(value))
+    }
+
+    /// Reads a `SEQUENCE` and returns a reader over its contents
+    pub fn read_sequence(&mut self) -> Result<Reader<'a>, Error> {
+        self.read(SEQUENCE).map(Reader::new)
+    }
+
+    /// Reads a non-negative `INTEGER` and returns its big-endian magnitude without leading zero bytes
+    pub fn read_uint(&mut self) -> Result<&'a [u8], Error> {
+        let value = self.read(INTEGER)?;
+        match value {
+            [] => Err(Error::InvalidEncoding),
+            [first, ..] if *first >= 0x80 => Err(Error::InvalidEncoding),
+            [0x00] => Ok(&[]),
+            [0x00, second, ..] if *second < 0x80 => Err(Error::InvalidEncoding),
+            [0x00, rest @ ..] => Ok(rest),
+            value => Ok(value),
+        }
+    }
+    /// Reads a small non-negative `INTEGER` like a version number
+    pub fn read_small_uint(&mut self) -> Result<u8, Error> {
+        match self.read_uint()? {
+            [] => Ok(0),
+            [value] => Ok(*value),
+            _ => Err(Error::InvalidEncoding),
+        }
+    }
+
+    /// Reads a `BIT STRING` without unused bits and returns its contents
+    pub fn read_bit_string(&mut self) -> Result<&'a [u8], Error> {
+        self.read(BIT_STRING).and_then(bits)
+    }
+
+    /// Ensures that all data has been consumed
+    pub fn finish(self) -> Result<(), Error> {
+        match self.data.is_empty() {
+            true => Ok(()),
+            false => Err(Error::InvalidEncoding),
+        }
+    }
+}
+
+/// A DER writer
+///
+/// Private keys are encoded with this writer as well, so its buffers are erased before they are released, including the
+/// ones that are left behind when the output grows.
+#[derive(Debug, Default)]
+pub struct Writer {
+    /// The encoded data
+    buf: Vec<u8>,
+}
+impl Writer {
+    /// Creates a new empty writer
+    pub fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+
+    /// Ensures that `additional` bytes can be appended without a reallocation that would leave an unerased copy behind
+    fn reserve(&mut self, additional: usize) {
+        if self.buf.capacity() - self.buf.len() < additional {
+            let mut buf = Vec::with_capacity(usize::max(2 * self.buf.capacity(), self.buf.len() + additional));
+            buf.extend_from_slice(&self.buf);
+            secret::memzero(&mut self.buf);
+            self.buf = buf;
+        }
+    }
+
+    /// Writes an element with the given tag and value
+    pub fn write(&mut self, tag: u8, value: &[u8]) -> &mut Self {
+        // Write the tag and length
+        self.reserve(2 + mem::size_of::<usize>() + value.len());
+        self.buf.push(tag);
+        match value.len() {
+            len @ 0x00..=0x7f => self.buf.push(len as u8),
+            len => {
+                let len_bytes = len.to_be_bytes();
+                let start = len_bytes.iter().position(|byte| *byte != 0).unwrap_or(len_bytes.len());
+                self.buf.push(0x80 | (len_bytes.len() - start) as u8);
+                self.buf.extend_from_slice(&len_bytes[start..]);
+            }
+        }
+
+        // Write the value
+        self.buf.extend_from_slice(value);
+        self
+    }
+    /// Writes a constructed element with the given tag whose contents are produced by `contents`
+    pub fn write_constructed<F>(&mut self, tag: u8, contents: F) -> &mut Self
+    where
+        F: FnOnce(&mut Writer),
+    {
+        let mut inner = Writer::new();
+        contents(&mut inner);
+        self.write(tag, &inner.buf)
+    }
+    /// Writes a `SEQUENCE` whose contents are produced by `contents`
+    pub fn write_sequence<F>(&mut self, contents: F) -> &mut Self
+    where
+        F: FnOnce(&mut Writer),
+    {
+        self.write_constructed(SEQUENCE, contents)
+    }
+
+    /// Writes a non-negative `INTEGER` from its big-endian magnitude
+    pub fn write_uint(&mut self, magnitude: &[u8]) -> &mut Self {
+        let start = magnitude.iter().position(|byte| *byte != 0).unwrap_or(magnitude.len());
+        let magnitude = &magnitude[start..];
+        match magnitude.first() {
+            None => self.write(INTEGER, &[0x00]),
+            Some(first) if *first >= 0x80 => {
+                let mut value = SecretBytes::new(magnitude.len() + 1);
+                value[1..].copy_from_slice(magnitude);
+                self.write(INTEGER, &value)
+            }
+            Some(_) => self.write(INTEGER, magnitude),
+        }
+    }
+
+    /// Writes a `BIT STRING` without unused bits
+    pub fn write_bit_string(&mut self, bits: &[u8]) -> &mut Self {
+        let mut value = Vec::with_capacity(bits.len() + 1);
+        value.push(0x00);
+        value.extend_from_slice(bits);
+        self.write(BIT_STRING, &value)
+    }
+
+    /// Returns the encoded data
+    pub fn into_vec(mut self) -> Vec<u8> {
+        mem::take(&mut self.buf)
+    }
+    /// Returns the encoded data as secret
+    pub fn into_secret(mut self) -> SecretBytes {
+        SecretBytes::from(mem::take(&mut self.buf))
+    }
+}
+impl Drop for Writer {
+    fn drop(&mut self) {
+        secret::memzero(&mut self.buf);
+    }
+}
@@ -0,0 +1,83 @@
+//! P-256 keys as specified in RFC 5480 and RFC 5915
+
+use crate::{
+    p256::{P256PublicKey, P256SecretKey, SECRET_KEY_LEN},
+    pkcs::{
+        decode_spki,
+        der::{self, Reader, Writer},
+        encode_spki, DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey, PrivateKeyInfo,
+    },
+    secret::SecretBytes,
+    Error,
+};
+use alloc::vec::Vec;
+
+/// The DER encoded `prime256v1` object identifier (1.2.840.10045.3.1.7)
+const CURVE: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+/// The `AlgorithmIdentifier` contents of `id-ecPublicKey` (1.2.840.10045.2.1) with `prime256v1` parameters
+const ALGORITHM: &[u8] =
+    &[0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+impl DecodePrivateKey for P256SecretKey {
+    fn from_pkcs8_der(der: &[u8]) -> Result<Self, Error> {
+        // Decode the `ECPrivateKey` structure
+        let info = PrivateKeyInfo::decode(der, ALGORITHM)?;
+        let mut outer = Reader::new(info.private_key);
+        let mut key = outer.read_sequence()?;
+        outer.finish()?;
+
+        // Decode the fields
+        if key.read_small_uint()? != 1 {
+            return Err(Error::InvalidEncoding);
+        }
+        let secret_key = match key.read(der::OCTET_STRING)? {
+            scalar if scalar.len() == SECRET_KEY_LEN => Self::from_bytes(scalar)?,
+            _ => return Err(Error::InvalidEncoding),
+        };
+        if let Some(parameters) = key.read_optional(der::CONTEXT_0)? {
+            if parameters != CURVE {
+                return Err(Error::UnsupportedAlgorithm);
+            }
+        }
+        let public_key = match key.read_optional(der::CONTEXT_1)? {
+            Some(public_key) => Some(Reader::new(public_key).read_bit_string()?),
+            None => None,
+        };
+        key.finish()?;
+
+        // Ensure that embedded public keys belong to the secret key
+        for public_key in public_key.into_iter().chain(info.public_key) {
+            if P256PublicKey::from_sec1(public_key)? != secret_key.public_key() {
+                return Err(Error::InvalidKey);
+            }
+        }
+        Ok(secret_key)
+    }
+}
+impl EncodePrivateKey for P256SecretKey {
+    fn to_pkcs8_der(&self) -> Result<SecretBytes, Error> {
+        // Encode the `ECPrivateKey` structure with the public key but without the implicit curve parameters
+        let scalar = self.to_bytes();
+        let mut private_key = Writer::new();
+        private_key.write_sequence(|key| {
+            key.write_uint(&[1]);
+            key.write(der::OCTET_STRING, &scalar[..]);
+            key.write_constructed(der::CONTEXT_1, |public_key| {
+                public_key.write_bit_string(&self.public_key().to_uncompressed());
+            });
+        });
+        Ok(PrivateKeyInfo::encode(ALGORITHM, &private_key.into_secret()))
+    }
+}
+
+impl DecodePublicKey for P256PublicKey {
+    fn from_public_key_der(der: &[u8]) -> Result<Self, Error> {
+        let public_key = decode_spki(der, ALGORITHM)?;
+        Self::from_sec1(public_key)
+    }
+}
+impl EncodePublicKey for P256PublicKey {
+    fn to_public_key_der(&self) -> Result<Vec<u8>, Error> {
+        Ok(encode_spki(ALGORITHM, &self.to_uncompressed()))
+    }
+}
@@ -0,0 +1,93 @@
+//! PEM armor as specified in RFC 7468
+//!
+//! Private keys pass through here as well, so no buffer is ever grown: every buffer is allocated with its final capacity
+//! up front and the intermediate ones are erased before they are released.
+
+use crate::{secret::SecretBytes, Error};
+use alloc::{string::String, vec::Vec};
+
+/// The base64 alphabet
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+/// The amount of base64 characters per line
+const LINE_LEN: usize = 64;
+
+/// Decodes the PEM document `pem` with the given `label`
+pub fn decode(pem: &str, label: &str) -> Result<SecretBytes, Error> {
+    // Strip the encapsulation boundaries
+    let body = pem.trim();
+    let body = body.strip_prefix("-----BEGIN ").ok_or(Error::InvalidEncoding)?;
+    let body = body.strip_prefix(label).ok_or(Error::InvalidEncoding)?;
+    let body = body.strip_prefix("-----").ok_or(Error::InvalidEncoding)?;
+    let body = body.strip_suffix("-----").ok_or(Error::InvalidEncoding)?;
+    let body = body.strip_suffix(label).ok_or(Error::InvalidEncoding)?;
+    let body = body.strip_suffix("-----END ").ok_or(Error::InvalidEncoding)?;
+
+    // Collect the base64 characters
+    let mut chars = Vec::with_capacity(body.len());
+    chars.extend(body.bytes().filter(|char| !char.is_ascii_whitespace()));
+    let chars = SecretBytes::from(chars);
+    if !chars.len().is_multiple_of(4) {
+        return Err(Error::InvalidEncoding);
+    }
+
+    // Decode the base64 body
+    let (mut decoded, mut decoded_len) = (SecretBytes::new(chars.len() / 4 * 3), 0);
+    for (index, quad) in chars.chunks_exact(4).enumerate() {
+        let is_last = index == chars.len() / 4 - 1;
+        let padding = match quad {
+            [_, _, b'=', b'='] if is_last => 2,
+            [_, _, _, b'='] if is_last => 1,
+            _ => 0,
+        };
+
+        // Decode the sextets and reject non-canonical trailing bits
+        let mut block = 0u32;
+        for char in &quad[..4 - padding] {
+            let sextet = ALPHABET.iter().position(|candidate| candidate == char).ok_or(Error::InvalidEncoding)?;
+            block = (block << 6) | sextet as u32;
+        }
+        block <<= 6 * padding as u32;
+        if block & ((1 << (8 * padding as u32)) - 1) != 0 {
+            return Err(Error::InvalidEncoding);
+        }
+        decoded[decoded_len..decoded_len + 3 - padding].copy_from_slice(&block.to_be_bytes()[1..4 - padding]);
+        decoded_len += 3 - padding;
+    }
+    Ok(SecretBytes::from_slice(&decoded[..decoded_len]))
+}
+
+/// Encodes `der` as PEM document with the given `label`
+pub fn encode(der: &[u8], label: &str) -> String {
+    // Allocate the final document
+    let base64_len = der.len().div_ceil(3) * 4;
+    let pem_len =
+        2 * label.len() + "-----BEGIN -----\n-----END -----\n".len() + base64_len + base64_len.div_ceil(LINE_LEN);
+    let mut pem = String::with_capacity(pem_len);
+    pem.push_str("-----BEGIN ");
+    pem.push_str(label);
+    pem.push_str("-----\n");
+
+    // Encode the data as base64 and wrap the lines
+    for (index, chunk) in der.chunks(3).enumerate() {
+        let mut block = [0; 4];
+        block[1..1 + chunk.len()].copy_from_slice(chunk);
+        let block = u32::from_be_bytes(block);
+
+        for sextet in 0..4 {
+            match sextet <= chunk.len() {
+                true => pem.push(ALPHABET[(block >> (18 - 6 * sextet) & 0x3f) as usize] as char),
+                false => pem.push('='),
+            }
+        }
+        if (index + 1) * 4 % LINE_LEN == 0 || (index + 1) * 4 == base64_len {
+            pem.push('\n');
+        }
+    }
+
+    // Add the closing encapsulation boundary
+    pem.push_str("-----END ");
+    pem.push_str(label);
+    pem.push_str("-----\n");
+    debug_assert_eq!(pem.len(), pem_len, "The document has been reallocated");
+    pem
+}
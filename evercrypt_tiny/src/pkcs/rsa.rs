@@ -0,0 +1,108 @@
+//! RSA keys as specified in RFC 8017, appendix A.1
+
+use crate::{
+    pkcs::{
+        decode_spki,
+        der::{Reader, Writer},
+        encode_spki, DecodePrivateKey, DecodePublicKey, DecodeRsaPrivateKey, DecodeRsaPublicKey, EncodePrivateKey,
+        EncodePublicKey, EncodeRsaPrivateKey, EncodeRsaPublicKey, PrivateKeyInfo,
+    },
+    rsa::{RsaCrtComponents, RsaPrivateKey, RsaPublicKey},
+    secret::SecretBytes,
+    Error,
+};
+use alloc::vec::Vec;
+
+/// The `AlgorithmIdentifier` contents of `rsaEncryption` (1.2.840.113549.1.1.1) with `NULL` parameters
+const ALGORITHM: &[u8] = &[0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01, 0x05, 0x00];
+
+impl DecodeRsaPrivateKey for RsaPrivateKey {
+    fn from_pkcs1_der(der: &[u8]) -> Result<Self, Error> {
+        // Decode the outer structure
+        let mut outer = Reader::new(der);
+        let mut key = outer.read_sequence()?;
+        outer.finish()?;
+
+        // Decode the two-prime key
+        if key.read_small_uint()? != 0 {
+            return Err(Error::UnsupportedAlgorithm);
+        }
+        let (n, e, d) = (key.read_uint()?, key.read_uint()?, key.read_uint()?);
+        let (p, q) = (key.read_uint()?.to_vec(), key.read_uint()?.to_vec());
+        let (dp, dq, qinv) = (key.read_uint()?.to_vec(), key.read_uint()?.to_vec(), key.read_uint()?.to_vec());
+        key.finish()?;
+
+        // Load the key and validate the CRT components
+        let private_key = Self::from_components(n, e, d)?;
+        private_key.with_crt_components(RsaCrtComponents { p, q, dp, dq, qinv })
+    }
+}
+impl EncodeRsaPrivateKey for RsaPrivateKey {
+    /// Encodes the private key as DER encoded PKCS#1
+    ///
+    /// This fails with [`Error::InvalidKey`] if the key has no CRT components attached.
+    fn to_pkcs1_der(&self) -> Result<SecretBytes, Error> {
+        let crt = self.crt_components().ok_or(Error::InvalidKey)?;
+        let public_key = self.public_key();
+
+        let mut writer = Writer::new();
+        writer.write_sequence(|key| {
+            key.write_uint(&[0]);
+            key.write_uint(public_key.modulus()).write_uint(public_key.public_exponent());
+            key.write_uint(self.private_exponent());
+            key.write_uint(&crt.p).write_uint(&crt.q);
+            key.write_uint(&crt.dp).write_uint(&crt.dq).write_uint(&crt.qinv);
+        });
+        Ok(writer.into_secret())
+    }
+}
+impl DecodePrivateKey for RsaPrivateKey {
+    fn from_pkcs8_der(der: &[u8]) -> Result<Self, Error> {
+        let info = PrivateKeyInfo::decode(der, ALGORITHM)?;
+        Self::from_pkcs1_der(info.private_key)
+    }
+}
+impl EncodePrivateKey for RsaPrivateKey {
+    /// Encodes the private key as DER encoded PKCS#8
+    ///
+    /// This fails with [`Error::InvalidKey`] if the key has no CRT components attached.
+    fn to_pkcs8_der(&self) -> Result<SecretBytes, Error> {
+        let private_key = self.to_pkcs1_der()?;
+        Ok(PrivateKeyInfo::encode(ALGORITHM, &private_key))
+    }
+}
+
+impl DecodeRsaPublicKey for RsaPublicKey {
+    fn from_pkcs1_der(der: &[u8]) -> Result<Self, Error> {
+        // Decode the outer structure
+        let mut outer = Reader::new(der);
+        let mut key = outer.read_sequence()?;
+        outer.finish()?;
+
+        // Decode and load the key
+        let (n, e) = (key.read_uint()?, key.read_uint()?);
+        key.finish()?;
+        Self::from_components(n, e)
+    }
+}
+impl EncodeRsaPublicKey for RsaPublicKey {
+    fn to_pkcs1_der(&self) -> Result<Vec<u8>, Error> {
+        let mut writer = Writer::new();
+        writer.write_sequence(|key| {
+            key.write_uint(self.modulus()).write_uint(self.public_exponent());
+        });
+        Ok(writer.into_vec())
+    }
+}
+impl DecodePublicKey for RsaPublicKey {
+    fn from_public_key_der(der: &[u8]) -> Result<Self, Error> {
+        let public_key = decode_spki(der, ALGORITHM)?;
+        Self::from_pkcs1_der(public_key)
+    }
+}
+impl EncodePublicKey for RsaPublicKey {
+    fn to_public_key_der(&self) -> Result<Vec<u8>, Error> {
+        let public_key = self.to_pkcs1_der()?;
+        Ok(encode_spki(ALGORITHM, &public_key))
+    }
+}
@@ -0,0 +1,339 @@
+//! A minimal fixed-width bignum on top of `Hacl_Bignum64_*`
+//!
+//! The expensive operations (multiplication, modular exponentiation and inversion) are delegated to HACL*; the cheap
+//! glue arithmetic with single-limb operands that HACL* does not expose is implemented here. Bignums hold primes and
+//! private exponents, so their limbs and all intermediate byte encodings are erased before they are released.
+
+use crate::{secret::SecretBytes, Error};
+use alloc::{vec, vec::Vec};
+use core::{cmp::Ordering, mem::size_of};
+use evercrypt_tiny_sys::{
+    Hacl_Bignum64_mod, Hacl_Bignum64_mod_exp_consttime, Hacl_Bignum64_mod_exp_vartime,
+    Hacl_Bignum64_mod_inv_prime_vartime, Hacl_Bignum64_mul, Hacl_Bignum64_sqr, Lib_Memzero0_memzero,
+};
+use rand_core::{CryptoRng, RngCore};
+
+/// An unsigned integer as little-endian `u64` limbs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bignum {
+    /// The limbs, least significant limb first
+    limbs: Vec<u64>,
+}
+impl Bignum {
+    /// Creates a bignum with `len` limbs from a single limb
+    pub fn from_u64(value: u64, len: usize) -> Self {
+        let mut limbs = vec![0; len];
+        limbs[0] = value;
+        Self { limbs }
+    }
+    /// Decodes a big-endian integer into `len` limbs
+    pub fn from_be_bytes(bytes: &[u8], len: usize) -> Result<Self, Error> {
+        let mut limbs = vec![0; len];
+        for (index, byte) in bytes.iter().rev().enumerate() {
+            match limbs.get_mut(index / 8) {
+                Some(limb) => *limb |= (*byte as u64) << (8 * (index % 8)),
+                None if *byte == 0 => (),
+                None => return Err(Error::InvalidLength),
+            }
+        }
+        Ok(Self { limbs })
+    }
+    /// Draws a uniformly random integer below `2^bits` from `rng`
+    pub fn random<R>(bits: usize, len: usize, rng: &mut R) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        // Fill the limbs and clear the excess bits
+        let mut bytes = SecretBytes::new(bits.div_ceil(8));
+        rng.try_fill_bytes(&mut bytes).map_err(|_| Error::RngFailure)?;
+        if !bits.is_multiple_of(8) {
+            bytes[0] &= 0xff >> (8 - bits % 8);
+        }
+        Self::from_be_bytes(&bytes, len)
+    }
+
+    /// Encodes all limbs as big-endian bytes
+    fn to_be_limb_bytes(&self) -> SecretBytes {
+        let mut bytes = SecretBytes::new(self.limbs.len() * size_of::<u64>());
+        for (chunk, limb) in bytes.chunks_exact_mut(size_of::<u64>()).zip(self.limbs.iter().rev()) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+    /// Encodes the integer as big-endian bytes without leading zero bytes
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let bytes = self.to_be_limb_bytes();
+        let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+        bytes[start..].to_vec()
+    }
+    /// Encodes the integer as big-endian bytes left-padded to `len` bytes
+    pub fn to_be_bytes_padded(&self, len: usize) -> Vec<u8> {
+        self.to_be_secret_padded(len).to_vec()
+    }
+    /// Encodes the integer as big-endian bytes left-padded to `len` bytes into a buffer that is erased on drop
+    pub fn to_be_secret_padded(&self, len: usize) -> SecretBytes {
+        let bytes = self.to_be_limb_bytes();
+        let mut padded = SecretBytes::new(len);
+        let copy_len = bytes.len().min(len);
+        padded[len - copy_len..].copy_from_slice(&bytes[bytes.len() - copy_len..]);
+        padded
+    }
+    /// Returns the least significant limb
+    pub fn to_u64(&self) -> u64 {
+        self.limbs[0]
+    }
+    /// Returns a copy that is zero-extended or truncated to `len` limbs
+    pub fn resize(&self, len: usize) -> Self {
+        // Allocate the final size up front so that no unerased copy is left behind
+        let mut limbs = Vec::with_capacity(len);
+        limbs.extend_from_slice(&self.limbs[..self.limbs.len().min(len)]);
+        limbs.resize(len, 0);
+        Self { limbs }
+    }
+
+    /// The amount of limbs
+    pub fn len(&self) -> usize {
+        self.limbs.len()
+    }
+    /// The amount of significant bits
+    pub fn bits(&self) -> usize {
+        let Some(top) = self.limbs.iter().rposition(|limb| *limb != 0) else {
+            return 0;
+        };
+        top * 64 + (64 - self.limbs[top].leading_zeros() as usize)
+    }
+    /// Returns the amount of trailing zero bits
+    pub fn trailing_zeros(&self) -> usize {
+        let Some(bottom) = self.limbs.iter().position(|limb| *limb != 0) else {
+            return 0;
+        };
+        bottom * 64 + self.limbs[bottom].trailing_zeros() as usize
+    }
+    /// Whether the integer is equal to the single limb `value`
+    pub fn is_u64(&self, value: u64) -> bool {
+        self.limbs[0] == value && self.limbs[1..].iter().all(|limb| *limb == 0)
+    }
+    /// Sets the bit at `index`
+    pub fn set_bit(&mut self, index: usize) {
+        self.limbs[index / 64] |= 1 << (index % 64);
+    }
+
+    /// Adds the single limb `value` in place, discarding the carry
+    pub fn add_u64(&mut self, value: u64) {
+        let mut carry = value;
+        for limb in self.limbs.iter_mut() {
+            let (sum, overflow) = limb.overflowing_add(carry);
+            (*limb, carry) = (sum, overflow as u64);
+        }
+    }
+    /// Subtracts the single limb `value` in place, discarding the borrow
+    pub fn sub_u64(&mut self, value: u64) {
+        let mut borrow = value;
+        for limb in self.limbs.iter_mut() {
+            let (difference, underflow) = limb.overflowing_sub(borrow);
+            (*limb, borrow) = (difference, underflow as u64);
+        }
+    }
+    /// Subtracts `other` which must not be greater than `self` and has the same amount of limbs
+    pub fn sub(&self, other: &Self) -> Self {
+        let mut limbs = self.limbs.clone();
+        let mut borrow = false;
+        for (limb, other) in limbs.iter_mut().zip(&other.limbs) {
+            let (difference, underflow0) = limb.overflowing_sub(*other);
+            let (difference, underflow1) = difference.overflowing_sub(borrow as u64);
+            (*limb, borrow) = (difference, underflow0 | underflow1);
+        }
+        debug_assert!(!borrow, "Subtraction underflow");
+        Self { limbs }
+    }
+    /// Multiplies with the single limb `value`; the result has one additional limb
+    pub fn mul_u64(&self, value: u64) -> Self {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = 0;
+        for limb in &self.limbs {
+            let product = (*limb as u128) * (value as u128) + carry;
+            limbs.push(product as u64);
+            carry = product >> 64;
+        }
+        limbs.push(carry as u64);
+        Self { limbs }
+    }
+    /// Divides by the single limb `divisor` and returns the quotient and remainder
+    pub fn div_rem_u64(&self, divisor: u64) -> (Self, u64) {
+        let mut limbs = vec![0; self.limbs.len()];
+        let mut remainder = 0u128;
+        for (index, limb) in self.limbs.iter().enumerate().rev() {
+            let dividend = (remainder << 64) | *limb as u128;
+            limbs[index] = (dividend / divisor as u128) as u64;
+            remainder = dividend % divisor as u128;
+        }
+        (Self { limbs }, remainder as u64)
+    }
+    /// Computes the remainder of the division by the single limb `divisor`
+    pub fn rem_u64(&self, divisor: u64) -> u64 {
+        let (_, remainder) = self.div_rem_u64(divisor);
+        remainder
+    }
+    /// Computes `self mod modulus` for a non-zero `modulus` with the same amount of limbs by binary long division
+    ///
+    /// Unlike `Hacl_Bignum64_mod`, this also supports even moduli. The running time only depends on the amount of limbs,
+    /// so the operands may be secret.
+    pub fn rem(&self, modulus: &Self) -> Self {
+        debug_assert_eq!(self.len(), modulus.len(), "Mismatching operand lengths");
+        let (mut remainder, mut difference) = (Self::from_u64(0, self.len()), Self::from_u64(0, self.len()));
+        for bit in (0..64 * self.len()).rev() {
+            // Shift the next bit into the remainder
+            let mut carry = (self.limbs[bit / 64] >> (bit % 64)) & 1;
+            for limb in remainder.limbs.iter_mut() {
+                (*limb, carry) = ((*limb << 1) | carry, *limb >> 63);
+            }
+
+            // Subtract the modulus unless this underflows without a carry to absorb it
+            let mut borrow = false;
+            for ((difference, limb), modulus) in difference.limbs.iter_mut().zip(&remainder.limbs).zip(&modulus.limbs) {
+                let (value, underflow0) = limb.overflowing_sub(*modulus);
+                let (value, underflow1) = value.overflowing_sub(borrow as u64);
+                (*difference, borrow) = (value, underflow0 | underflow1);
+            }
+            let mask = (carry | !borrow as u64).wrapping_neg();
+            for (limb, difference) in remainder.limbs.iter_mut().zip(&difference.limbs) {
+                *limb = (*difference & mask) | (*limb & !mask);
+            }
+        }
+        remainder
+    }
+    /// Shifts the integer to the right by `shift` bits
+    pub fn shr(&self, shift: usize) -> Self {
+        let (limb_shift, bit_shift) = (shift / 64, shift % 64);
+        let mut limbs = vec![0; self.limbs.len()];
+        for (index, limb) in limbs.iter_mut().enumerate() {
+            let low = self.limbs.get(index + limb_shift).copied().unwrap_or(0);
+            let high = self.limbs.get(index + limb_shift + 1).copied().unwrap_or(0);
+            *limb = match bit_shift {
+                0 => low,
+                _ => (low >> bit_shift) | (high << (64 - bit_shift)),
+            };
+        }
+        Self { limbs }
+    }
+
+    /// Computes the greatest common divisor with `other` which has the same amount of limbs
+    pub fn gcd(&self, other: &Self) -> Self {
+        // Use the binary GCD algorithm since this is a rare operation on small inputs
+        let (mut a, mut b) = (self.clone(), other.clone());
+        if a.bits() == 0 {
+            return b;
+        }
+        if b.bits() == 0 {
+            return a;
+        }
+
+        // Factor out the common powers of two and make `a` odd
+        let shift = a.trailing_zeros().min(b.trailing_zeros());
+        a = a.shr(a.trailing_zeros());
+        while b.bits() != 0 {
+            b = b.shr(b.trailing_zeros());
+            if a > b {
+                (a, b) = (b, a);
+            }
+            b = b.sub(&a);
+        }
+
+        // Reapply the common powers of two
+        let mut gcd = a;
+        for _ in 0..shift {
+            gcd = gcd.mul_u64(2).resize(self.len());
+        }
+        gcd
+    }
+
+    /// Multiplies with `other` which has the same amount of limbs; the result has twice the amount of limbs
+    pub fn mul(&self, other: &Self) -> Self {
+        debug_assert_eq!(self.len(), other.len(), "Mismatching operand lengths");
+        let (mut a, mut b) = (self.clone(), other.clone());
+        let mut limbs = vec![0; 2 * self.len()];
+        unsafe { Hacl_Bignum64_mul(self.len() as u32, a.limbs.as_mut_ptr(), b.limbs.as_mut_ptr(), limbs.as_mut_ptr()) };
+        Self { limbs }
+    }
+    /// Computes `self^2 mod modulus` for an odd `modulus > 1` with the same amount of limbs
+    pub fn sqr_mod(&self, modulus: &Self) -> Self {
+        let (mut a, mut n) = (self.clone(), modulus.clone());
+        let mut square = vec![0; 2 * self.len()];
+        let mut limbs = vec![0; self.len()];
+        let is_ok = unsafe {
+            Hacl_Bignum64_sqr(self.len() as u32, a.limbs.as_mut_ptr(), square.as_mut_ptr());
+            Hacl_Bignum64_mod(self.len() as u32, n.limbs.as_mut_ptr(), square.as_mut_ptr(), limbs.as_mut_ptr())
+        };
+        debug_assert!(is_ok, "Invalid modulus");
+        Self { limbs }
+    }
+    /// Computes `self^exponent mod modulus` for an odd `modulus > 1` and `self < modulus` in variable time
+    pub fn mod_exp_vartime(&self, exponent: &Self, modulus: &Self) -> Self {
+        let (mut a, mut b, mut n) = (self.clone(), exponent.clone(), modulus.clone());
+        let mut limbs = vec![0; self.len()];
+        let is_ok = unsafe {
+            Hacl_Bignum64_mod_exp_vartime(
+                self.len() as u32,
+                n.limbs.as_mut_ptr(),
+                a.limbs.as_mut_ptr(),
+                (b.len() * 64) as u32,
+                b.limbs.as_mut_ptr(),
+                limbs.as_mut_ptr(),
+            )
+        };
+        debug_assert!(is_ok, "Invalid modulus or base");
+        Self { limbs }
+    }
+    /// Computes `self^exponent mod modulus` for an odd `modulus > 1` and `self < modulus` in constant time
+    ///
+    /// The exponent must have the same amount of limbs as the modulus.
+    pub fn mod_exp_consttime(&self, exponent: &Self, modulus: &Self) -> Self {
+        let (mut a, mut b, mut n) = (self.clone(), exponent.clone(), modulus.clone());
+        let mut limbs = vec![0; self.len()];
+        let is_ok = unsafe {
+            Hacl_Bignum64_mod_exp_consttime(
+                self.len() as u32,
+                n.limbs.as_mut_ptr(),
+                a.limbs.as_mut_ptr(),
+                (b.len() * 64) as u32,
+                b.limbs.as_mut_ptr(),
+                limbs.as_mut_ptr(),
+            )
+        };
+        debug_assert!(is_ok, "Invalid modulus or base");
+        Self { limbs }
+    }
+    /// Computes `self^-1 mod modulus` for a prime `modulus` and `0 < self < modulus` in variable time
+    pub fn mod_inv_prime_vartime(&self, modulus: &Self) -> Result<Self, Error> {
+        let (mut a, mut n) = (self.clone(), modulus.clone());
+        let mut limbs = vec![0; self.len()];
+        let is_ok = unsafe {
+            Hacl_Bignum64_mod_inv_prime_vartime(
+                self.len() as u32,
+                n.limbs.as_mut_ptr(),
+                a.limbs.as_mut_ptr(),
+                limbs.as_mut_ptr(),
+            )
+        };
+        match is_ok {
+            true => Ok(Self { limbs }),
+            false => Err(Error::InvalidKey),
+        }
+    }
+}
+impl Drop for Bignum {
+    fn drop(&mut self) {
+        unsafe { Lib_Memzero0_memzero(self.limbs.as_mut_ptr().cast(), (self.limbs.len() * size_of::<u64>()) as u64) };
+    }
+}
+impl PartialOrd for Bignum {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Bignum {
+    fn cmp(&self, other: &Self) -> Ordering {
+        debug_assert_eq!(self.len(), other.len(), "Mismatching operand lengths");
+        self.limbs.iter().rev().cmp(other.limbs.iter().rev())
+    }
+}
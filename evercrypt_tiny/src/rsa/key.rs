@@ -0,0 +1,205 @@
+//! RSA key types with owned HACL* key buffers
+
+use crate::{
+    rsa::{self, bignum::Bignum, HeapLimbs, RsaCrtComponents},
+    secret::SecretBytes,
+    Error,
+};
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Formatter};
+use evercrypt_tiny_sys::{Hacl_RSAPSS_new_rsapss_load_pkey, Hacl_RSAPSS_new_rsapss_load_skey};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// An RSA public key
+#[derive(Debug)]
+pub struct RsaPublicKey {
+    /// The big-endian modulus without leading zero bytes
+    pub(super) n: Vec<u8>,
+    /// The big-endian public exponent without leading zero bytes
+    pub(super) e: Vec<u8>,
+    /// The bit length of the modulus
+    pub(super) mod_bits: u32,
+    /// The bit length of the public exponent
+    pub(super) e_bits: u32,
+}
+impl RsaPublicKey {
+    /// Loads a public key from its big-endian modulus `n` and public exponent `e`
+    pub fn from_components(n: &[u8], e: &[u8]) -> Result<Self, Error> {
+        // Normalize the components
+        let (mut n, mut e) = (rsa::trim(n).to_vec(), rsa::trim(e).to_vec());
+        let (mod_bits, e_bits) = (rsa::bit_len(&n), rsa::bit_len(&e));
+
+        // Validate the key by loading it (`Hacl_RSAPSS_rsapss_pkey_verify` loads it from the components on every call)
+        let pkey = unsafe { Hacl_RSAPSS_new_rsapss_load_pkey(mod_bits, e_bits, n.as_mut_ptr(), e.as_mut_ptr()) };
+        let pkey_len = 2 * rsa::limbs(mod_bits) + rsa::limbs(e_bits);
+        HeapLimbs::new(pkey, pkey_len).ok_or(Error::InvalidKey)?;
+        Ok(Self { n, e, mod_bits, e_bits })
+    }
+
+    /// The big-endian modulus
+    pub fn modulus(&self) -> &[u8] {
+        &self.n
+    }
+    /// The big-endian public exponent
+    pub fn public_exponent(&self) -> &[u8] {
+        &self.e
+    }
+    /// The size of the modulus in bits
+    pub fn modulus_bits(&self) -> usize {
+        self.mod_bits as usize
+    }
+    /// The length of a signature or ciphertext in bytes
+    pub fn signature_len(&self) -> usize {
+        self.n.len()
+    }
+
+    /// Applies the RSA public key operation (RSAEP/RSAVP1) to a big-endian `input` of the modulus' length
+    ///
+    /// This fails with [`Error::InvalidEncoding`] if `input` is not smaller than the modulus.
+    pub(super) fn public_op(&self, input: &[u8]) -> Result<Vec<u8>, Error> {
+        let (n, input) = self.load_input(input)?;
+        let e = Bignum::from_be_bytes(&self.e, self.e.len().div_ceil(8))?;
+        let output = input.mod_exp_vartime(&e, &n);
+        Ok(output.to_be_bytes_padded(self.n.len()))
+    }
+
+    /// Decodes the modulus and the big-endian `input` of the modulus' length and ensures that `input < n`
+    fn load_input(&self, input: &[u8]) -> Result<(Bignum, Bignum), Error> {
+        if input.len() != self.n.len() {
+            return Err(Error::InvalidLength);
+        }
+
+        let len = self.n.len().div_ceil(8);
+        let (n, input) = (Bignum::from_be_bytes(&self.n, len)?, Bignum::from_be_bytes(input, len)?);
+        match input < n {
+            true => Ok((n, input)),
+            false => Err(Error::InvalidEncoding),
+        }
+    }
+}
+impl Clone for RsaPublicKey {
+    fn clone(&self) -> Self {
+        Self::from_components(&self.n, &self.e).expect("Failed to reload a valid public key")
+    }
+}
+impl PartialEq for RsaPublicKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.n == other.n && self.e == other.e
+    }
+}
+impl Eq for RsaPublicKey {}
+
+/// An RSA private key
+pub struct RsaPrivateKey {
+    /// The public key
+    pub(super) public_key: RsaPublicKey,
+    /// The big-endian private exponent without leading zero bytes
+    pub(super) d: SecretBytes,
+    /// The bit length of the private exponent
+    pub(super) d_bits: u32,
+    /// The CRT components if known
+    pub(super) crt: Option<RsaCrtComponents>,
+    /// The key as loaded by `Hacl_RSAPSS_new_rsapss_load_skey`
+    pub(super) skey: HeapLimbs,
+}
+impl RsaPrivateKey {
+    /// Loads a private key from its big-endian modulus `n`, public exponent `e` and private exponent `d`
+    pub fn from_components(n: &[u8], e: &[u8], d: &[u8]) -> Result<Self, Error> {
+        // Load the public key and normalize the private exponent
+        let public_key = RsaPublicKey::from_components(n, e)?;
+        let mut d = SecretBytes::from_slice(rsa::trim(d));
+        let d_bits = rsa::bit_len(&d);
+
+        // Load and validate the key
+        let skey = unsafe {
+            let (n, e) = (public_key.n.as_ptr().cast_mut(), public_key.e.as_ptr().cast_mut());
+            Hacl_RSAPSS_new_rsapss_load_skey(public_key.mod_bits, public_key.e_bits, d_bits, n, e, d.as_mut_ptr())
+        };
+        let skey_len = 2 * rsa::limbs(public_key.mod_bits) + rsa::limbs(public_key.e_bits) + rsa::limbs(d_bits);
+        let skey = HeapLimbs::new(skey, skey_len).ok_or(Error::InvalidKey)?;
+        Ok(Self { public_key, d, d_bits, crt: None, skey })
+    }
+    /// Attaches the CRT components which are required to serialize the key as PKCS#1 or PKCS#8
+    ///
+    /// This fails with [`Error::InvalidKey`] unless `p * q = n`, `e * dP = 1 mod (p - 1)`, `dP = d mod (p - 1)`,
+    /// `dQ = d mod (q - 1)` and `qInv * q = 1 mod p` with `qInv < p`. The primality of `p` and `q` is not verified.
+    pub fn with_crt_components(mut self, crt: RsaCrtComponents) -> Result<Self, Error> {
+        // Decode all components to the width of the modulus
+        let len = self.public_key.n.len().div_ceil(8);
+        let decode = |bytes: &[u8]| Bignum::from_be_bytes(bytes, len).map_err(|_| Error::InvalidKey);
+        let (n, e, d) = (decode(&self.public_key.n)?, decode(&self.public_key.e)?, decode(&self.d)?);
+        let (p, q) = (decode(&crt.p)?, decode(&crt.q)?);
+        let (dp, dq, qinv) = (decode(&crt.dp)?, decode(&crt.dq)?, decode(&crt.qinv)?);
+
+        // Validate the factorization
+        let one = Bignum::from_u64(1, len);
+        if p <= one || q <= one || p.mul(&q) != n.resize(2 * len) {
+            return Err(Error::InvalidKey);
+        }
+
+        // Validate the CRT exponents and the private exponent they are derived from
+        let (mut p_minus_one, mut q_minus_one) = (p.clone(), q.clone());
+        p_minus_one.sub_u64(1);
+        q_minus_one.sub_u64(1);
+        let is_valid = d.rem(&p_minus_one) == dp
+            && d.rem(&q_minus_one) == dq
+            && e.mul(&dp).rem(&p_minus_one.resize(2 * len)).is_u64(1)
+            && qinv < p
+            && qinv.mul(&q).rem(&p.resize(2 * len)).is_u64(1);
+        if !is_valid {
+            return Err(Error::InvalidKey);
+        }
+
+        self.crt = Some(crt);
+        Ok(self)
+    }
+
+    /// The corresponding public key
+    pub fn public_key(&self) -> &RsaPublicKey {
+        &self.public_key
+    }
+    /// The big-endian private exponent
+    pub fn private_exponent(&self) -> &[u8] {
+        &self.d
+    }
+    /// The CRT components if known
+    pub fn crt_components(&self) -> Option<&RsaCrtComponents> {
+        self.crt.as_ref()
+    }
+
+    /// Applies the RSA private key operation (RSADP/RSASP1) in constant time to a big-endian `input` of the modulus'
+    /// length
+    ///
+    /// This fails with [`Error::InvalidEncoding`] if `input` is not smaller than the modulus.
+    pub(super) fn private_op(&self, input: &[u8]) -> Result<SecretBytes, Error> {
+        // Use the modulus width as exponent bound so that the timing does not depend on the private exponent
+        let (n, input) = self.public_key.load_input(input)?;
+        let d = Bignum::from_be_bytes(&self.d, n.len())?;
+        let output = input.mod_exp_consttime(&d, &n);
+        Ok(output.to_be_secret_padded(self.public_key.n.len()))
+    }
+}
+impl Clone for RsaPrivateKey {
+    fn clone(&self) -> Self {
+        let mut clone = Self::from_components(&self.public_key.n, &self.public_key.e, &self.d)
+            .expect("Failed to reload a valid private key");
+        clone.crt = self.crt.clone();
+        clone
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for RsaPrivateKey {
+    fn zeroize(&mut self) {
+        self.d.zeroize();
+        self.crt.zeroize();
+        self.skey.erase();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for RsaPrivateKey {}
+impl Debug for RsaPrivateKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("RsaPrivateKey").field("public_key", &self.public_key).finish_non_exhaustive()
+    }
+}
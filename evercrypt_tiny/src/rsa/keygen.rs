@@ -0,0 +1,246 @@
+//! RSA key pair generation as specified in FIPS 186-5, appendix A.1.3
+//!
+//! Primes are generated as random probable primes; candidates are sieved by trial division and then tested with
+//! Miller-Rabin via `Hacl_Bignum64_mod_exp_vartime`. The private exponent is computed modulo `lcm(p - 1, q - 1)`,
+//! which yields the same keys as OpenSSL for the same primes.
+
+use crate::{
+    rsa::{self, bignum::Bignum, RsaCrtComponents, RsaPrivateKey},
+    secret::SecretBytes,
+    Error,
+};
+use core::cmp::Ordering;
+use rand_core::{CryptoRng, RngCore};
+
+/// The public exponent of generated keys
+pub const PUBLIC_EXPONENT: u64 = 65537;
+/// The supported modulus sizes in bits
+pub const SUPPORTED_MODULUS_BITS: [usize; 3] = [2048, 3072, 4096];
+
+/// The amount of Miller-Rabin rounds
+///
+/// This bounds the probability that a composite passes to `4^-64 = 2^-128` regardless of how the candidate was chosen.
+const MILLER_RABIN_ROUNDS: usize = 64;
+/// The maximum amount of prime pairs drawn before key generation is aborted
+///
+/// A pair is only rejected if the primes are too close or the private exponent is too small, which is negligibly
+/// likely for a working RNG.
+const MAX_GENERATE_ATTEMPTS: usize = 16;
+/// The amount of small primes used for trial division
+const SMALL_PRIMES_LEN: usize = 256;
+/// The first odd primes used for trial division
+const SMALL_PRIMES: [u64; SMALL_PRIMES_LEN] = small_primes();
+
+/// Computes the first odd primes via trial division at compile time
+const fn small_primes() -> [u64; SMALL_PRIMES_LEN] {
+    let (mut primes, mut count, mut candidate) = ([0; SMALL_PRIMES_LEN], 0, 3);
+    while count < SMALL_PRIMES_LEN {
+        // Test the candidate against all smaller odd primes
+        let (mut index, mut is_prime) = (0, true);
+        while index < count && primes[index] * primes[index] <= candidate {
+            if candidate % primes[index] == 0 {
+                is_prime = false;
+                break;
+            }
+            index += 1;
+        }
+
+        if is_prime {
+            primes[count] = candidate;
+            count += 1;
+        }
+        candidate += 2;
+    }
+    primes
+}
+
+/// Computes `value^-1 mod modulus` for single-limb operands via the extended Euclidean algorithm
+fn inv_mod_u64(value: u64, modulus: u64) -> Option<u64> {
+    let (mut r0, mut r1) = (modulus as i128, value as i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let quotient = r0 / r1;
+        (r0, r1) = (r1, r0 - quotient * r1);
+        (t0, t1) = (t1, t0 - quotient * t1);
+    }
+    match r0 {
+        1 => Some(t0.rem_euclid(modulus as i128) as u64),
+        _ => None,
+    }
+}
+
+/// Computes `e^-1 mod modulus` for a single-limb `e`
+///
+/// With `t = modulus^-1 mod e`, `(modulus * (e - t) + 1)` is divisible by `e` and the quotient is the inverse of `e`.
+/// This avoids a full-width inversion modulo the even `modulus`, which HACL* does not support.
+fn inv_small_mod(e: u64, modulus: &Bignum) -> Result<Bignum, Error> {
+    let t = inv_mod_u64(modulus.rem_u64(e), e).ok_or(Error::InvalidKey)?;
+    let mut product = modulus.mul_u64(e - t);
+    product.add_u64(1);
+
+    let (inverse, remainder) = product.div_rem_u64(e);
+    debug_assert_eq!(remainder, 0, "Inexact division");
+    Ok(inverse.resize(modulus.len()))
+}
+
+/// Tests whether the odd `candidate > 3` is a probable prime with the Miller-Rabin test (FIPS 186-5, appendix B.3.1)
+fn is_probable_prime<R>(candidate: &Bignum, rng: &mut R) -> Result<bool, Error>
+where
+    R: RngCore + CryptoRng,
+{
+    // Decompose `candidate - 1 = 2^a * m`
+    let mut candidate_minus_one = candidate.clone();
+    candidate_minus_one.sub_u64(1);
+    let a = candidate_minus_one.trailing_zeros();
+    let m = candidate_minus_one.shr(a);
+
+    'rounds: for _ in 0..MILLER_RABIN_ROUNDS {
+        // Draw a base within `[2, candidate - 2]`; every value below `2^(bits - 1)` is smaller than `candidate - 1`
+        let base = Bignum::random(candidate.bits() - 1, candidate.len(), rng)?;
+        if base.bits() < 2 {
+            continue;
+        }
+
+        // Test the base
+        let mut z = base.mod_exp_vartime(&m, candidate);
+        if z.is_u64(1) || z == candidate_minus_one {
+            continue;
+        }
+        for _ in 1..a {
+            z = z.sqr_mod(candidate);
+            if z == candidate_minus_one {
+                continue 'rounds;
+            }
+            if z.is_u64(1) {
+                return Ok(false);
+            }
+        }
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Generates a probable prime `p` with exactly `bits` bits, `p >= sqrt(2) * 2^(bits - 1)` and `gcd(p - 1, e) = 1`
+/// (FIPS 186-5, appendix A.1.3)
+fn generate_prime<R>(bits: usize, e: u64, rng: &mut R) -> Result<Bignum, Error>
+where
+    R: RngCore + CryptoRng,
+{
+    let len = bits.div_ceil(64);
+    for _ in 0..5 * bits {
+        // Draw an odd candidate with the two most significant bits set, which implies the lower bound
+        let mut candidate = Bignum::random(bits, len, rng)?;
+        candidate.set_bit(bits - 1);
+        candidate.set_bit(bits - 2);
+        candidate.set_bit(0);
+
+        // Sieve the candidate and ensure that `e` is invertible modulo `p - 1`
+        if SMALL_PRIMES.iter().any(|prime| candidate.rem_u64(*prime) == 0) {
+            continue;
+        }
+        if inv_mod_u64((candidate.rem_u64(e) + e - 1) % e, e).is_none() {
+            continue;
+        }
+        if is_probable_prime(&candidate, rng)? {
+            return Ok(candidate);
+        }
+    }
+    Err(Error::RngFailure)
+}
+
+/// Assembles a private key from the primes `p` and `q` and the public exponent `e`
+fn assemble(p: &Bignum, q: &Bignum, e: u64) -> Result<RsaPrivateKey, Error> {
+    // Ensure that `p > q` so that `qinv` can be computed modulo the prime `p`
+    let len = p.len().max(q.len());
+    let (p, q) = match p.resize(len).cmp(&q.resize(len)) {
+        Ordering::Greater => (p.resize(len), q.resize(len)),
+        Ordering::Less => (q.resize(len), p.resize(len)),
+        Ordering::Equal => return Err(Error::InvalidKey),
+    };
+    let one = Bignum::from_u64(1, len);
+    if p.rem_u64(2) == 0 || q.rem_u64(2) == 0 || q <= one {
+        return Err(Error::InvalidKey);
+    }
+
+    // Compute `lambda = lcm(p - 1, q - 1) = (p - 1) * ((q - 1) / gcd(p - 1, q - 1))`
+    let (p_minus_one, q_minus_one) = (p.sub(&one), q.sub(&one));
+    let gcd = p_minus_one.gcd(&q_minus_one);
+    if gcd.bits() > 64 {
+        // The GCD of two random primes minus one is tiny with overwhelming probability
+        return Err(Error::InvalidKey);
+    }
+    let (q_minus_one_reduced, _) = q_minus_one.div_rem_u64(gcd.to_u64());
+    let lambda = p_minus_one.mul(&q_minus_one_reduced);
+
+    // Compute the private exponent and the CRT components
+    let n = p.mul(&q);
+    let d = inv_small_mod(e, &lambda)?;
+    let dp = inv_small_mod(e, &p_minus_one)?;
+    let dq = inv_small_mod(e, &q_minus_one)?;
+    let qinv = q.mod_inv_prime_vartime(&p)?;
+
+    // Load the key
+    let (e, d) = (e.to_be_bytes(), SecretBytes::from(d.to_be_bytes()));
+    let mut private_key = RsaPrivateKey::from_components(&n.to_be_bytes(), &e, &d)?;
+    let crt = RsaCrtComponents {
+        p: p.to_be_bytes(),
+        q: q.to_be_bytes(),
+        dp: dp.to_be_bytes(),
+        dq: dq.to_be_bytes(),
+        qinv: qinv.to_be_bytes(),
+    };
+    private_key.crt = Some(crt);
+    Ok(private_key)
+}
+
+impl RsaPrivateKey {
+    /// Generates a new key pair with a modulus of `mod_bits` bits and the public exponent 65537
+    ///
+    /// `mod_bits` must be one of [`SUPPORTED_MODULUS_BITS`]; the generated key carries its CRT components.
+    pub fn generate<R>(mod_bits: usize, rng: &mut R) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        if !SUPPORTED_MODULUS_BITS.contains(&mod_bits) {
+            return Err(Error::UnsupportedAlgorithm);
+        }
+
+        for _ in 0..MAX_GENERATE_ATTEMPTS {
+            // Generate the primes and ensure that `|p - q| > 2^(mod_bits/2 - 100)` (FIPS 186-5, appendix A.1.3)
+            let p = generate_prime(mod_bits / 2, PUBLIC_EXPONENT, rng)?;
+            let q = generate_prime(mod_bits / 2, PUBLIC_EXPONENT, rng)?;
+            let difference = match p > q {
+                true => p.sub(&q),
+                false => q.sub(&p),
+            };
+            if difference.bits() <= mod_bits / 2 - 100 {
+                continue;
+            }
+
+            // Assemble the key and ensure that `d > 2^(mod_bits/2)`
+            match assemble(&p, &q, PUBLIC_EXPONENT) {
+                Ok(private_key) if rsa::bit_len(private_key.private_exponent()) as usize > mod_bits / 2 => {
+                    return Ok(private_key)
+                }
+                Ok(_) | Err(Error::InvalidKey) => continue,
+                Err(error) => return Err(error),
+            }
+        }
+        Err(Error::RngFailure)
+    }
+
+    /// Computes a private key including its CRT components from the big-endian primes `p` and `q` and the public
+    /// exponent `e`
+    ///
+    /// The primality of `p` and `q` is not verified; `e` must fit into 64 bits.
+    pub fn from_primes(p: &[u8], q: &[u8], e: &[u8]) -> Result<Self, Error> {
+        // Decode the primes and the exponent
+        let len = p.len().max(q.len()).div_ceil(8);
+        let (p, q) = (Bignum::from_be_bytes(p, len)?, Bignum::from_be_bytes(q, len)?);
+        let e = Bignum::from_be_bytes(e, 1).map_err(|_| Error::UnsupportedAlgorithm)?.to_u64();
+        if e < 3 {
+            return Err(Error::InvalidKey);
+        }
+        assemble(&p, &q, e)
+    }
+}
@@ -0,0 +1,141 @@
+//! RSA signatures, encryption and key generation
+
+mod bignum;
+mod key;
+mod keygen;
+mod oaep;
+mod pkcs1v15;
+mod pss;
+
+pub use crate::rsa::{
+    key::{RsaPrivateKey, RsaPublicKey},
+    keygen::{PUBLIC_EXPONENT, SUPPORTED_MODULUS_BITS},
+    oaep::Oaep,
+    pss::{RsaPssPrivateKey, RsaPssPublicKey},
+};
+use crate::{secret, Error, HashAlgorithm};
+use alloc::vec::Vec;
+use core::{
+    ffi::c_void,
+    fmt::{self, Debug, Formatter},
+    mem::size_of,
+    ptr::NonNull,
+};
+use evercrypt_tiny_sys::Lib_Memzero0_memzero;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+extern "C" {
+    /// The C allocator's `free` which is used by KaRaMeL's `KRML_HOST_FREE`
+    fn free(ptr: *mut c_void);
+}
+
+/// The CRT components of an RSA private key as stored in a PKCS#1 `RSAPrivateKey`
+///
+/// HACL* only operates on `n`, `e` and `d`, so these components are carried along for serialization only. All
+/// components are big-endian integers.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RsaCrtComponents {
+    /// The first prime factor `p`
+    pub p: Vec<u8>,
+    /// The second prime factor `q`
+    pub q: Vec<u8>,
+    /// The first factor's CRT exponent `d mod (p - 1)`
+    pub dp: Vec<u8>,
+    /// The second factor's CRT exponent `d mod (q - 1)`
+    pub dq: Vec<u8>,
+    /// The CRT coefficient `q^-1 mod p`
+    pub qinv: Vec<u8>,
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for RsaCrtComponents {
+    fn zeroize(&mut self) {
+        for component in [&mut self.p, &mut self.q, &mut self.dp, &mut self.dq, &mut self.qinv] {
+            component.zeroize();
+        }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for RsaCrtComponents {}
+impl Debug for RsaCrtComponents {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("RsaCrtComponents").finish_non_exhaustive()
+    }
+}
+impl Drop for RsaCrtComponents {
+    fn drop(&mut self) {
+        for component in [&mut self.p, &mut self.q, &mut self.dp, &mut self.dq, &mut self.qinv] {
+            secret::memzero(component);
+        }
+    }
+}
+
+/// A limb buffer that has been allocated by HACL* and is erased and freed on drop
+#[derive(Debug)]
+struct HeapLimbs {
+    /// The allocation
+    ptr: NonNull<u64>,
+    /// The amount of limbs
+    len: usize,
+}
+impl HeapLimbs {
+    /// Takes ownership over a buffer of `len` limbs returned by a `Hacl_*_new_*` function or returns `None` if `ptr` is
+    /// `NULL`
+    fn new(ptr: *mut u64, len: usize) -> Option<Self> {
+        NonNull::new(ptr).map(|ptr| Self { ptr, len })
+    }
+
+    /// The raw pointer to pass to HACL*
+    fn as_mut_ptr(&self) -> *mut u64 {
+        self.ptr.as_ptr()
+    }
+
+    /// Erases all limbs
+    fn erase(&mut self) {
+        unsafe { Lib_Memzero0_memzero(self.ptr.as_ptr().cast(), (self.len * size_of::<u64>()) as u64) };
+    }
+}
+impl Drop for HeapLimbs {
+    fn drop(&mut self) {
+        self.erase();
+        unsafe { free(self.ptr.as_ptr().cast()) };
+    }
+}
+// The buffer is exclusively owned and HACL* never modifies a loaded key
+unsafe impl Send for HeapLimbs {}
+unsafe impl Sync for HeapLimbs {}
+
+/// The amount of 64 bit limbs that HACL* uses for an integer of `bits` bits
+fn limbs(bits: u32) -> usize {
+    (bits as usize).div_ceil(64)
+}
+
+/// Strips the leading zero bytes from a big-endian integer
+fn trim(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+/// The bit length of a trimmed big-endian integer
+fn bit_len(trimmed: &[u8]) -> u32 {
+    match trimmed.first() {
+        Some(first) => (trimmed.len() as u32 - 1) * 8 + (8 - first.leading_zeros()),
+        None => 0,
+    }
+}
+
+/// XORs the MGF1 mask (RFC 8017, appendix B.2.1) generated from `seed` with `hash` into `target`
+fn mgf1_xor(hash: HashAlgorithm, seed: &[u8], target: &mut [u8]) -> Result<(), Error> {
+    let mut block = Vec::with_capacity(seed.len() + 4);
+    for (counter, chunk) in target.chunks_mut(hash.digest_len()).enumerate() {
+        // Compute `Hash(seed || I2OSP(counter, 4))`
+        block.clear();
+        block.extend_from_slice(seed);
+        block.extend_from_slice(&(counter as u32).to_be_bytes());
+        let mask = hash.digest(&block)?;
+
+        // Apply the mask
+        chunk.iter_mut().zip(mask).for_each(|(byte, mask)| *byte ^= mask);
+    }
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! RSAES-OAEP as specified in RFC 8017, section 7.1
+
+use crate::{
+    rsa::{self, RsaPrivateKey, RsaPublicKey},
+    Error, HashAlgorithm,
+};
+use alloc::{vec, vec::Vec};
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+/// The parameters of RSAES-OAEP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Oaep<'a> {
+    /// The hash function that is applied to the label
+    pub hash: HashAlgorithm,
+    /// The hash function that is used by MGF1
+    pub mgf_hash: HashAlgorithm,
+    /// The label that is bound to the ciphertext
+    pub label: &'a [u8],
+}
+impl<'a> Oaep<'a> {
+    /// Creates new parameters that use `hash` for the label and MGF1 and an empty label
+    pub const fn new(hash: HashAlgorithm) -> Self {
+        Self { hash, mgf_hash: hash, label: &[] }
+    }
+    /// Sets the hash function that is used by MGF1
+    pub const fn with_mgf_hash(mut self, mgf_hash: HashAlgorithm) -> Self {
+        self.mgf_hash = mgf_hash;
+        self
+    }
+    /// Sets the label
+    pub const fn with_label(mut self, label: &'a [u8]) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Validates the hash functions and computes the label hash
+    fn label_hash(&self) -> Result<Vec<u8>, Error> {
+        for hash in [self.hash, self.mgf_hash] {
+            match hash {
+                HashAlgorithm::Sha1
+                | HashAlgorithm::Sha2_224
+                | HashAlgorithm::Sha2_256
+                | HashAlgorithm::Sha2_384
+                | HashAlgorithm::Sha2_512 => (),
+                _ => return Err(Error::UnsupportedAlgorithm),
+            }
+        }
+        self.hash.digest(self.label)
+    }
+}
+
+impl RsaPublicKey {
+    /// Encrypts `message` with RSAES-OAEP using a random seed drawn from `rng`
+    ///
+    /// The message must not be longer than `k - 2 * hLen - 2` bytes, where `k` is the length of the modulus.
+    pub fn encrypt_oaep<R>(&self, params: &Oaep, message: &[u8], rng: &mut R) -> Result<Vec<u8>, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        // Validate the parameters
+        let label_hash = params.label_hash()?;
+        let (k, h_len) = (self.signature_len(), params.hash.digest_len());
+        if k < 2 * h_len + 2 || message.len() > k - 2 * h_len - 2 {
+            return Err(Error::InvalidLength);
+        }
+
+        // Build `EM = 0x00 || seed || DB` with `DB = lHash || PS || 0x01 || M`
+        let mut em = vec![0; k];
+        let (seed, db) = em[1..].split_at_mut(h_len);
+        rng.try_fill_bytes(seed).map_err(|_| Error::RngFailure)?;
+        let message_start = db.len() - message.len();
+        db[..h_len].copy_from_slice(&label_hash);
+        db[message_start - 1] = 0x01;
+        db[message_start..].copy_from_slice(message);
+
+        // Mask the data block and the seed and encrypt the encoded message
+        rsa::mgf1_xor(params.mgf_hash, seed, db)?;
+        rsa::mgf1_xor(params.mgf_hash, db, seed)?;
+        self.public_op(&em)
+    }
+}
+
+impl RsaPrivateKey {
+    /// Decrypts an RSAES-OAEP `ciphertext`
+    ///
+    /// The padding is checked in constant time and all decoding failures are reported as
+    /// [`Error::DecryptionFailed`] to avoid Manger's attack.
+    pub fn decrypt_oaep(&self, params: &Oaep, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        // Validate the parameters
+        let label_hash = params.label_hash()?;
+        let (k, h_len) = (self.public_key.signature_len(), params.hash.digest_len());
+        if k < 2 * h_len + 2 {
+            return Err(Error::DecryptionFailed);
+        }
+
+        // Decrypt the ciphertext and unmask the seed and the data block
+        let mut em = self.private_op(ciphertext).map_err(|_| Error::DecryptionFailed)?;
+        let (y, masked) = em.split_at_mut(1);
+        let (seed, db) = masked.split_at_mut(h_len);
+        rsa::mgf1_xor(params.mgf_hash, db, seed)?;
+        rsa::mgf1_xor(params.mgf_hash, seed, db)?;
+
+        // Validate the leading zero byte and the label hash
+        let (db_label_hash, padded) = db.split_at(h_len);
+        let mut is_valid = y[0].ct_eq(&0x00) & db_label_hash.ct_eq(&label_hash);
+
+        // Locate the `0x01` separator without branching on the contents
+        let (mut is_padding, mut message_start) = (Choice::from(1), 0u32);
+        for (index, byte) in padded.iter().enumerate() {
+            let (is_zero, is_one) = (byte.ct_eq(&0x00), byte.ct_eq(&0x01));
+            message_start.conditional_assign(&(index as u32 + 1), is_padding & is_one);
+            is_valid &= !(is_padding & !is_zero & !is_one);
+            is_padding &= is_zero;
+        }
+        is_valid &= !is_padding;
+
+        match bool::from(is_valid) {
+            true => Ok(padded[message_start as usize..].to_vec()),
+            false => Err(Error::DecryptionFailed),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! RSASSA-PKCS1-v1_5 as specified in RFC 8017, section 8.2
+
+use crate::{
+    rsa::{RsaPrivateKey, RsaPublicKey},
+    Error, HashAlgorithm,
+};
+use alloc::{vec, vec::Vec};
+
+/// Returns the DER encoded `DigestInfo` prefix for `hash` (RFC 8017, section 9.2, note 1)
+fn digest_info_prefix(hash: HashAlgorithm) -> Result<&'static [u8], Error> {
+    match hash {
+        HashAlgorithm::Sha1 => {
+            Ok(&[0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14])
+        }
+        HashAlgorithm::Sha2_224 => Ok(&[
+            0x30, 0x2d, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x04, 0x05, 0x00, 0x04,
+            0x1c,
+        ]),
+        HashAlgorithm::Sha2_256 => Ok(&[
+            0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04,
+            0x20,
+        ]),
+        HashAlgorithm::Sha2_384 => Ok(&[
+            0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02, 0x05, 0x00, 0x04,
+            0x30,
+        ]),
+        HashAlgorithm::Sha2_512 => Ok(&[
+            0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05, 0x00, 0x04,
+            0x40,
+        ]),
+        _ => Err(Error::UnsupportedAlgorithm),
+    }
+}
+
+/// Encodes `message` with EMSA-PKCS1-v1_5 (RFC 8017, section 9.2) into `em_len` bytes
+fn encode(hash: HashAlgorithm, message: &[u8], em_len: usize) -> Result<Vec<u8>, Error> {
+    // Compute `T = DigestInfo || H(M)`
+    let prefix = digest_info_prefix(hash)?;
+    let digest = hash.digest(message)?;
+    let t_len = prefix.len() + digest.len();
+    if em_len < t_len + 11 {
+        return Err(Error::InvalidLength);
+    }
+
+    // Build `EM = 0x00 || 0x01 || PS || 0x00 || T`
+    let mut em = vec![0xff; em_len];
+    em[0] = 0x00;
+    em[1] = 0x01;
+    em[em_len - t_len - 1] = 0x00;
+    em[em_len - t_len..em_len - digest.len()].copy_from_slice(prefix);
+    em[em_len - digest.len()..].copy_from_slice(&digest);
+    Ok(em)
+}
+
+impl RsaPublicKey {
+    /// Verifies an RSASSA-PKCS1-v1_5 `signature` over `message` which has been created with `hash`
+    pub fn verify_pkcs1v15(&self, hash: HashAlgorithm, message: &[u8], signature: &[u8]) -> Result<(), Error> {
+        // Compute the expected encoding first so that unsupported algorithms are reported as such
+        let expected = match encode(hash, message, self.signature_len()) {
+            Err(Error::InvalidLength) => return Err(Error::VerificationFailed),
+            result => result?,
+        };
+
+        // Recover the encoded message and compare it as a whole instead of parsing it
+        let em = self.public_op(signature).map_err(|_| Error::VerificationFailed)?;
+        match em == expected {
+            true => Ok(()),
+            false => Err(Error::VerificationFailed),
+        }
+    }
+}
+
+impl RsaPrivateKey {
+    /// Creates an RSASSA-PKCS1-v1_5 signature over `message` using `hash`
+    pub fn sign_pkcs1v15(&self, hash: HashAlgorithm, message: &[u8]) -> Result<Vec<u8>, Error> {
+        let em = encode(hash, message, self.public_key.signature_len())?;
+        let signature = self.private_op(&em)?;
+
+        // Verify the signature to guard against faults in the private key operation
+        match self.public_key.public_op(&signature)? == em {
+            true => Ok(signature.to_vec()),
+            false => Err(Error::OperationFailed),
+        }
+    }
+}
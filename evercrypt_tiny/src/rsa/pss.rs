@@ -0,0 +1,102 @@
+//! RSASSA-PSS as specified in RFC 8017, section 8.1
+
+use crate::{
+    rsa::{RsaPrivateKey, RsaPublicKey},
+    Error, HashAlgorithm,
+};
+use alloc::{vec, vec::Vec};
+use evercrypt_tiny_sys::{Hacl_RSAPSS_rsapss_pkey_verify, Hacl_RSAPSS_rsapss_sign};
+use rand_core::{CryptoRng, RngCore};
+
+/// An RSA public key for RSASSA-PSS verification
+///
+/// RSASSA-PSS shares its keys with the other RSA schemes, so this is the same type as [`RsaPublicKey`].
+pub type RsaPssPublicKey = RsaPublicKey;
+/// An RSA private key for RSASSA-PSS signing
+///
+/// RSASSA-PSS shares its keys with the other RSA schemes, so this is the same type as [`RsaPrivateKey`].
+pub type RsaPssPrivateKey = RsaPrivateKey;
+
+/// Ensures that the hash algorithm is supported by HACL*'s RSA-PSS implementation
+fn check_hash(hash: HashAlgorithm) -> Result<(), Error> {
+    match hash {
+        HashAlgorithm::Sha2_256 | HashAlgorithm::Sha2_384 | HashAlgorithm::Sha2_512 => Ok(()),
+        _ => Err(Error::UnsupportedAlgorithm),
+    }
+}
+
+impl RsaPublicKey {
+    /// Verifies an RSASSA-PSS `signature` over `message` which has been created with `hash` and a salt of `salt_len`
+    /// bytes
+    pub fn verify(&self, hash: HashAlgorithm, salt_len: usize, message: &[u8], signature: &[u8]) -> Result<(), Error> {
+        // Validate the parameters
+        check_hash(hash)?;
+        let salt_len = u32::try_from(salt_len).map_err(|_| Error::VerificationFailed)?;
+        let signature_len = u32::try_from(signature.len()).map_err(|_| Error::VerificationFailed)?;
+        let message_len = u32::try_from(message.len()).map_err(|_| Error::InvalidLength)?;
+
+        // Verify the signature against the components that have been validated by `Hacl_RSAPSS_new_rsapss_load_pkey`
+        let is_valid = unsafe {
+            Hacl_RSAPSS_rsapss_pkey_verify(
+                hash.to_spec(),
+                self.mod_bits,
+                self.e_bits,
+                self.n.as_ptr().cast_mut(),
+                self.e.as_ptr().cast_mut(),
+                salt_len,
+                signature_len,
+                signature.as_ptr().cast_mut(),
+                message_len,
+                message.as_ptr().cast_mut(),
+            )
+        };
+        match is_valid {
+            true => Ok(()),
+            false => Err(Error::VerificationFailed),
+        }
+    }
+}
+
+impl RsaPrivateKey {
+    /// Creates an RSASSA-PSS signature over `message` using `hash` and a random salt of `salt_len` bytes drawn from
+    /// `rng`
+    ///
+    /// RFC 8017 recommends to use a salt that is as long as the digest.
+    pub fn sign<R>(&self, hash: HashAlgorithm, salt_len: usize, message: &[u8], rng: &mut R) -> Result<Vec<u8>, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        // Validate the parameters (see RFC 8017, section 9.1.1)
+        check_hash(hash)?;
+        let em_len = (self.public_key.modulus_bits() - 1).div_ceil(8);
+        if salt_len + hash.digest_len() + 2 > em_len {
+            return Err(Error::InvalidLength);
+        }
+        let message_len = u32::try_from(message.len()).map_err(|_| Error::InvalidLength)?;
+
+        // Generate the salt
+        let mut salt = vec![0; salt_len];
+        rng.try_fill_bytes(&mut salt).map_err(|_| Error::RngFailure)?;
+
+        // Sign the message
+        let mut signature = vec![0; self.public_key.signature_len()];
+        let is_ok = unsafe {
+            Hacl_RSAPSS_rsapss_sign(
+                hash.to_spec(),
+                self.public_key.mod_bits,
+                self.public_key.e_bits,
+                self.d_bits,
+                self.skey.as_mut_ptr(),
+                salt_len as u32,
+                salt.as_mut_ptr(),
+                message_len,
+                message.as_ptr().cast_mut(),
+                signature.as_mut_ptr(),
+            )
+        };
+        match is_ok {
+            true => Ok(signature),
+            false => Err(Error::OperationFailed),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Secret buffers that are zeroized on drop
+//!
+//! Writes to memory that is about to be released are dead stores, so the compiler may elide a plain `fill(0)`.
+//! [`SecretBytes`], [`SecretString`] and [`SecretArray`] are therefore erased via HACL*'s `Lib_Memzero0_memzero`, which
+//! uses `explicit_bzero`, `memset_s`, `SecureZeroMemory` or volatile writes depending on the platform. All of them redact
+//! their contents in their `Debug` output and compare in constant time. Note that moving a [`SecretArray`] may leave a copy of
+//! its contents behind, so long-lived secrets should stay in place or be boxed.
+//!
+//! With the `zeroize` feature, these types and all key types of this crate implement `zeroize::Zeroize`.
+
+use crate::Error;
+use alloc::{string::String, vec::Vec};
+use core::{
+    fmt::{self, Debug, Formatter},
+    ops::{Deref, DerefMut},
+};
+use evercrypt_tiny_sys::Lib_Memzero0_memzero;
+use subtle::{Choice, ConstantTimeEq};
+
+/// Erases `buf` with writes that cannot be elided by the compiler
+pub fn memzero(buf: &mut [u8]) {
+    unsafe { Lib_Memzero0_memzero(buf.as_mut_ptr().cast(), buf.len() as u64) };
+}
+
+/// A heap-allocated secret byte string of a fixed length
+///
+/// The length is fixed at construction, so the buffer is never reallocated and left behind unerased.
+#[derive(Clone, Default)]
+pub struct SecretBytes {
+    /// The bytes
+    bytes: Vec<u8>,
+}
+impl SecretBytes {
+    /// Creates `len` zero bytes
+    pub fn new(len: usize) -> Self {
+        Self { bytes: alloc::vec![0; len] }
+    }
+
+    /// Copies `bytes` into a new secret
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        Self { bytes: bytes.to_vec() }
+    }
+
+    /// The secret bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+impl From<Vec<u8>> for SecretBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+}
+impl Deref for SecretBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.bytes
+    }
+}
+impl DerefMut for SecretBytes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.bytes
+    }
+}
+impl AsRef<[u8]> for SecretBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+impl AsMut<[u8]> for SecretBytes {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+}
+impl ConstantTimeEq for SecretBytes {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.bytes.ct_eq(&other.bytes)
+    }
+}
+impl PartialEq for SecretBytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+impl Eq for SecretBytes {}
+impl Debug for SecretBytes {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("SecretBytes").field("len", &self.bytes.len()).finish_non_exhaustive()
+    }
+}
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SecretBytes {
+    fn zeroize(&mut self) {
+        memzero(&mut self.bytes);
+    }
+}
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SecretBytes {}
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        // Erase the entire allocation, including any spare capacity of the original vector
+        let capacity = self.bytes.capacity() as u64;
+        unsafe { Lib_Memzero0_memzero(self.bytes.as_mut_ptr().cast(), capacity) };
+    }
+}
+
+/// A heap-allocated secret string, e.g. a PEM encoded private key
+///
+/// Like [`SecretBytes`], the string is never reallocated after construction, so erasing it on drop erases every copy.
+#[derive(Clone, Default)]
+pub struct SecretString {
+    /// The string
+    string: String,
+}
+impl SecretString {
+    /// The secret string
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+}
+impl From<String> for SecretString {
+    fn from(string: String) -> Self {
+        Self { string }
+    }
+}
+impl Deref for SecretString {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.string
+    }
+}
+impl AsRef<str> for SecretString {
+    fn as_ref(&self) -> &str {
+        &self.string
+    }
+}
+impl ConstantTimeEq for SecretString {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.string.as_bytes().ct_eq(other.string.as_bytes())
+    }
+}
+impl PartialEq for SecretString {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+impl Eq for SecretString {}
+impl Debug for SecretString {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("SecretString").field("len", &self.string.len()).finish_non_exhaustive()
+    }
+}
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SecretString {
+    fn zeroize(&mut self) {
+        // Zero bytes are valid UTF-8, so the string stays well-formed
+        memzero(unsafe { self.string.as_bytes_mut() });
+    }
+}
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SecretString {}
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        // Erase the entire allocation; zero bytes are valid UTF-8, so the string stays well-formed
+        let bytes = unsafe { self.string.as_mut_vec() };
+        let capacity = bytes.capacity() as u64;
+        unsafe { Lib_Memzero0_memzero(bytes.as_mut_ptr().cast(), capacity) };
+    }
+}
+
+/// A secret byte array of `N` bytes
+#[derive(Clone)]
+pub struct SecretArray<const N: usize> {
+    /// The bytes
+    bytes: [u8; N],
+}
+impl<const N: usize> SecretArray<N> {
+    /// Wraps `bytes`
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Self { bytes }
+    }
+
+    /// Copies `bytes` into a new secret
+    ///
+    /// This fails with [`Error::InvalidLength`] if `bytes` is not exactly `N` bytes long.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes = bytes.try_into().map_err(|_| Error::InvalidLength)?;
+        Ok(Self { bytes })
+    }
+
+    /// The secret bytes
+    pub const fn as_bytes(&self) -> &[u8; N] {
+        &self.bytes
+    }
+}
+impl<const N: usize> Default for SecretArray<N> {
+    fn default() -> Self {
+        Self { bytes: [0; N] }
+    }
+}
+impl<const N: usize> From<[u8; N]> for SecretArray<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Self { bytes }
+    }
+}
+impl<const N: usize> Deref for SecretArray<N> {
+    type Target = [u8; N];
+
+    fn deref(&self) -> &Self::Target {
+        &self.bytes
+    }
+}
+impl<const N: usize> DerefMut for SecretArray<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.bytes
+    }
+}
+impl<const N: usize> AsRef<[u8]> for SecretArray<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+impl<const N: usize> AsMut<[u8]> for SecretArray<N> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+}
+impl<const N: usize> ConstantTimeEq for SecretArray<N> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.bytes.ct_eq(&other.bytes)
+    }
+}
+impl<const N: usize> PartialEq for SecretArray<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+impl<const N: usize> Eq for SecretArray<N> {}
+impl<const N: usize> Debug for SecretArray<N> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("SecretArray").finish_non_exhaustive()
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<const N: usize> zeroize::Zeroize for SecretArray<N> {
+    fn zeroize(&mut self) {
+        memzero(&mut self.bytes);
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<const N: usize> zeroize::ZeroizeOnDrop for SecretArray<N> {}
+impl<const N: usize> Drop for SecretArray<N> {
+    fn drop(&mut self) {
+        memzero(&mut self.bytes);
+    }
+}
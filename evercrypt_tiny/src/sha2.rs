@@ -0,0 +1,153 @@
+//! Multi-buffer SHA-2 hashing
+//!
+//! [`hash_many`] hashes many independent inputs at once. Inputs of equal length are batched into HACL*'s 4 and 8 lane
+//! vector kernels if they were compiled in and are supported by the CPU; all remaining inputs are hashed with the
+//! portable `Hacl_SHA2_Scalar32` implementation.
+
+#[cfg(any(evercrypt_vec128, evercrypt_vec256))]
+use crate::cpu;
+use crate::{Error, HashAlgorithm};
+use alloc::vec::Vec;
+use core::array;
+use evercrypt_tiny_sys::{
+    Hacl_SHA2_Scalar32_sha224, Hacl_SHA2_Scalar32_sha256, Hacl_SHA2_Scalar32_sha384, Hacl_SHA2_Scalar32_sha512,
+};
+
+/// A kernel that hashes a single input
+type Kernel1 = unsafe extern "C" fn(*mut u8, u32, *mut u8);
+/// A kernel that hashes 4 inputs of equal length
+type Kernel4 = unsafe extern "C" fn(*mut u8, *mut u8, *mut u8, *mut u8, u32, *mut u8, *mut u8, *mut u8, *mut u8);
+/// A kernel that hashes 8 inputs of equal length
+type Kernel8 = unsafe extern "C" fn(
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    u32,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+    *mut u8,
+);
+
+/// The kernels that are available for a hash function on the current CPU
+#[derive(Debug, Clone, Copy)]
+struct Kernels {
+    /// The 8 lane kernel if available
+    x8: Option<Kernel8>,
+    /// The 4 lane kernel if available
+    x4: Option<Kernel4>,
+    /// The scalar kernel
+    x1: Kernel1,
+}
+impl Kernels {
+    /// Selects the fastest available kernels for `hash`
+    fn select(hash: HashAlgorithm) -> Result<Self, Error> {
+        #[cfg_attr(not(any(evercrypt_vec128, evercrypt_vec256)), allow(unused_mut))]
+        let mut kernels = match hash {
+            HashAlgorithm::Sha2_224 => Self { x8: None, x4: None, x1: Hacl_SHA2_Scalar32_sha224 },
+            HashAlgorithm::Sha2_256 => Self { x8: None, x4: None, x1: Hacl_SHA2_Scalar32_sha256 },
+            HashAlgorithm::Sha2_384 => Self { x8: None, x4: None, x1: Hacl_SHA2_Scalar32_sha384 },
+            HashAlgorithm::Sha2_512 => Self { x8: None, x4: None, x1: Hacl_SHA2_Scalar32_sha512 },
+            _ => return Err(Error::UnsupportedAlgorithm),
+        };
+
+        // The 128 bit kernels process 4 lanes of 32 bit words
+        #[cfg(evercrypt_vec128)]
+        if cpu::features() & cpu::AVX != 0 {
+            use evercrypt_tiny_sys::{Hacl_SHA2_Vec128_sha224_4, Hacl_SHA2_Vec128_sha256_4};
+            match hash {
+                HashAlgorithm::Sha2_224 => kernels.x4 = Some(Hacl_SHA2_Vec128_sha224_4),
+                HashAlgorithm::Sha2_256 => kernels.x4 = Some(Hacl_SHA2_Vec128_sha256_4),
+                _ => (),
+            }
+        }
+
+        // The 256 bit kernels process 8 lanes of 32 bit words or 4 lanes of 64 bit words
+        #[cfg(evercrypt_vec256)]
+        if cpu::features() & cpu::AVX2 != 0 {
+            use evercrypt_tiny_sys::{
+                Hacl_SHA2_Vec256_sha224_8, Hacl_SHA2_Vec256_sha256_8, Hacl_SHA2_Vec256_sha384_4,
+                Hacl_SHA2_Vec256_sha512_4,
+            };
+            match hash {
+                HashAlgorithm::Sha2_224 => kernels.x8 = Some(Hacl_SHA2_Vec256_sha224_8),
+                HashAlgorithm::Sha2_256 => kernels.x8 = Some(Hacl_SHA2_Vec256_sha256_8),
+                HashAlgorithm::Sha2_384 => kernels.x4 = Some(Hacl_SHA2_Vec256_sha384_4),
+                HashAlgorithm::Sha2_512 => kernels.x4 = Some(Hacl_SHA2_Vec256_sha512_4),
+                _ => (),
+            }
+        }
+        Ok(kernels)
+    }
+}
+
+/// Computes the SHA-2 digests of all `inputs` into the corresponding `digests`
+///
+/// `hash` must be a SHA-2 function, `digests` must have the same number of elements as `inputs`, and every digest must
+/// be exactly [`HashAlgorithm::digest_len`] bytes long. The inputs may have arbitrary lengths; inputs of equal length
+/// are hashed in parallel, so batches of equally sized chunks benefit the most.
+pub fn hash_many<D>(hash: HashAlgorithm, inputs: &[&[u8]], digests: &mut [D]) -> Result<(), Error>
+where
+    D: AsMut<[u8]>,
+{
+    // Validate the parameters
+    let kernels = Kernels::select(hash)?;
+    if inputs.len() != digests.len() || inputs.iter().any(|input| u32::try_from(input.len()).is_err()) {
+        return Err(Error::InvalidLength);
+    }
+    let mut outputs = Vec::with_capacity(digests.len());
+    for digest in digests.iter_mut() {
+        let digest = digest.as_mut();
+        if digest.len() != hash.digest_len() {
+            return Err(Error::InvalidLength);
+        }
+        outputs.push(digest.as_mut_ptr());
+    }
+
+    // Group the inputs by length
+    let mut order: Vec<usize> = (0..inputs.len()).collect();
+    order.sort_unstable_by_key(|&index| inputs[index].len());
+    for group in order.chunk_by(|&a, &b| inputs[a].len() == inputs[b].len()) {
+        let input_len = inputs[group[0]].len() as u32;
+
+        // Hash as many inputs as possible with the widest kernels
+        let mut rest = group;
+        if let Some(x8) = kernels.x8 {
+            let mut batches = rest.chunks_exact(8);
+            for batch in &mut batches {
+                let ([d0, d1, d2, d3, d4, d5, d6, d7], [s0, s1, s2, s3, s4, s5, s6, s7]) =
+                    lanes(batch, &outputs, inputs);
+                unsafe { x8(d0, d1, d2, d3, d4, d5, d6, d7, input_len, s0, s1, s2, s3, s4, s5, s6, s7) };
+            }
+            rest = batches.remainder();
+        }
+        if let Some(x4) = kernels.x4 {
+            let mut batches = rest.chunks_exact(4);
+            for batch in &mut batches {
+                let ([d0, d1, d2, d3], [s0, s1, s2, s3]) = lanes(batch, &outputs, inputs);
+                unsafe { x4(d0, d1, d2, d3, input_len, s0, s1, s2, s3) };
+            }
+            rest = batches.remainder();
+        }
+        for &index in rest {
+            unsafe { (kernels.x1)(outputs[index], input_len, inputs[index].as_ptr().cast_mut()) };
+        }
+    }
+    Ok(())
+}
+
+/// Collects the output and input pointers for a batch of `N` input indices
+fn lanes<const N: usize>(batch: &[usize], outputs: &[*mut u8], inputs: &[&[u8]]) -> ([*mut u8; N], [*mut u8; N]) {
+    let outputs = array::from_fn(|lane| outputs[batch[lane]]);
+    let inputs = array::from_fn(|lane| inputs[batch[lane]].as_ptr().cast_mut());
+    (outputs, inputs)
+}
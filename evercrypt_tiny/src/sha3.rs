@@ -0,0 +1,432 @@
+//! SHA-3 and SHAKE as specified in FIPS 202
+//!
+//! All functions share a streaming Keccak sponge that is built on HACL*'s `Hacl_Impl_SHA3` primitives: full blocks
+//! are absorbed with `loadState` and `state_permute`, the final padding is applied by `absorb`, and output blocks are
+//! squeezed with `storeState`. This allows incremental absorption as well as SHAKE readers that squeeze arbitrarily
+//! many bytes over multiple calls.
+
+use crate::secret;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    mem, slice,
+};
+use digest::{
+    consts::{U104, U136, U144, U168, U28, U32, U48, U64, U72},
+    crypto_common::BlockSizeUser,
+    generic_array::ArrayLength,
+    typenum::Unsigned,
+    ExtendableOutput, ExtendableOutputReset, FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset,
+    Update, XofReader,
+};
+use evercrypt_tiny_sys::{
+    Hacl_Impl_SHA3_absorb, Hacl_Impl_SHA3_loadState, Hacl_Impl_SHA3_state_permute, Hacl_Impl_SHA3_storeState,
+};
+
+/// The largest rate of all Keccak instances in bytes (SHAKE128)
+pub(crate) const MAX_RATE: usize = 168;
+/// The domain separation suffix of SHA-3 including the first padding bit
+const SHA3_SUFFIX: u8 = 0x06;
+/// The domain separation suffix of SHAKE including the first padding bit
+const SHAKE_SUFFIX: u8 = 0x1f;
+
+/// A streaming Keccak-f[1600] sponge with a rate of up to [`MAX_RATE`] bytes
+#[derive(Clone)]
+pub(crate) struct Keccak {
+    /// The permutation state
+    state: [u64; 25],
+    /// The rate in bytes
+    rate: usize,
+    /// The partial block that has not been absorbed yet
+    buffer: [u8; MAX_RATE],
+    /// The amount of bytes in `buffer`
+    buffered: usize,
+}
+impl Keccak {
+    /// Creates a new, empty sponge with a rate of `rate` bytes
+    pub const fn new(rate: usize) -> Self {
+        assert!(rate > 0 && rate <= MAX_RATE && rate.is_multiple_of(8), "Invalid Keccak rate");
+        Self { state: [0; 25], rate, buffer: [0; MAX_RATE], buffered: 0 }
+    }
+
+    /// The rate in bytes
+    pub const fn rate(&self) -> usize {
+        self.rate
+    }
+
+    /// Resets the sponge to the empty input
+    pub fn reset(&mut self) {
+        *self = Self::new(self.rate);
+    }
+
+    /// Absorbs `data`
+    pub fn absorb(&mut self, mut data: &[u8]) {
+        // Complete a pending partial block first
+        if self.buffered > 0 {
+            let take = data.len().min(self.rate - self.buffered);
+            self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+
+            // Keep the partial block if it is still incomplete
+            if self.buffered < self.rate {
+                return;
+            }
+            Self::absorb_block(&mut self.state, self.rate, &self.buffer);
+            self.buffered = 0;
+        }
+
+        // Absorb all full blocks directly and buffer the remainder
+        let mut blocks = data.chunks_exact(self.rate);
+        for block in blocks.by_ref() {
+            Self::absorb_block(&mut self.state, self.rate, block);
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    /// Pads the absorbed data with the domain separation `suffix` and returns a reader over the output stream
+    ///
+    /// The sponge is left unchanged, so more data can be absorbed afterwards.
+    pub fn finalize(&self, suffix: u8) -> KeccakReader {
+        // Absorb the last, partial block including the padding
+        let mut reader = KeccakReader { state: self.state, rate: self.rate, block: [0; MAX_RATE], position: 0 };
+        unsafe {
+            Hacl_Impl_SHA3_absorb(
+                reader.state.as_mut_ptr(),
+                self.rate as u32,
+                self.buffered as u32,
+                self.buffer.as_ptr().cast_mut(),
+                suffix,
+            )
+        };
+
+        // Extract the first output block
+        reader.store_block();
+        reader
+    }
+
+    /// XORs a full `block` into `state` and applies the permutation
+    fn absorb_block(state: &mut [u64; 25], rate: usize, block: &[u8]) {
+        debug_assert!(block.len() >= rate, "Block is too short");
+        unsafe {
+            Hacl_Impl_SHA3_loadState(rate as u32, block.as_ptr().cast_mut(), state.as_mut_ptr());
+            Hacl_Impl_SHA3_state_permute(state.as_mut_ptr());
+        }
+    }
+}
+impl Drop for Keccak {
+    fn drop(&mut self) {
+        // The sponge may have absorbed key material
+        erase_state(&mut self.state);
+        secret::memzero(&mut self.buffer);
+    }
+}
+
+/// A reader over the output stream of a finalized Keccak sponge
+#[derive(Clone)]
+pub struct KeccakReader {
+    /// The permutation state
+    state: [u64; 25],
+    /// The rate in bytes
+    rate: usize,
+    /// The current output block
+    block: [u8; MAX_RATE],
+    /// The amount of bytes of `block` that have already been read
+    position: usize,
+}
+impl KeccakReader {
+    /// Squeezes the next `buf.len()` bytes of the output stream into `buf`
+    pub fn read(&mut self, buf: &mut [u8]) {
+        for byte in buf.iter_mut() {
+            // Permute the state if the current block is exhausted
+            if self.position == self.rate {
+                unsafe { Hacl_Impl_SHA3_state_permute(self.state.as_mut_ptr()) };
+                self.store_block();
+            }
+            *byte = self.block[self.position];
+            self.position += 1;
+        }
+    }
+
+    /// Extracts the current output block from the state
+    fn store_block(&mut self) {
+        unsafe { Hacl_Impl_SHA3_storeState(self.rate as u32, self.state.as_mut_ptr(), self.block.as_mut_ptr()) };
+        self.position = 0;
+    }
+}
+impl Drop for KeccakReader {
+    fn drop(&mut self) {
+        erase_state(&mut self.state);
+        secret::memzero(&mut self.block);
+    }
+}
+impl Debug for KeccakReader {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("KeccakReader").field("rate", &self.rate).finish_non_exhaustive()
+    }
+}
+impl XofReader for KeccakReader {
+    fn read(&mut self, buffer: &mut [u8]) {
+        KeccakReader::read(self, buffer);
+    }
+}
+
+/// Erases a permutation `state`
+fn erase_state(state: &mut [u64; 25]) {
+    secret::memzero(unsafe { slice::from_raw_parts_mut(state.as_mut_ptr().cast(), mem::size_of_val(state)) });
+}
+
+/// A SHA-3 instance that is known at compile time
+pub trait Sha3Variant {
+    /// The digest length as type
+    type OutputSize: ArrayLength<u8> + 'static;
+    /// The rate as type
+    type BlockSize: ArrayLength<u8> + 'static;
+}
+
+/// SHA3-224 as [`Sha3Variant`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct V224;
+impl Sha3Variant for V224 {
+    type OutputSize = U28;
+    type BlockSize = U144;
+}
+
+/// SHA3-256 as [`Sha3Variant`] and SHAKE256 as [`ShakeVariant`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct V256;
+impl Sha3Variant for V256 {
+    type OutputSize = U32;
+    type BlockSize = U136;
+}
+
+/// SHA3-384 as [`Sha3Variant`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct V384;
+impl Sha3Variant for V384 {
+    type OutputSize = U48;
+    type BlockSize = U104;
+}
+
+/// SHA3-512 as [`Sha3Variant`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct V512;
+impl Sha3Variant for V512 {
+    type OutputSize = U64;
+    type BlockSize = U72;
+}
+
+/// SHA3-224
+pub type Sha3_224 = Sha3<V224>;
+/// SHA3-256
+pub type Sha3_256 = Sha3<V256>;
+/// SHA3-384
+pub type Sha3_384 = Sha3<V384>;
+/// SHA3-512
+pub type Sha3_512 = Sha3<V512>;
+
+/// An incremental SHA-3 computation
+///
+/// This type implements the traits required by [`digest::Digest`].
+pub struct Sha3<V> {
+    /// The sponge
+    keccak: Keccak,
+    /// The variant
+    _variant: PhantomData<V>,
+}
+impl<V> Sha3<V>
+where
+    V: Sha3Variant,
+{
+    /// Creates a new, empty computation
+    pub const fn new() -> Self {
+        Self { keccak: Keccak::new(V::BlockSize::USIZE), _variant: PhantomData }
+    }
+
+    /// Absorbs `data`
+    pub fn update(&mut self, data: &[u8]) {
+        self.keccak.absorb(data);
+    }
+
+    /// Computes the digest over all absorbed data
+    pub fn finalize(self) -> Output<Self> {
+        let mut digest = Output::<Self>::default();
+        self.finalize_into(&mut digest);
+        digest
+    }
+
+    /// Resets the computation to the empty input
+    pub fn reset(&mut self) {
+        self.keccak.reset();
+    }
+
+    /// Writes the digest over all absorbed data into `digest`
+    fn finalize_into(&self, digest: &mut [u8]) {
+        self.keccak.finalize(SHA3_SUFFIX).read(digest);
+    }
+}
+impl<V> Default for Sha3<V>
+where
+    V: Sha3Variant,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<V> Clone for Sha3<V> {
+    fn clone(&self) -> Self {
+        Self { keccak: self.keccak.clone(), _variant: PhantomData }
+    }
+}
+impl<V> Debug for Sha3<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Sha3").field("rate", &self.keccak.rate()).finish_non_exhaustive()
+    }
+}
+impl<V> HashMarker for Sha3<V> {}
+impl<V> OutputSizeUser for Sha3<V>
+where
+    V: Sha3Variant,
+{
+    type OutputSize = V::OutputSize;
+}
+impl<V> BlockSizeUser for Sha3<V>
+where
+    V: Sha3Variant,
+{
+    type BlockSize = V::BlockSize;
+}
+impl<V> Update for Sha3<V> {
+    fn update(&mut self, data: &[u8]) {
+        self.keccak.absorb(data);
+    }
+}
+impl<V> FixedOutput for Sha3<V>
+where
+    V: Sha3Variant,
+{
+    fn finalize_into(self, out: &mut Output<Self>) {
+        Sha3::finalize_into(&self, out);
+    }
+}
+impl<V> Reset for Sha3<V> {
+    fn reset(&mut self) {
+        self.keccak.reset();
+    }
+}
+impl<V> FixedOutputReset for Sha3<V>
+where
+    V: Sha3Variant,
+{
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        Sha3::finalize_into(self, out);
+        self.keccak.reset();
+    }
+}
+
+/// A SHAKE instance that is known at compile time
+pub trait ShakeVariant {
+    /// The rate as type
+    type BlockSize: ArrayLength<u8> + 'static;
+}
+
+/// SHAKE128 as [`ShakeVariant`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct V128;
+impl ShakeVariant for V128 {
+    type BlockSize = U168;
+}
+
+impl ShakeVariant for V256 {
+    type BlockSize = U136;
+}
+
+/// SHAKE128
+pub type Shake128 = Shake<V128>;
+/// SHAKE256
+pub type Shake256 = Shake<V256>;
+
+/// An incremental SHAKE computation
+///
+/// This type implements [`digest::ExtendableOutput`]; the output stream is read with a [`KeccakReader`].
+pub struct Shake<V> {
+    /// The sponge
+    keccak: Keccak,
+    /// The variant
+    _variant: PhantomData<V>,
+}
+impl<V> Shake<V>
+where
+    V: ShakeVariant,
+{
+    /// Creates a new, empty computation
+    pub const fn new() -> Self {
+        Self { keccak: Keccak::new(V::BlockSize::USIZE), _variant: PhantomData }
+    }
+
+    /// Absorbs `data`
+    pub fn update(&mut self, data: &[u8]) {
+        self.keccak.absorb(data);
+    }
+
+    /// Finishes absorption and returns a reader over the output stream
+    pub fn finalize_xof(self) -> KeccakReader {
+        self.keccak.finalize(SHAKE_SUFFIX)
+    }
+
+    /// Resets the computation to the empty input
+    pub fn reset(&mut self) {
+        self.keccak.reset();
+    }
+}
+impl<V> Default for Shake<V>
+where
+    V: ShakeVariant,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<V> Clone for Shake<V> {
+    fn clone(&self) -> Self {
+        Self { keccak: self.keccak.clone(), _variant: PhantomData }
+    }
+}
+impl<V> Debug for Shake<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Shake").field("rate", &self.keccak.rate()).finish_non_exhaustive()
+    }
+}
+impl<V> HashMarker for Shake<V> {}
+impl<V> BlockSizeUser for Shake<V>
+where
+    V: ShakeVariant,
+{
+    type BlockSize = V::BlockSize;
+}
+impl<V> Update for Shake<V> {
+    fn update(&mut self, data: &[u8]) {
+        self.keccak.absorb(data);
+    }
+}
+impl<V> ExtendableOutput for Shake<V> {
+    type Reader = KeccakReader;
+
+    fn finalize_xof(self) -> Self::Reader {
+        self.keccak.finalize(SHAKE_SUFFIX)
+    }
+}
+impl<V> Reset for Shake<V> {
+    fn reset(&mut self) {
+        self.keccak.reset();
+    }
+}
+impl<V> ExtendableOutputReset for Shake<V> {
+    fn finalize_xof_reset(&mut self) -> Self::Reader {
+        let reader = self.keccak.finalize(SHAKE_SUFFIX);
+        self.keccak.reset();
+        reader
+    }
+}
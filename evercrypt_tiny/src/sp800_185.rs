@@ -0,0 +1,307 @@
+//! cSHAKE, KMAC and TupleHash as specified in NIST SP 800-185
+//!
+//! All functions are built on the same streaming Keccak sponge as [`crate::sha3`] and are parameterized with the
+//! [`ShakeVariant`] markers [`V128`](crate::sha3::V128) and [`V256`](crate::sha3::V256).
+
+use crate::{
+    sha3::{Keccak, KeccakReader, ShakeVariant, MAX_RATE},
+    Error,
+};
+use alloc::vec;
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+use digest::{typenum::Unsigned, ExtendableOutput, Update};
+use subtle::ConstantTimeEq;
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The domain separation suffix of cSHAKE including the first padding bit
+const CSHAKE_SUFFIX: u8 = 0x04;
+/// The domain separation suffix of SHAKE including the first padding bit
+const SHAKE_SUFFIX: u8 = 0x1f;
+/// The function name of KMAC
+const KMAC_NAME: &[u8] = b"KMAC";
+/// The function name of TupleHash
+const TUPLE_HASH_NAME: &[u8] = b"TupleHash";
+/// The maximum length of an encoded integer (a length byte followed by up to 8 value bytes)
+const MAX_ENCODED_LEN: usize = 9;
+
+/// An integer encoded with `left_encode` or `right_encode`
+struct EncodedInt {
+    /// The encoding
+    bytes: [u8; MAX_ENCODED_LEN],
+    /// The length of the encoding
+    len: usize,
+}
+impl EncodedInt {
+    /// Encodes `value` with `left_encode`, i.e. the byte length followed by the big-endian value
+    fn left(value: u64) -> Self {
+        let (value, value_len) = Self::minimal_be(value);
+        let mut bytes = [0; MAX_ENCODED_LEN];
+        bytes[0] = value_len as u8;
+        bytes[1..=value_len].copy_from_slice(&value[8 - value_len..]);
+        Self { bytes, len: value_len + 1 }
+    }
+
+    /// Encodes `value` with `right_encode`, i.e. the big-endian value followed by the byte length
+    fn right(value: u64) -> Self {
+        let (value, value_len) = Self::minimal_be(value);
+        let mut bytes = [0; MAX_ENCODED_LEN];
+        bytes[..value_len].copy_from_slice(&value[8 - value_len..]);
+        bytes[value_len] = value_len as u8;
+        Self { bytes, len: value_len + 1 }
+    }
+
+    /// The big-endian encoding of `value` and the length of its minimal suffix, which is at least one byte
+    fn minimal_be(value: u64) -> ([u8; 8], usize) {
+        let value_len = (8 - value.leading_zeros() as usize / 8).max(1);
+        (value.to_be_bytes(), value_len)
+    }
+
+    /// The encoding
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+/// Converts a byte length into a bit length as used by the encodings
+fn bit_len(byte_len: usize) -> Result<u64, Error> {
+    u64::try_from(byte_len).ok().and_then(|len| len.checked_mul(8)).ok_or(Error::InvalidLength)
+}
+
+/// Absorbs `encode_string(string)`
+fn absorb_encoded_string(keccak: &mut Keccak, string: &[u8]) -> Result<usize, Error> {
+    let prefix = EncodedInt::left(bit_len(string.len())?);
+    keccak.absorb(prefix.as_bytes());
+    keccak.absorb(string);
+    Ok(prefix.len + string.len())
+}
+
+/// Absorbs `bytepad(encode_string(strings[0]) || encode_string(strings[1]) || ..., rate)`
+fn absorb_bytepad(keccak: &mut Keccak, strings: &[&[u8]]) -> Result<(), Error> {
+    // Absorb the encoded rate and strings
+    let rate = keccak.rate();
+    let prefix = EncodedInt::left(rate as u64);
+    keccak.absorb(prefix.as_bytes());
+    let mut absorbed = prefix.len;
+    for string in strings {
+        absorbed += absorb_encoded_string(keccak, string)?;
+    }
+
+    // Pad with zero bytes to the next block boundary
+    let padding = (rate - absorbed % rate) % rate;
+    keccak.absorb(&[0; MAX_RATE][..padding]);
+    Ok(())
+}
+
+/// Creates the sponge for `cSHAKE(X, L, function_name, customization)` and returns it together with the suffix that
+/// must be used to finalize it
+fn cshake_init(rate: usize, function_name: &[u8], customization: &[u8]) -> Result<(Keccak, u8), Error> {
+    // cSHAKE with empty strings is defined to be SHAKE
+    let mut keccak = Keccak::new(rate);
+    if function_name.is_empty() && customization.is_empty() {
+        return Ok((keccak, SHAKE_SUFFIX));
+    }
+
+    absorb_bytepad(&mut keccak, &[function_name, customization])?;
+    Ok((keccak, CSHAKE_SUFFIX))
+}
+
+/// cSHAKE128
+pub type CShake128 = CShake<crate::sha3::V128>;
+/// cSHAKE256
+pub type CShake256 = CShake<crate::sha3::V256>;
+
+/// An incremental cSHAKE computation
+///
+/// This type implements [`digest::ExtendableOutput`]; the output stream is read with a [`KeccakReader`].
+pub struct CShake<V> {
+    /// The sponge
+    keccak: Keccak,
+    /// The domain separation suffix
+    suffix: u8,
+    /// The variant
+    _variant: PhantomData<V>,
+}
+impl<V> CShake<V>
+where
+    V: ShakeVariant,
+{
+    /// Creates a new computation with the NIST-defined `function_name` and a user-defined `customization` string
+    ///
+    /// If both strings are empty, the computation is equivalent to SHAKE.
+    pub fn new(function_name: &[u8], customization: &[u8]) -> Result<Self, Error> {
+        let (keccak, suffix) = cshake_init(V::BlockSize::USIZE, function_name, customization)?;
+        Ok(Self { keccak, suffix, _variant: PhantomData })
+    }
+
+    /// Absorbs `data`
+    pub fn update(&mut self, data: &[u8]) {
+        self.keccak.absorb(data);
+    }
+
+    /// Finishes absorption and returns a reader over the output stream
+    pub fn finalize_xof(self) -> KeccakReader {
+        self.keccak.finalize(self.suffix)
+    }
+}
+impl<V> Clone for CShake<V> {
+    fn clone(&self) -> Self {
+        Self { keccak: self.keccak.clone(), suffix: self.suffix, _variant: PhantomData }
+    }
+}
+impl<V> Debug for CShake<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("CShake").field("rate", &self.keccak.rate()).finish_non_exhaustive()
+    }
+}
+impl<V> Update for CShake<V> {
+    fn update(&mut self, data: &[u8]) {
+        self.keccak.absorb(data);
+    }
+}
+impl<V> ExtendableOutput for CShake<V> {
+    type Reader = KeccakReader;
+
+    fn finalize_xof(self) -> Self::Reader {
+        self.keccak.finalize(self.suffix)
+    }
+}
+
+/// KMAC128
+pub type Kmac128 = Kmac<crate::sha3::V128>;
+/// KMAC256
+pub type Kmac256 = Kmac<crate::sha3::V256>;
+
+/// An incremental KMAC computation
+///
+/// The requested output length is part of the MAC input, so [`Kmac::finalize_into`] outputs of different lengths are
+/// unrelated. [`Kmac::finalize_xof`] computes KMACXOF instead, whose outputs are prefixes of each other.
+pub struct Kmac<V> {
+    /// The sponge which has already absorbed the key
+    keccak: Keccak,
+    /// The variant
+    _variant: PhantomData<V>,
+}
+impl<V> Kmac<V>
+where
+    V: ShakeVariant,
+{
+    /// Creates a new KMAC computation with `key` and an optional `customization` string
+    pub fn new(key: &[u8], customization: &[u8]) -> Result<Self, Error> {
+        let (mut keccak, _) = cshake_init(V::BlockSize::USIZE, KMAC_NAME, customization)?;
+        absorb_bytepad(&mut keccak, &[key])?;
+        Ok(Self { keccak, _variant: PhantomData })
+    }
+
+    /// Absorbs `data`
+    pub fn update(&mut self, data: &[u8]) {
+        self.keccak.absorb(data);
+    }
+
+    /// Computes the MAC over all absorbed data with a length of `mac.len()` bytes into `mac`
+    pub fn finalize_into(mut self, mac: &mut [u8]) -> Result<(), Error> {
+        let length = EncodedInt::right(bit_len(mac.len())?);
+        self.keccak.absorb(length.as_bytes());
+        self.keccak.finalize(CSHAKE_SUFFIX).read(mac);
+        Ok(())
+    }
+
+    /// Verifies in constant time that `tag` is the MAC with a length of `tag.len()` bytes over all absorbed data
+    pub fn verify(self, tag: &[u8]) -> Result<(), Error> {
+        let mut mac = vec![0; tag.len()];
+        self.finalize_into(&mut mac)?;
+        match bool::from(mac.ct_eq(tag)) {
+            true => Ok(()),
+            false => Err(Error::VerificationFailed),
+        }
+    }
+
+    /// Finishes absorption for KMACXOF and returns a reader over the output stream
+    pub fn finalize_xof(mut self) -> KeccakReader {
+        self.keccak.absorb(EncodedInt::right(0).as_bytes());
+        self.keccak.finalize(CSHAKE_SUFFIX)
+    }
+}
+impl<V> Clone for Kmac<V> {
+    fn clone(&self) -> Self {
+        Self { keccak: self.keccak.clone(), _variant: PhantomData }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<V> Zeroize for Kmac<V> {
+    fn zeroize(&mut self) {
+        // Replacing the sponge erases the keyed state and leaves one that has absorbed nothing
+        self.keccak.reset();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl<V> ZeroizeOnDrop for Kmac<V> {}
+impl<V> Debug for Kmac<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Kmac").field("rate", &self.keccak.rate()).finish_non_exhaustive()
+    }
+}
+impl<V> Update for Kmac<V> {
+    fn update(&mut self, data: &[u8]) {
+        self.keccak.absorb(data);
+    }
+}
+
+/// TupleHash128
+pub type TupleHash128 = TupleHash<crate::sha3::V128>;
+/// TupleHash256
+pub type TupleHash256 = TupleHash<crate::sha3::V256>;
+
+/// An incremental TupleHash computation over a sequence of byte strings
+///
+/// Unlike a plain hash, the boundaries between the elements are part of the input, so `("ab", "c")` and `("a", "bc")`
+/// hash to different values.
+pub struct TupleHash<V> {
+    /// The sponge
+    keccak: Keccak,
+    /// The variant
+    _variant: PhantomData<V>,
+}
+impl<V> TupleHash<V>
+where
+    V: ShakeVariant,
+{
+    /// Creates a new computation with an optional `customization` string
+    pub fn new(customization: &[u8]) -> Result<Self, Error> {
+        let (keccak, _) = cshake_init(V::BlockSize::USIZE, TUPLE_HASH_NAME, customization)?;
+        Ok(Self { keccak, _variant: PhantomData })
+    }
+
+    /// Appends `element` to the tuple
+    pub fn push(&mut self, element: &[u8]) -> Result<(), Error> {
+        absorb_encoded_string(&mut self.keccak, element)?;
+        Ok(())
+    }
+
+    /// Computes the hash over all elements with a length of `digest.len()` bytes into `digest`
+    pub fn finalize_into(mut self, digest: &mut [u8]) -> Result<(), Error> {
+        let length = EncodedInt::right(bit_len(digest.len())?);
+        self.keccak.absorb(length.as_bytes());
+        self.keccak.finalize(CSHAKE_SUFFIX).read(digest);
+        Ok(())
+    }
+
+    /// Finishes the tuple for TupleHashXOF and returns a reader over the output stream
+    pub fn finalize_xof(mut self) -> KeccakReader {
+        self.keccak.absorb(EncodedInt::right(0).as_bytes());
+        self.keccak.finalize(CSHAKE_SUFFIX)
+    }
+}
+impl<V> Clone for TupleHash<V> {
+    fn clone(&self) -> Self {
+        Self { keccak: self.keccak.clone(), _variant: PhantomData }
+    }
+}
+impl<V> Debug for TupleHash<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("TupleHash").field("rate", &self.keccak.rate()).finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,434 @@
+//! The TLS 1.3 key schedule as specified in RFC 8446, section 7.1 and the QUIC packet protection keys as specified in
+//! RFC 9001, section 5
+//!
+//! The schedule is modelled as a chain of typed stages: [`EarlySecret`] → [`HandshakeSecret`] → [`MasterSecret`].
+//! Each stage derives its traffic secrets from a transcript hash, which can be computed incrementally with
+//! [`Transcript`]. Traffic secrets are HKDF pseudorandom keys and are turned into AEAD keys and IVs with
+//! [`CipherSuite::traffic_keys`] or [`CipherSuite::quic_keys`].
+
+use crate::{
+    hash::HashState,
+    hkdf::{Hkdf, Prk},
+    hmac::Hmac,
+    secret::{SecretArray, SecretBytes},
+    Error, HashAlgorithm,
+};
+use alloc::{vec, vec::Vec};
+use core::fmt::{self, Debug, Formatter};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The length of an AEAD IV in bytes
+pub const IV_LEN: usize = 12;
+/// The prefix of all `HkdfLabel` labels
+const LABEL_PREFIX: &[u8] = b"tls13 ";
+/// The handshake type of the synthetic `message_hash` message (RFC 8446, section 4.4.1)
+const MESSAGE_HASH: u8 = 254;
+/// The QUIC version 1 initial salt (RFC 9001, section 5.2)
+const QUIC_V1_INITIAL_SALT: [u8; 20] = [
+    0x38, 0x76, 0x2c, 0xf7, 0xf5, 0x59, 0x34, 0xb3, 0x4d, 0x17, 0x9a, 0xe6, 0xa4, 0xc8, 0x0c, 0xad, 0xcc, 0xbb, 0x7f,
+    0x0a,
+];
+
+/// A TLS 1.3 cipher suite
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CipherSuite {
+    /// `TLS_AES_128_GCM_SHA256`
+    Aes128GcmSha256,
+    /// `TLS_AES_256_GCM_SHA384`
+    Aes256GcmSha384,
+    /// `TLS_CHACHA20_POLY1305_SHA256`
+    ChaCha20Poly1305Sha256,
+}
+impl CipherSuite {
+    /// The hash function of the key schedule
+    pub const fn hash(self) -> HashAlgorithm {
+        match self {
+            Self::Aes128GcmSha256 | Self::ChaCha20Poly1305Sha256 => HashAlgorithm::Sha2_256,
+            Self::Aes256GcmSha384 => HashAlgorithm::Sha2_384,
+        }
+    }
+
+    /// The AEAD key length in bytes, which is also the QUIC header protection key length
+    pub const fn key_len(self) -> usize {
+        match self {
+            Self::Aes128GcmSha256 => 16,
+            Self::Aes256GcmSha384 | Self::ChaCha20Poly1305Sha256 => 32,
+        }
+    }
+
+    /// Derives the record protection key and IV from a traffic secret (RFC 8446, section 7.3)
+    pub fn traffic_keys(self, secret: &Prk) -> Result<TrafficKeys, Error> {
+        self.check_secret(secret)?;
+        let mut keys = TrafficKeys { key: SecretBytes::new(self.key_len()), iv: SecretArray::default() };
+        hkdf_expand_label(secret, b"key", b"", &mut keys.key)?;
+        hkdf_expand_label(secret, b"iv", b"", &mut keys.iv[..])?;
+        Ok(keys)
+    }
+
+    /// Derives the QUIC packet protection key, IV and header protection key from a traffic secret (RFC 9001,
+    /// section 5.1)
+    pub fn quic_keys(self, secret: &Prk) -> Result<QuicKeys, Error> {
+        self.check_secret(secret)?;
+        let mut keys = QuicKeys {
+            key: SecretBytes::new(self.key_len()),
+            iv: SecretArray::default(),
+            hp: SecretBytes::new(self.key_len()),
+        };
+        hkdf_expand_label(secret, b"quic key", b"", &mut keys.key)?;
+        hkdf_expand_label(secret, b"quic iv", b"", &mut keys.iv[..])?;
+        hkdf_expand_label(secret, b"quic hp", b"", &mut keys.hp)?;
+        Ok(keys)
+    }
+
+    /// Ensures that `secret` belongs to the hash function of this suite
+    fn check_secret(self, secret: &Prk) -> Result<(), Error> {
+        match secret.hash() == self.hash() {
+            true => Ok(()),
+            false => Err(Error::InvalidKey),
+        }
+    }
+}
+
+/// A record protection key and IV
+#[derive(Clone)]
+pub struct TrafficKeys {
+    /// The AEAD key
+    key: SecretBytes,
+    /// The static IV which is XORed with the record sequence number
+    iv: SecretArray<IV_LEN>,
+}
+impl TrafficKeys {
+    /// The AEAD key
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+    /// The static IV which is XORed with the record sequence number
+    pub fn iv(&self) -> &[u8; IV_LEN] {
+        self.iv.as_bytes()
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for TrafficKeys {
+    fn zeroize(&mut self) {
+        self.key.zeroize();
+        self.iv.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for TrafficKeys {}
+impl Debug for TrafficKeys {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("TrafficKeys").finish_non_exhaustive()
+    }
+}
+
+/// A QUIC packet protection key, IV and header protection key
+#[derive(Clone)]
+pub struct QuicKeys {
+    /// The AEAD key
+    key: SecretBytes,
+    /// The static IV which is XORed with the packet number
+    iv: SecretArray<IV_LEN>,
+    /// The header protection key
+    hp: SecretBytes,
+}
+impl QuicKeys {
+    /// The AEAD key
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+    /// The static IV which is XORed with the packet number
+    pub fn iv(&self) -> &[u8; IV_LEN] {
+        self.iv.as_bytes()
+    }
+    /// The header protection key
+    pub fn hp(&self) -> &[u8] {
+        &self.hp
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for QuicKeys {
+    fn zeroize(&mut self) {
+        self.key.zeroize();
+        self.iv.zeroize();
+        self.hp.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for QuicKeys {}
+impl Debug for QuicKeys {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("QuicKeys").finish_non_exhaustive()
+    }
+}
+
+/// A running transcript hash over the handshake messages
+#[derive(Debug)]
+pub struct Transcript {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The streaming hash state
+    state: HashState,
+}
+impl Transcript {
+    /// Creates a new, empty transcript for `suite`
+    pub fn new(suite: CipherSuite) -> Self {
+        Self { hash: suite.hash(), state: HashState::new(suite.hash()) }
+    }
+
+    /// Appends an encoded handshake message including its header
+    pub fn update(&mut self, message: &[u8]) {
+        self.state.update(message);
+    }
+
+    /// Replaces the transcript, which must only contain the first `ClientHello`, with the synthetic `message_hash`
+    /// message after a `HelloRetryRequest` (RFC 8446, section 4.4.1)
+    pub fn hello_retry_request(&mut self) {
+        let client_hello_hash = self.current_hash();
+        self.state.reset();
+        self.state.update(&[MESSAGE_HASH, 0, 0, client_hello_hash.len() as u8]);
+        self.state.update(&client_hello_hash);
+    }
+
+    /// The hash over all messages so far
+    pub fn current_hash(&self) -> Vec<u8> {
+        let mut hash = vec![0; self.hash.digest_len()];
+        self.state.finish_into(&mut hash);
+        hash
+    }
+}
+
+/// Computes `HKDF-Expand-Label(secret, label, context, okm.len())` (RFC 8446, section 7.1)
+///
+/// `label` must not include the `"tls13 "` prefix.
+pub fn hkdf_expand_label(secret: &Prk, label: &[u8], context: &[u8], okm: &mut [u8]) -> Result<(), Error> {
+    // Encode the length fields of the `HkdfLabel` structure
+    let length = u16::try_from(okm.len()).map_err(|_| Error::InvalidLength)?.to_be_bytes();
+    let label_len = u8::try_from(LABEL_PREFIX.len() + label.len()).map_err(|_| Error::InvalidLength)?;
+    let context_len = u8::try_from(context.len()).map_err(|_| Error::InvalidLength)?;
+
+    // Expand the secret over the structure without concatenating it
+    let info: [&[u8]; 6] = [&length, &[label_len], LABEL_PREFIX, label, &[context_len], context];
+    secret.expand_multi_info(&info, okm)
+}
+
+/// Computes `Derive-Secret(secret, label, messages)` from the hash of the messages (RFC 8446, section 7.1)
+pub fn derive_secret(secret: &Prk, label: &[u8], transcript_hash: &[u8]) -> Result<Prk, Error> {
+    // Validate the transcript hash; this also bounds the context length
+    let digest_len = secret.hash().digest_len();
+    if transcript_hash.len() != digest_len {
+        return Err(Error::InvalidLength);
+    }
+
+    // Expand the secret over the `HkdfLabel` structure
+    let length = (digest_len as u16).to_be_bytes();
+    let label_len = u8::try_from(LABEL_PREFIX.len() + label.len()).map_err(|_| Error::InvalidLength)?;
+    let context_len = digest_len as u8;
+    let info: [&[u8]; 6] = [&length, &[label_len], LABEL_PREFIX, label, &[context_len], transcript_hash];
+    Ok(secret.expand_prk(&info))
+}
+
+/// Derives the next generation of a traffic secret for a key update (RFC 8446, section 7.2)
+pub fn next_traffic_secret(secret: &Prk) -> Prk {
+    expand_label_prk(secret, b"traffic upd")
+}
+
+/// Computes the `verify_data` of a `Finished` message from the sender's base key and the transcript hash (RFC 8446,
+/// section 4.4.4)
+pub fn finished_verify_data(secret: &Prk, transcript_hash: &[u8]) -> Vec<u8> {
+    let finished_key = expand_label_prk(secret, b"finished");
+    let mut hmac = Hmac::new_unchecked(secret.hash(), finished_key.as_bytes());
+    hmac.update(transcript_hash);
+    hmac.finalize()
+}
+
+/// Derives the client and server initial secrets of QUIC version 1 from the client's destination connection ID
+/// (RFC 9001, section 5.2)
+///
+/// Initial packets are always protected with [`CipherSuite::Aes128GcmSha256`].
+pub fn quic_initial_secrets(connection_id: &[u8]) -> (Prk, Prk) {
+    let initial_secret = Hkdf::new_unchecked(HashAlgorithm::Sha2_256).extract(&QUIC_V1_INITIAL_SALT, connection_id);
+    (expand_label_prk(&initial_secret, b"client in"), expand_label_prk(&initial_secret, b"server in"))
+}
+
+/// Derives the next generation of a QUIC packet protection secret for a key update (RFC 9001, section 6.1)
+///
+/// The header protection key is not updated and must be retained from the first generation.
+pub fn quic_next_secret(secret: &Prk) -> Prk {
+    expand_label_prk(secret, b"quic ku")
+}
+
+/// Computes `HKDF-Expand-Label(secret, label, "", Hash.length)` for a constant, short `label`
+fn expand_label_prk(secret: &Prk, label: &'static [u8]) -> Prk {
+    let length = (secret.hash().digest_len() as u16).to_be_bytes();
+    let label_len = (LABEL_PREFIX.len() + label.len()) as u8;
+    let info: [&[u8]; 5] = [&length, &[label_len], LABEL_PREFIX, label, &[0]];
+    secret.expand_prk(&info)
+}
+
+/// Computes the hash of the empty transcript
+fn empty_hash(hash: HashAlgorithm) -> Vec<u8> {
+    let mut digest = vec![0; hash.digest_len()];
+    HashState::new(hash).finish_into(&mut digest);
+    digest
+}
+
+/// Computes `Derive-Secret(secret, "derived", "")` and extracts the next stage's secret from it and `ikm`, or from
+/// `HashLen` zero bytes if `ikm` is not present
+fn next_stage(secret: &Prk, ikm: Option<&[u8]>) -> Prk {
+    // Derive the salt and extract the next secret
+    let hash = secret.hash();
+    let salt = derive_secret(secret, b"derived", &empty_hash(hash)).expect("Invalid transcript hash");
+    let zeros = [0; 64];
+    let ikm = ikm.unwrap_or(&zeros[..hash.digest_len()]);
+    Hkdf::new_unchecked(hash).extract(salt.as_bytes(), ikm)
+}
+
+/// The early secret, which is extracted from an optional pre-shared key
+pub struct EarlySecret {
+    /// The cipher suite
+    suite: CipherSuite,
+    /// The secret
+    secret: Prk,
+}
+impl EarlySecret {
+    /// Extracts the early secret from `psk`, or from `HashLen` zero bytes if no PSK is used
+    pub fn new(suite: CipherSuite, psk: Option<&[u8]>) -> Self {
+        let zeros = [0; 64];
+        let ikm = psk.unwrap_or(&zeros[..suite.hash().digest_len()]);
+        Self { suite, secret: Hkdf::new_unchecked(suite.hash()).extract(&[], ikm) }
+    }
+
+    /// The cipher suite
+    pub const fn suite(&self) -> CipherSuite {
+        self.suite
+    }
+
+    /// Derives the PSK binder key for an external (`"ext binder"`) or resumption (`"res binder"`) PSK
+    pub fn binder_key(&self, external: bool) -> Prk {
+        let label: &[u8] = match external {
+            true => b"ext binder",
+            false => b"res binder",
+        };
+        derive_secret(&self.secret, label, &empty_hash(self.suite.hash())).expect("Invalid transcript hash")
+    }
+
+    /// Derives `client_early_traffic_secret` from the hash over the `ClientHello`
+    pub fn client_early_traffic_secret(&self, transcript_hash: &[u8]) -> Result<Prk, Error> {
+        derive_secret(&self.secret, b"c e traffic", transcript_hash)
+    }
+
+    /// Derives `early_exporter_master_secret` from the hash over the `ClientHello`
+    pub fn early_exporter_master_secret(&self, transcript_hash: &[u8]) -> Result<Prk, Error> {
+        derive_secret(&self.secret, b"e exp master", transcript_hash)
+    }
+
+    /// Advances to the handshake secret with the (EC)DHE shared secret, or `HashLen` zero bytes for PSK-only
+    /// handshakes
+    pub fn into_handshake_secret(self, shared_secret: Option<&[u8]>) -> HandshakeSecret {
+        HandshakeSecret { suite: self.suite, secret: next_stage(&self.secret, shared_secret) }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for EarlySecret {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for EarlySecret {}
+impl Debug for EarlySecret {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("EarlySecret").field("suite", &self.suite).finish_non_exhaustive()
+    }
+}
+
+/// The handshake secret
+pub struct HandshakeSecret {
+    /// The cipher suite
+    suite: CipherSuite,
+    /// The secret
+    secret: Prk,
+}
+impl HandshakeSecret {
+    /// The cipher suite
+    pub const fn suite(&self) -> CipherSuite {
+        self.suite
+    }
+
+    /// Derives `client_handshake_traffic_secret` from the hash over `ClientHello...ServerHello`
+    pub fn client_handshake_traffic_secret(&self, transcript_hash: &[u8]) -> Result<Prk, Error> {
+        derive_secret(&self.secret, b"c hs traffic", transcript_hash)
+    }
+
+    /// Derives `server_handshake_traffic_secret` from the hash over `ClientHello...ServerHello`
+    pub fn server_handshake_traffic_secret(&self, transcript_hash: &[u8]) -> Result<Prk, Error> {
+        derive_secret(&self.secret, b"s hs traffic", transcript_hash)
+    }
+
+    /// Advances to the master secret
+    pub fn into_master_secret(self) -> MasterSecret {
+        MasterSecret { suite: self.suite, secret: next_stage(&self.secret, None) }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for HandshakeSecret {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for HandshakeSecret {}
+impl Debug for HandshakeSecret {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("HandshakeSecret").field("suite", &self.suite).finish_non_exhaustive()
+    }
+}
+
+/// The master secret
+pub struct MasterSecret {
+    /// The cipher suite
+    suite: CipherSuite,
+    /// The secret
+    secret: Prk,
+}
+impl MasterSecret {
+    /// The cipher suite
+    pub const fn suite(&self) -> CipherSuite {
+        self.suite
+    }
+
+    /// Derives `client_application_traffic_secret_0` from the hash over `ClientHello...server Finished`
+    pub fn client_application_traffic_secret(&self, transcript_hash: &[u8]) -> Result<Prk, Error> {
+        derive_secret(&self.secret, b"c ap traffic", transcript_hash)
+    }
+
+    /// Derives `server_application_traffic_secret_0` from the hash over `ClientHello...server Finished`
+    pub fn server_application_traffic_secret(&self, transcript_hash: &[u8]) -> Result<Prk, Error> {
+        derive_secret(&self.secret, b"s ap traffic", transcript_hash)
+    }
+
+    /// Derives `exporter_master_secret` from the hash over `ClientHello...server Finished`
+    pub fn exporter_master_secret(&self, transcript_hash: &[u8]) -> Result<Prk, Error> {
+        derive_secret(&self.secret, b"exp master", transcript_hash)
+    }
+
+    /// Derives `resumption_master_secret` from the hash over `ClientHello...client Finished`
+    pub fn resumption_master_secret(&self, transcript_hash: &[u8]) -> Result<Prk, Error> {
+        derive_secret(&self.secret, b"res master", transcript_hash)
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for MasterSecret {
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for MasterSecret {}
+impl Debug for MasterSecret {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("MasterSecret").field("suite", &self.suite).finish_non_exhaustive()
+    }
+}
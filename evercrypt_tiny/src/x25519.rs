@@ -0,0 +1,97 @@
+//! X25519 key agreement as specified in RFC 7748
+
+use crate::{secret::SecretArray, Error};
+use core::fmt::{self, Debug, Formatter};
+use evercrypt_tiny_sys::{EverCrypt_Curve25519_ecdh, EverCrypt_Curve25519_secret_to_public};
+use rand_core::{CryptoRng, RngCore};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The length of a secret key
+pub const SECRET_KEY_LEN: usize = 32;
+/// The length of a public key
+pub const PUBLIC_KEY_LEN: usize = 32;
+/// The length of a shared secret
+pub const SHARED_SECRET_LEN: usize = 32;
+
+/// An X25519 secret key
+#[derive(Clone)]
+pub struct X25519SecretKey {
+    /// The scalar
+    scalar: SecretArray<SECRET_KEY_LEN>,
+}
+impl X25519SecretKey {
+    /// Generates a new random secret key
+    pub fn generate<R>(rng: &mut R) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut scalar = SecretArray::default();
+        rng.try_fill_bytes(&mut scalar[..]).map_err(|_| Error::RngFailure)?;
+        Ok(Self { scalar })
+    }
+
+    /// Loads a secret key from its 32 byte representation
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let scalar = SecretArray::from_slice(bytes).map_err(|_| Error::InvalidKey)?;
+        Ok(Self { scalar })
+    }
+
+    /// Returns the 32 byte representation
+    pub fn to_bytes(&self) -> SecretArray<SECRET_KEY_LEN> {
+        self.scalar.clone()
+    }
+
+    /// Computes the public key that belongs to this secret key
+    pub fn public_key(&self) -> X25519PublicKey {
+        let (mut scalar, mut point) = (self.scalar.clone(), [0; PUBLIC_KEY_LEN]);
+        unsafe { EverCrypt_Curve25519_secret_to_public(point.as_mut_ptr(), scalar.as_mut_ptr()) };
+        X25519PublicKey { point }
+    }
+
+    /// Performs a key agreement with `public_key`
+    ///
+    /// This fails if the shared secret is all-zero, which happens for low-order public keys.
+    pub fn diffie_hellman(&self, public_key: &X25519PublicKey) -> Result<SecretArray<SHARED_SECRET_LEN>, Error> {
+        // `shared` is erased on drop if the key agreement fails
+        let (mut scalar, mut point) = (self.scalar.clone(), public_key.point);
+        let mut shared = SecretArray::<SHARED_SECRET_LEN>::default();
+        let is_ok = unsafe { EverCrypt_Curve25519_ecdh(shared.as_mut_ptr(), scalar.as_mut_ptr(), point.as_mut_ptr()) };
+        match is_ok {
+            true => Ok(shared),
+            false => Err(Error::InvalidKey),
+        }
+    }
+}
+#[cfg(feature = "zeroize")]
+impl Zeroize for X25519SecretKey {
+    fn zeroize(&mut self) {
+        self.scalar.zeroize();
+    }
+}
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for X25519SecretKey {}
+impl Debug for X25519SecretKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("X25519SecretKey").finish_non_exhaustive()
+    }
+}
+
+/// An X25519 public key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct X25519PublicKey {
+    /// The u-coordinate
+    point: [u8; PUBLIC_KEY_LEN],
+}
+impl X25519PublicKey {
+    /// Loads a public key from its 32 byte representation
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let point = bytes.try_into().map_err(|_| Error::InvalidKey)?;
+        Ok(Self { point })
+    }
+
+    /// Returns the 32 byte representation
+    pub fn to_bytes(&self) -> [u8; PUBLIC_KEY_LEN] {
+        self.point
+    }
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::hex;
+use digest::{Update, VariableOutput};
+use evercrypt_tiny::{
+    blake2::{Blake2Params, Blake2b, Blake2s},
+    Error,
+};
+
+/// The keyed test vectors from the official BLAKE2 repository
+const BLAKE2B_KAT: &str = include_str!("fixtures/blake2b-kat.txt");
+/// The keyed test vectors from the official BLAKE2 repository
+const BLAKE2S_KAT: &str = include_str!("fixtures/blake2s-kat.txt");
+
+/// A known-answer test vector
+struct KatVector {
+    /// The input
+    input: Vec<u8>,
+    /// The key
+    key: Vec<u8>,
+    /// The digest
+    hash: Vec<u8>,
+}
+impl KatVector {
+    /// Parses all vectors from a KAT file
+    fn parse(kat: &str) -> Vec<Self> {
+        kat.split("\n\n")
+            .map(|vector| {
+                let mut lines = vector.lines().map(|line| line.split_once('\t').expect("Invalid KAT line").1);
+                let mut next = || hex(lines.next().expect("Truncated KAT vector"));
+                Self { input: next(), key: next(), hash: next() }
+            })
+            .collect()
+    }
+}
+
+/// Tests the official BLAKE2b KAT vectors with one-shot and byte-wise updates
+#[test]
+fn blake2b_kat() {
+    let vectors = KatVector::parse(BLAKE2B_KAT);
+    assert_eq!(vectors.len(), 256);
+
+    for vector in vectors {
+        let params = Blake2Params::new(64).with_key(&vector.key);
+        let mut blake2b = Blake2b::new(&params).expect("Failed to create BLAKE2b");
+        blake2b.update(&vector.input);
+        assert_eq!(blake2b.finalize(), vector.hash, "Invalid digest for input length {}", vector.input.len());
+
+        let mut blake2b = Blake2b::new(&params).expect("Failed to create BLAKE2b");
+        vector.input.chunks(1).for_each(|byte| blake2b.update(byte));
+        blake2b.verify(&vector.hash).expect("Invalid MAC");
+    }
+}
+
+/// Tests the official BLAKE2s KAT vectors with one-shot and byte-wise updates
+#[test]
+fn blake2s_kat() {
+    let vectors = KatVector::parse(BLAKE2S_KAT);
+    assert_eq!(vectors.len(), 256);
+
+    for vector in vectors {
+        let params = Blake2Params::new(32).with_key(&vector.key);
+        let mut blake2s = Blake2s::new(&params).expect("Failed to create BLAKE2s");
+        blake2s.update(&vector.input);
+        assert_eq!(blake2s.finalize(), vector.hash, "Invalid digest for input length {}", vector.input.len());
+
+        let mut blake2s = Blake2s::new(&params).expect("Failed to create BLAKE2s");
+        vector.input.chunks(1).for_each(|byte| blake2s.update(byte));
+        blake2s.verify(&vector.hash).expect("Invalid MAC");
+    }
+}
+
+/// Tests unkeyed hashing, truncated digests, salts and personalization strings
+#[test]
+fn parameters() {
+    // The RFC 7693 appendix A and B examples
+    let mut blake2b = Blake2b::new(&Blake2Params::new(64)).expect("Failed to create BLAKE2b");
+    blake2b.update(b"abc");
+    let expected = concat!(
+        "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1",
+        "7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+    );
+    assert_eq!(blake2b.finalize(), hex(expected));
+
+    let mut blake2s = Blake2s::new(&Blake2Params::new(32)).expect("Failed to create BLAKE2s");
+    blake2s.update(b"abc");
+    assert_eq!(blake2s.finalize(), hex("508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"));
+
+    // Salted and personalized hashes with a short digest and zero-padded parameters
+    let salt: Vec<u8> = (0..16).collect();
+    let params = Blake2Params::new(20).with_salt(&salt).with_personalization(b"my-app-v1");
+    let mut blake2b = Blake2b::new(&params).expect("Failed to create BLAKE2b");
+    blake2b.update(b"hello");
+    assert_eq!(blake2b.finalize(), hex("927d680187139dd29886ab249e7391187c70c099"));
+
+    let params = Blake2Params::new(16).with_key(b"secret").with_salt(&salt[..8]).with_personalization(b"app");
+    let mut blake2s = Blake2s::new(&params).expect("Failed to create BLAKE2s");
+    blake2s.update(b"hel");
+    blake2s.update(b"lo");
+    assert_eq!(blake2s.finalize(), hex("016c59760446611290e63cf702e34c98"));
+}
+
+/// Tests resetting a keyed computation and the `digest::VariableOutput` implementation
+#[test]
+fn reset_and_variable_output() {
+    let vectors = KatVector::parse(BLAKE2B_KAT);
+    let mut blake2b = Blake2b::new(&Blake2Params::new(64).with_key(&vectors[0].key)).expect("Failed to create BLAKE2b");
+    blake2b.update(b"garbage");
+    blake2b.reset();
+    blake2b.update(&vectors[200].input);
+    assert_eq!(blake2b.finalize(), vectors[200].hash);
+
+    let mut blake2s = <Blake2s as VariableOutput>::new(32).expect("Failed to create BLAKE2s");
+    Update::update(&mut blake2s, b"abc");
+    let mut digest = [0; 32];
+    blake2s.finalize_variable(&mut digest).expect("Failed to finalize BLAKE2s");
+    assert_eq!(digest.as_slice(), hex("508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982"));
+    assert!(<Blake2b as VariableOutput>::new(65).is_err());
+}
+
+/// Tests that invalid parameters are rejected
+#[test]
+fn invalid_parameters() {
+    assert_eq!(Blake2b::new(&Blake2Params::new(0)).unwrap_err(), Error::InvalidLength);
+    assert_eq!(Blake2b::new(&Blake2Params::new(65)).unwrap_err(), Error::InvalidLength);
+    assert_eq!(Blake2s::new(&Blake2Params::new(33)).unwrap_err(), Error::InvalidLength);
+    assert_eq!(Blake2b::new(&Blake2Params::new(64).with_key(&[0; 65])).unwrap_err(), Error::InvalidKey);
+    assert_eq!(Blake2s::new(&Blake2Params::new(32).with_key(&[0; 33])).unwrap_err(), Error::InvalidKey);
+    assert_eq!(Blake2b::new(&Blake2Params::new(64).with_salt(&[0; 17])).unwrap_err(), Error::InvalidLength);
+    assert_eq!(Blake2s::new(&Blake2Params::new(32).with_personalization(&[0; 9])).unwrap_err(), Error::InvalidLength);
+
+    let mut blake2s = Blake2s::new(&Blake2Params::new(32).with_key(b"key")).expect("Failed to create BLAKE2s");
+    blake2s.update(b"data");
+    assert_eq!(blake2s.verify(&[0; 32]), Err(Error::VerificationFailed));
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use common::hex;
+use digest::{Update, VariableOutput};
+use evercrypt_tiny::{
+    blake2p::{Blake2bp, Blake2sp},
+    Error,
+};
+
+/// The keyed test vectors from the official BLAKE2 repository
+const BLAKE2BP_KAT: &str = include_str!("fixtures/blake2bp-kat.txt");
+/// The keyed test vectors from the official BLAKE2 repository
+const BLAKE2SP_KAT: &str = include_str!("fixtures/blake2sp-kat.txt");
+
+/// A known-answer test vector
+struct KatVector {
+    /// The input
+    input: Vec<u8>,
+    /// The key
+    key: Vec<u8>,
+    /// The digest
+    hash: Vec<u8>,
+}
+impl KatVector {
+    /// Parses all vectors from a KAT file
+    fn parse(kat: &str) -> Vec<Self> {
+        kat.split("\n\n")
+            .map(|vector| {
+                let mut lines = vector.lines().map(|line| line.split_once('\t').expect("Invalid KAT line").1);
+                let mut next = || hex(lines.next().expect("Truncated KAT vector"));
+                Self { input: next(), key: next(), hash: next() }
+            })
+            .collect()
+    }
+}
+
+/// A test vector for a large input
+struct TestVector {
+    /// The input length
+    input_len: usize,
+    /// The key
+    key: &'static [u8],
+    /// The BLAKE2bp digest
+    blake2bp: &'static str,
+    /// The BLAKE2sp digest
+    blake2sp: &'static str,
+}
+
+/// Test vectors over `i % 251` for `i` in `0..input_len`, computed with a reference implementation
+const TEST_VECTORS: &[TestVector] = &[
+    TestVector {
+        input_len: 100_000,
+        key: b"",
+        blake2bp: concat!(
+            "5cdeae115c2491fb0b9c70267e9d3294e47d4f30f3218d8f9b3e2ed58cf7a67e",
+            "05c08ce328a86fd4e0d09fc239d3f397480290a8576f9dbdc97222fae9bba448"
+        ),
+        blake2sp: "eb7050ea034453a75de798404d87f5568c4c0c6a7cccba79bd5863a40a33d10b",
+    },
+    TestVector {
+        input_len: 4097,
+        key: b"secret",
+        blake2bp: "2ab279fa81310c217ecc95f03171d93db6d48c9a",
+        blake2sp: "0510415bf50a8b46e3cde043ee3715e01d3743e2",
+    },
+];
+
+/// Tests the official BLAKE2bp KAT vectors with one-shot and byte-wise updates
+#[test]
+fn blake2bp_kat() {
+    let vectors = KatVector::parse(BLAKE2BP_KAT);
+    assert_eq!(vectors.len(), 256);
+
+    for vector in vectors {
+        let mut blake2bp = Blake2bp::new(64, &vector.key).expect("Failed to create BLAKE2bp");
+        blake2bp.update(&vector.input);
+        assert_eq!(blake2bp.finalize(), vector.hash, "Invalid digest for input length {}", vector.input.len());
+
+        let mut blake2bp = Blake2bp::new(64, &vector.key).expect("Failed to create BLAKE2bp");
+        vector.input.chunks(1).for_each(|byte| blake2bp.update(byte));
+        blake2bp.verify(&vector.hash).expect("Invalid MAC");
+    }
+}
+
+/// Tests the official BLAKE2sp KAT vectors with one-shot and byte-wise updates
+#[test]
+fn blake2sp_kat() {
+    let vectors = KatVector::parse(BLAKE2SP_KAT);
+    assert_eq!(vectors.len(), 256);
+
+    for vector in vectors {
+        let mut blake2sp = Blake2sp::new(32, &vector.key).expect("Failed to create BLAKE2sp");
+        blake2sp.update(&vector.input);
+        assert_eq!(blake2sp.finalize(), vector.hash, "Invalid digest for input length {}", vector.input.len());
+
+        let mut blake2sp = Blake2sp::new(32, &vector.key).expect("Failed to create BLAKE2sp");
+        vector.input.chunks(1).for_each(|byte| blake2sp.update(byte));
+        blake2sp.verify(&vector.hash).expect("Invalid MAC");
+    }
+}
+
+/// Tests large inputs with truncated digests and odd-sized updates
+#[test]
+fn large_inputs() {
+    for vector in TEST_VECTORS {
+        let input: Vec<u8> = (0..vector.input_len).map(|i| (i % 251) as u8).collect();
+        let (blake2bp, blake2sp) = (hex(vector.blake2bp), hex(vector.blake2sp));
+
+        let mut state = Blake2bp::new(blake2bp.len(), vector.key).expect("Failed to create BLAKE2bp");
+        state.update(&input);
+        assert_eq!(state.finalize(), blake2bp);
+        let mut state = Blake2bp::new(blake2bp.len(), vector.key).expect("Failed to create BLAKE2bp");
+        input.chunks(1000).for_each(|chunk| state.update(chunk));
+        assert_eq!(state.finalize(), blake2bp);
+
+        let mut state = Blake2sp::new(blake2sp.len(), vector.key).expect("Failed to create BLAKE2sp");
+        state.update(&input);
+        assert_eq!(state.finalize(), blake2sp);
+        let mut state = Blake2sp::new(blake2sp.len(), vector.key).expect("Failed to create BLAKE2sp");
+        input.chunks(777).for_each(|chunk| state.update(chunk));
+        assert_eq!(state.finalize(), blake2sp);
+    }
+}
+
+/// Tests the `digest` trait implementation
+#[test]
+fn digest_traits() {
+    let mut blake2bp = <Blake2bp as VariableOutput>::new(64).expect("Failed to create BLAKE2bp");
+    Update::update(&mut blake2bp, b"abc");
+    let mut digest = [0; 64];
+    blake2bp.finalize_variable(&mut digest).expect("Failed to finalize BLAKE2bp");
+    let expected = concat!(
+        "b91a6b66ae87526c400b0a8b53774dc65284ad8f6575f8148ff93dff943a6ecd",
+        "8362130f22d6dae633aa0f91df4ac89aaff31d0f1b923c898e82025dedbdad6e"
+    );
+    assert_eq!(digest.as_slice(), hex(expected));
+
+    assert!(<Blake2sp as VariableOutput>::new(33).is_err());
+    let blake2sp = <Blake2sp as VariableOutput>::new(16).expect("Failed to create BLAKE2sp");
+    assert!(blake2sp.finalize_variable(&mut [0; 32]).is_err());
+}
+
+/// Tests that invalid parameters and digests are rejected
+#[test]
+fn invalid() {
+    assert_eq!(Blake2bp::new(0, b"").unwrap_err(), Error::InvalidLength);
+    assert_eq!(Blake2bp::new(65, b"").unwrap_err(), Error::InvalidLength);
+    assert_eq!(Blake2sp::new(33, b"").unwrap_err(), Error::InvalidLength);
+    assert_eq!(Blake2bp::new(64, &[0; 65]).unwrap_err(), Error::InvalidKey);
+    assert_eq!(Blake2sp::new(32, &[0; 33]).unwrap_err(), Error::InvalidKey);
+
+    let mut blake2sp = Blake2sp::new(32, b"key").expect("Failed to create BLAKE2sp");
+    blake2sp.update(b"data");
+    assert_eq!(blake2sp.verify(&[0; 32]), Err(Error::VerificationFailed));
+}
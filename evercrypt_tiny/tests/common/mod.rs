@@ -0,0 +1,33 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use rand_core::{CryptoRng, RngCore};
+use std::num::NonZeroU32;
+
+/// Decodes a hex string
+pub fn hex(hex: &str) -> Vec<u8> {
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("Invalid hex literal")).collect()
+}
+
+/// An RNG that replays a fixed byte sequence and fails once it is exhausted
+pub struct ReplayRng(pub Vec<u8>);
+impl RngCore for ReplayRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).expect("RNG is exhausted")
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        if dest.len() > self.0.len() {
+            return Err(rand_core::Error::from(NonZeroU32::new(rand_core::Error::CUSTOM_START).unwrap()));
+        }
+        dest.copy_from_slice(&self.0[..dest.len()]);
+        self.0.drain(..dest.len());
+        Ok(())
+    }
+}
+impl CryptoRng for ReplayRng {}
@@ -0,0 +1,66 @@
+#![cfg(feature = "std")]
+
+use evercrypt_tiny::csprng::{self, ThreadRng};
+use rand_core::RngCore;
+use std::{collections::HashSet, thread};
+
+/// Registers a fixed entropy source, since there is no default source with the `custom-entropy` feature
+///
+/// The DRBGs are personalized per instance, so their outputs are still distinct.
+fn register_entropy() {
+    #[cfg(feature = "custom-entropy")]
+    evercrypt_tiny::entropy::set_entropy_source(|buf| {
+        buf.fill(0x42);
+        Ok(())
+    });
+}
+
+/// Tests that all threads draw distinct outputs from their own DRBGs
+#[test]
+fn threads() {
+    register_entropy();
+    let outputs: Vec<[u8; 32]> = (0..8)
+        .map(|_| {
+            thread::spawn(|| {
+                let mut outputs = Vec::new();
+                for _ in 0..16 {
+                    let mut output = [0; 32];
+                    csprng::fill_random(&mut output).expect("Failed to generate random bytes");
+                    outputs.push(output);
+                }
+                outputs
+            })
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flat_map(|thread| thread.join().expect("Thread panicked"))
+        .collect();
+    assert_eq!(outputs.iter().collect::<HashSet<_>>().len(), outputs.len());
+}
+
+/// Tests large requests and the `rand_core` handle
+#[test]
+fn thread_rng() {
+    register_entropy();
+    let mut output = vec![0; 200_000];
+    csprng::fill_random(&mut output).expect("Failed to generate random bytes");
+    assert_ne!(output[..32], output[output.len() - 32..]);
+
+    let mut rng = ThreadRng;
+    let (mut first, mut second) = ([0; 32], [0; 32]);
+    rng.fill_bytes(&mut first);
+    rng.try_fill_bytes(&mut second).expect("Failed to generate random bytes");
+    assert_ne!(first, second);
+    assert_ne!(rng.next_u64(), rng.next_u64());
+}
+
+/// Tests the custom `getrandom` backend
+#[test]
+#[cfg(feature = "getrandom")]
+fn getrandom_backend() {
+    register_entropy();
+    let (mut first, mut second) = ([0; 32], [0; 32]);
+    csprng::getrandom_backend(&mut first).expect("Failed to generate random bytes");
+    csprng::getrandom_backend(&mut second).expect("Failed to generate random bytes");
+    assert_ne!(first, second);
+}
@@ -0,0 +1,24 @@
+#![cfg(all(feature = "std", feature = "custom-entropy"))]
+
+use evercrypt_tiny::{
+    csprng::{self, ThreadRng},
+    Error,
+};
+use rand_core::RngCore;
+use std::thread;
+
+/// Tests that all requests fail instead of blocking while no entropy source is registered
+///
+/// This test lives in its own binary since the entropy source is process-wide.
+#[test]
+fn unregistered_source() {
+    assert_eq!(csprng::fill_random(&mut [0; 32]), Err(Error::RngFailure));
+    assert!(ThreadRng.try_fill_bytes(&mut [0; 32]).is_err());
+    #[cfg(feature = "getrandom")]
+    assert!(csprng::getrandom_backend(&mut [0; 32]).is_err());
+
+    // A failed instantiation is not cached, and other threads fail as well
+    assert_eq!(csprng::fill_random(&mut [0; 32]), Err(Error::RngFailure));
+    let result = thread::spawn(|| csprng::fill_random(&mut [0; 32])).join().expect("Thread panicked");
+    assert_eq!(result, Err(Error::RngFailure));
+}
@@ -0,0 +1,193 @@
+mod common;
+
+use common::{hex, ReplayRng};
+use evercrypt_tiny::{
+    drbg::{EverCryptDrbg, HmacDrbg},
+    sha2, Error, HashAlgorithm,
+};
+use rand_core::RngCore;
+
+/// The NIST CAVP HMAC_DRBG SHA-256 vectors without prediction resistance and reseeding
+const SHA256_CAVP: &str = include_str!("fixtures/hmac-drbg-sha256-cavp.txt");
+
+/// A CAVP test vector
+struct CavpVector {
+    /// The entropy input followed by the nonce
+    seed: Vec<u8>,
+    /// The additional input for the first request
+    additional_input1: Vec<u8>,
+    /// The additional input for the second request
+    additional_input2: Vec<u8>,
+    /// The output of the second request
+    output: Vec<u8>,
+}
+impl CavpVector {
+    /// Parses all vectors from a CAVP file
+    fn parse(cavp: &str) -> Vec<Self> {
+        cavp.split("\n\n")
+            .map(|vector| {
+                let mut lines = vector.lines().map(|line| line.split_once('\t').expect("Invalid CAVP line").1);
+                let mut next = || hex(lines.next().expect("Truncated CAVP vector"));
+                let seed = [next(), next()].concat();
+                Self { seed, additional_input1: next(), additional_input2: next(), output: next() }
+            })
+            .collect()
+    }
+}
+
+/// A test vector for a DRBG seeded with `seq(entropy_len, 1) || seq(entropy_len / 2, 2)`, the personalization string
+/// `personalization` and the reseed entropy `seq(entropy_len, 3)`, computed with an independent implementation
+struct TestVector {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The entropy input length
+    entropy_len: usize,
+    /// The second 64 byte output after a reseed with `reseed` and requests with `first` and `second`
+    reseeded: &'static str,
+    /// The SHA2-256 digest over a 70000 byte request
+    long: &'static str,
+    /// The 16 byte output after 1024 requests and an automatic reseed
+    automatic: &'static str,
+}
+
+/// The test vectors
+const TEST_VECTORS: &[TestVector] = &[
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        entropy_len: 16,
+        reseeded: concat!(
+            "258944ff68c00a718a92abbf4976726d175ec31b391c8f0e61314601bf0349b0",
+            "f70772c151b12ce2ea2e247f61963043b4d10db67fa05d34c0a550b88b9a5dcb"
+        ),
+        long: "c0c2e9e85bffec2df417733c73c158fe4e22bc77de47f8ccb9b76545d11b0835",
+        automatic: "75dc6194cbdf9bade84a36bd9ff2b1e4",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        entropy_len: 32,
+        reseeded: concat!(
+            "df235c0d116c77f13d5a10abc053bcca21ff29e351e4aa7ce86d459ae1e47b92",
+            "be449c9f472e380a38e78e7396e2c3fcb529d0f98dd29f10b897b656a3dc9bb8"
+        ),
+        long: "b5827451bf61afcdaac7745d31f24bced215ddbe27655d4e7ccf5362cec2289d",
+        automatic: "7d55343bccb3578adb7ba6cfa76ba04b",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_384,
+        entropy_len: 32,
+        reseeded: concat!(
+            "e4569ed5b71119d1c2fa3842c031de283b3f009c81a73f05997506dc6f2a4acd",
+            "12ade5288dbe36df07afa3f9edd0a9d971c656c7837e2f5e563f843efac5dbf2"
+        ),
+        long: "4acc52c63080accf455514d21cbfb2d8821779210ae4ec67700be647bb148576",
+        automatic: "7cdfa7c4d894304e8354ff9a9e59335e",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_512,
+        entropy_len: 32,
+        reseeded: concat!(
+            "96392dea3b5693d3d61e0a9561b9e1740e8574e9627c05a0541d351fc0e00717",
+            "8677b63a5e2bbc984a7cbf9291ef50df7c3c449b0dfaaab41dc64c7428e35e90"
+        ),
+        long: "7c44640c84b9af4dbfe02d5ba58aa7972529897e39a212d7840d9bf7b4767ec9",
+        automatic: "bd385d3fd60d74460adb0f52a7e3b3c0",
+    },
+];
+
+/// Tests the NIST CAVP HMAC_DRBG SHA-256 vectors
+#[test]
+fn sha256_cavp() {
+    let vectors = CavpVector::parse(SHA256_CAVP);
+    assert_eq!(vectors.len(), 30);
+
+    for vector in vectors {
+        let mut drbg =
+            HmacDrbg::new(HashAlgorithm::Sha2_256, ReplayRng(vector.seed), b"").expect("Failed to create HMAC-DRBG");
+        let mut output = vec![0; vector.output.len()];
+        drbg.generate(&mut output, &vector.additional_input1).expect("Failed to generate output");
+        drbg.generate(&mut output, &vector.additional_input2).expect("Failed to generate output");
+        assert_eq!(output, vector.output);
+    }
+}
+
+/// Tests reseeding, request splitting and automatic reseeding with all hash functions
+#[test]
+fn hmac_drbg() {
+    for vector in TEST_VECTORS {
+        let seed = [seq(vector.entropy_len, 1), seq(vector.entropy_len / 2, 2)].concat();
+        let reseed = seq(vector.entropy_len, 3);
+        let entropy = |reseed: &[u8]| ReplayRng([seed.as_slice(), reseed].concat());
+
+        let mut drbg = HmacDrbg::new(vector.hash, entropy(&reseed), b"personalization").expect("Failed to create DRBG");
+        drbg.reseed(b"reseed").expect("Failed to reseed DRBG");
+        let mut output = [0; 64];
+        drbg.generate(&mut output, b"first").expect("Failed to generate output");
+        drbg.generate(&mut output, b"second").expect("Failed to generate output");
+        assert_eq!(output.as_slice(), hex(vector.reseeded), "Invalid output for {:?}", vector.hash);
+
+        let mut drbg = HmacDrbg::new(vector.hash, entropy(&[]), b"personalization").expect("Failed to create DRBG");
+        let mut output = vec![0; 70000];
+        drbg.fill_bytes(&mut output);
+        assert_eq!(sha256(&output), hex(vector.long), "Invalid long output for {:?}", vector.hash);
+
+        let mut drbg = HmacDrbg::new(vector.hash, entropy(&reseed), b"personalization").expect("Failed to create DRBG");
+        let mut output = [0; 16];
+        (0..1024).for_each(|_| drbg.fill_bytes(&mut output));
+        drbg.fill_bytes(&mut output);
+        assert_eq!(output.as_slice(), hex(vector.automatic), "Invalid reseeded output for {:?}", vector.hash);
+        assert!(drbg.try_fill_bytes(&mut output).is_ok());
+    }
+}
+
+/// Tests the operating system seeded DRBG
+#[test]
+#[cfg(not(feature = "custom-entropy"))]
+fn evercrypt_drbg() {
+    let hashes = [HashAlgorithm::Sha1, HashAlgorithm::Sha2_256, HashAlgorithm::Sha2_384, HashAlgorithm::Sha2_512];
+    for hash in hashes {
+        let mut drbg = EverCryptDrbg::new(hash, b"personalization").expect("Failed to create DRBG");
+        let (mut first, mut second) = ([0; 32], [0; 32]);
+        drbg.generate(&mut first, b"additional input").expect("Failed to generate output");
+        drbg.reseed(b"reseed").expect("Failed to reseed DRBG");
+        drbg.fill_bytes(&mut second);
+        assert_ne!(first, second);
+
+        let mut output = vec![0; 200_000];
+        drbg.try_fill_bytes(&mut output).expect("Failed to generate long output");
+        assert_ne!(output[..32], output[output.len() - 32..]);
+        assert_ne!(drbg.next_u64(), drbg.next_u64());
+    }
+}
+
+/// Tests that invalid parameters and exhausted entropy sources are rejected
+#[test]
+fn invalid() {
+    let entropy = || ReplayRng(vec![0; 48]);
+    assert_eq!(HmacDrbg::new(HashAlgorithm::Md5, entropy(), b"").unwrap_err(), Error::UnsupportedAlgorithm);
+    assert_eq!(HmacDrbg::new(HashAlgorithm::Blake2b, entropy(), b"").unwrap_err(), Error::UnsupportedAlgorithm);
+    assert_eq!(EverCryptDrbg::new(HashAlgorithm::Sha2_224, b"").unwrap_err(), Error::UnsupportedAlgorithm);
+    assert_eq!(HmacDrbg::new(HashAlgorithm::Sha2_256, entropy(), &[0; 65537]).unwrap_err(), Error::InvalidLength);
+    assert_eq!(EverCryptDrbg::new(HashAlgorithm::Sha2_256, &[0; 65537]).unwrap_err(), Error::InvalidLength);
+    assert_eq!(HmacDrbg::new(HashAlgorithm::Sha2_256, ReplayRng(vec![0; 47]), b"").unwrap_err(), Error::RngFailure);
+
+    let mut drbg = HmacDrbg::new(HashAlgorithm::Sha2_256, entropy(), b"").expect("Failed to create DRBG");
+    assert_eq!(drbg.generate(&mut [0; 16], &[0; 65537]), Err(Error::InvalidLength));
+    assert_eq!(drbg.reseed(b""), Err(Error::RngFailure));
+
+    let mut output = [0; 16];
+    (0..1024).for_each(|_| drbg.fill_bytes(&mut output));
+    assert_eq!(drbg.generate(&mut output, b""), Err(Error::RngFailure));
+    assert!(drbg.try_fill_bytes(&mut output).is_err());
+}
+
+/// Generates `len` bytes of the sequence `i * 7 + offset`
+fn seq(len: usize, offset: usize) -> Vec<u8> {
+    (0..len).map(|i| ((i * 7 + offset) % 256) as u8).collect()
+}
+
+/// Computes the SHA2-256 digest of `data`
+fn sha256(data: &[u8]) -> Vec<u8> {
+    let mut digest = [0; 32];
+    sha2::hash_many(HashAlgorithm::Sha2_256, &[data], &mut [&mut digest]).expect("Failed to compute digest");
+    digest.to_vec()
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::hex;
+use evercrypt_tiny::{
+    ed25519::{Ed25519PublicKey, Ed25519SecretKey},
+    Error,
+};
+use rand_core::OsRng;
+use wycheproof::{
+    eddsa::{TestName, TestSet},
+    TestResult,
+};
+
+/// Runs the Wycheproof `ed25519` vectors
+#[test]
+fn wycheproof_verify() {
+    let test_set = TestSet::load(TestName::Ed25519).expect("Failed to load test set");
+    for group in test_set.test_groups {
+        let public_key = Ed25519PublicKey::from_bytes(&group.key.pk).expect("Failed to load public key");
+        for test in group.tests {
+            let result = public_key.verify(&test.msg, &test.sig);
+            match (test.result, result) {
+                (TestResult::Valid | TestResult::Acceptable, Ok(())) => (/* Expected success */),
+                (TestResult::Invalid | TestResult::Acceptable, Err(_)) => (/* Expected failure */),
+                (result, verified) => {
+                    panic!("Test case {} ({}): expected {result:?}, got {verified:?}", test.tc_id, test.comment)
+                }
+            }
+        }
+    }
+}
+
+/// Tests the first test vector from RFC 8032, section 7.1
+#[test]
+fn rfc8032() {
+    const SECRET_KEY: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const SIGNATURE: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+
+    let secret_key = Ed25519SecretKey::from_bytes(&hex(SECRET_KEY)).expect("Failed to load secret key");
+    assert_eq!(secret_key.public_key().to_bytes().as_slice(), hex(PUBLIC_KEY).as_slice());
+
+    let signature = secret_key.sign(b"").expect("Failed to sign message");
+    assert_eq!(signature.as_slice(), hex(SIGNATURE).as_slice());
+    secret_key.public_key().verify(b"", &signature).expect("Failed to verify signature");
+}
+
+/// Tests that generated keys sign and verify and that tampered messages are rejected
+#[test]
+fn sign_verify() {
+    let secret_key = Ed25519SecretKey::generate(&mut OsRng).expect("Failed to generate secret key");
+    let signature = secret_key.sign(b"Testolope").expect("Failed to sign message");
+
+    let public_key = secret_key.public_key();
+    public_key.verify(b"Testolope", &signature).expect("Failed to verify signature");
+    assert_eq!(public_key.verify(b"Testolopf", &signature), Err(Error::VerificationFailed));
+    assert_eq!(public_key.verify(b"Testolope", &signature[..63]), Err(Error::VerificationFailed));
+}
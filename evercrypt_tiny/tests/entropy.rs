@@ -0,0 +1,65 @@
+#![cfg(feature = "custom-entropy")]
+
+mod common;
+
+use common::ReplayRng;
+use evercrypt_tiny::{
+    drbg::{EverCryptDrbg, HmacDrbg},
+    entropy::{self, SystemRng},
+    Error, HashAlgorithm,
+};
+use rand_core::RngCore;
+
+/// A fixed entropy source
+fn fixed_entropy(buf: &mut [u8]) -> Result<(), Error> {
+    buf.fill(0x42);
+    Ok(())
+}
+
+/// The fixed entropy source as `rand_core` source with enough bytes for all requests of a test
+fn fixed_rng() -> ReplayRng {
+    ReplayRng(vec![0x42; 4096])
+}
+
+/// A failing entropy source
+fn failing_entropy(_buf: &mut [u8]) -> Result<(), Error> {
+    Err(Error::RngFailure)
+}
+
+/// Tests that the EverCrypt DRBG is seeded from the registered source
+///
+/// All steps run in a single test since the entropy source is process-wide.
+#[test]
+fn registered_source() {
+    // Without a source, instantiation fails
+    entropy::clear_entropy_source();
+    assert_eq!(EverCryptDrbg::new(HashAlgorithm::Sha2_256, b"").unwrap_err(), Error::RngFailure);
+    assert_eq!(entropy::fill_system_random(&mut [0; 16]), Err(Error::RngFailure));
+
+    // The system randomness is read directly from the registered source
+    entropy::set_entropy_source(fixed_entropy);
+    let mut buf = [0; 37];
+    SystemRng.fill_bytes(&mut buf);
+    assert_eq!(buf, [0x42; 37]);
+
+    // With a fixed source, the output is reproducible and matches an HMAC-DRBG that reseeds before every request
+    for hash in [HashAlgorithm::Sha1, HashAlgorithm::Sha2_256, HashAlgorithm::Sha2_384, HashAlgorithm::Sha2_512] {
+        let mut drbg = EverCryptDrbg::new(hash, b"personalization").expect("Failed to create DRBG");
+        let mut reference = HmacDrbg::new(hash, fixed_rng(), b"personalization").expect("Failed to create DRBG");
+        for _ in 0..3 {
+            let (mut output, mut expected) = ([0; 80], [0; 80]);
+            drbg.generate(&mut output, b"additional input").expect("Failed to generate output");
+            reference.reseed(b"additional input").expect("Failed to reseed DRBG");
+            reference.generate(&mut expected, b"additional input").expect("Failed to generate output");
+            assert_eq!(output, expected, "Invalid output for {hash:?}");
+        }
+    }
+
+    // A failing source makes reseeding and generation fail
+    let mut drbg = EverCryptDrbg::new(HashAlgorithm::Sha2_512, b"").expect("Failed to create DRBG");
+    entropy::set_entropy_source(failing_entropy);
+    assert_eq!(drbg.reseed(b""), Err(Error::RngFailure));
+    assert_eq!(drbg.generate(&mut [0; 16], b""), Err(Error::RngFailure));
+    assert!(SystemRng.try_fill_bytes(&mut [0; 16]).is_err());
+    entropy::clear_entropy_source();
+}
@@ -0,0 +1,324 @@
+mod common;
+
+use aes::{
+    cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit},
+    Aes256,
+};
+use common::{hex, ReplayRng};
+use evercrypt_tiny::{
+    frodo::{
+        Frodo1344, Frodo64, Frodo640, Frodo976, FrodoCiphertext, FrodoKem, FrodoParams, FrodoPublicKey, FrodoSecretKey,
+    },
+    kem::{Decapsulate, Encapsulate},
+    sha2, Error, HashAlgorithm,
+};
+use rand_core::{CryptoRng, OsRng, RngCore};
+use std::thread;
+
+/// The first vector (`count = 0`) of FrodoKEM's `PQCkemKAT_19888_shake.rsp`
+const FRODO640_KAT: &str = include_str!("fixtures/frodokem-640-shake-kat.rsp");
+/// The first vector of `PQCkemKAT_31296_shake.rsp`
+const FRODO976_KAT: &str = include_str!("fixtures/frodokem-976-shake-kat.rsp");
+/// The first vector of `PQCkemKAT_43088_shake.rsp`
+const FRODO1344_KAT: &str = include_str!("fixtures/frodokem-1344-shake-kat.rsp");
+
+/// The `nistkat-sha256` value of `frodokem640shake` from PQClean's `META.yml`
+///
+/// PQClean's `test/crypto_kem/nistkat.c` prints the `count = 0` vector from `count` to `ss`, and `nistkat-sha256` is
+/// the SHA2-256 digest of that output. Checking the vector text against it ties the fixtures to the official vectors.
+const FRODO640_KAT_SHA256: &str = "df2b77b8e108c61d16c78a99e79f3351ab15840a690f25c1f87a8e89295e9219";
+/// The `nistkat-sha256` value of `frodokem976shake`
+const FRODO976_KAT_SHA256: &str = "0d3d3a3ad11b69a93e72f1233b310884e97be8d16c9981bf1eb1321880cd0658";
+/// The `nistkat-sha256` value of `frodokem1344shake`
+const FRODO1344_KAT_SHA256: &str = "6e54e319cc590c3f136af81990a04cd0009ef78dec92825d2eb834adfec661dc";
+
+/// The stack size for the test threads, since HACL* runs on the calling thread without the `std` feature
+const STACK_SIZE: usize = 32 * 1024 * 1024;
+
+/// NIST's AES-256 CTR DRBG from `rng.c` of the PQC KAT generator
+struct KatDrbg {
+    key: [u8; 32],
+    v: [u8; 16],
+}
+impl KatDrbg {
+    /// Instantiates the DRBG like `randombytes_init` without personalization string
+    fn new(entropy: &[u8; 48]) -> Self {
+        let mut drbg = Self { key: [0; 32], v: [0; 16] };
+        drbg.update(Some(entropy));
+        drbg
+    }
+
+    /// Increments `V` and encrypts it
+    fn next_block(&mut self) -> [u8; 16] {
+        self.v = (u128::from_be_bytes(self.v).wrapping_add(1)).to_be_bytes();
+        let mut block = GenericArray::from(self.v);
+        Aes256::new(&self.key.into()).encrypt_block(&mut block);
+        block.into()
+    }
+
+    /// Updates the state like `AES256_CTR_DRBG_Update`
+    fn update(&mut self, provided_data: Option<&[u8; 48]>) {
+        let mut temp = [0; 48];
+        temp.chunks_mut(16).for_each(|chunk| chunk.copy_from_slice(&self.next_block()));
+        if let Some(provided_data) = provided_data {
+            temp.iter_mut().zip(provided_data).for_each(|(temp, data)| *temp ^= data);
+        }
+        self.key.copy_from_slice(&temp[..32]);
+        self.v.copy_from_slice(&temp[32..]);
+    }
+}
+impl RngCore for KatDrbg {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // Like `randombytes`, each request consumes whole blocks and updates the state afterwards
+        dest.chunks_mut(16).for_each(|chunk| chunk.copy_from_slice(&self.next_block()[..chunk.len()]));
+        self.update(None);
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+impl CryptoRng for KatDrbg {}
+
+/// A vector of a `PQCkemKAT_*.rsp` file
+struct KatVector {
+    /// The DRBG seed
+    seed: [u8; 48],
+    /// The public key
+    pk: Vec<u8>,
+    /// The secret key
+    sk: Vec<u8>,
+    /// The ciphertext
+    ct: Vec<u8>,
+    /// The shared secret
+    ss: Vec<u8>,
+}
+impl KatVector {
+    /// Parses all vectors from a KAT file
+    fn parse(kat: &str) -> Vec<Self> {
+        kat.split("\n\n")
+            .filter(|vector| vector.starts_with("count = "))
+            .enumerate()
+            .map(|(count, vector)| {
+                let mut lines = vector.lines().map(|line| line.split_once(" = ").expect("Invalid KAT line"));
+                assert_eq!(lines.next(), Some(("count", count.to_string().as_str())));
+                let mut next = |name| {
+                    let (name_, value) = lines.next().expect("Truncated KAT vector");
+                    assert_eq!(name_, name, "Unexpected KAT field");
+                    hex(value)
+                };
+                let seed = next("seed").try_into().expect("Invalid seed length");
+                Self { seed, pk: next("pk"), sk: next("sk"), ct: next("ct"), ss: next("ss") }
+            })
+            .collect()
+    }
+}
+
+/// Runs `f` on a thread with a large enough stack for FrodoKEM
+fn with_stack<F>(f: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let thread = thread::Builder::new().stack_size(STACK_SIZE).spawn(f).expect("Failed to spawn thread");
+    thread.join().expect("Thread panicked");
+}
+
+/// Validates all vectors of `kat` against the parameter set `P` after checking the vector text against `sha256`
+fn kat<P>(kat: &'static str, sha256: &str)
+where
+    P: FrodoParams + 'static,
+{
+    let header = format!("# {}\n\n", P::NAME);
+    let vector_text = kat.strip_prefix(&header).expect("Invalid KAT file");
+    let mut digest = [[0; 32]];
+    sha2::hash_many(HashAlgorithm::Sha2_256, &[vector_text.as_bytes()], &mut digest).expect("Failed to hash KAT");
+    assert_eq!(digest[0].as_slice(), hex(sha256), "KAT file does not match the official vectors");
+
+    with_stack(move || {
+        let vectors = KatVector::parse(kat);
+        assert!(!vectors.is_empty(), "Empty KAT file");
+
+        // The seeds are drawn from the DRBG instantiated with the bytes `0..48`
+        let mut seeds = KatDrbg::new(&core::array::from_fn(|i| i as u8));
+        for vector in vectors {
+            let mut seed = [0; 48];
+            seeds.fill_bytes(&mut seed);
+            assert_eq!(seed, vector.seed, "Invalid KAT seed");
+
+            // Generate the key pair and encapsulate like `PQCgenKAT_kem`
+            let mut rng = KatDrbg::new(&vector.seed);
+            let (secret_key, public_key) = FrodoKem::<P>::generate(&mut rng).expect("Failed to generate key pair");
+            assert_eq!(public_key.as_bytes().as_ref(), vector.pk);
+            assert_eq!(secret_key.as_bytes().as_ref(), vector.sk);
+            assert_eq!(secret_key.public_key(), public_key);
+            let (ciphertext, shared_secret) = public_key.encapsulate(&mut rng).expect("Failed to encapsulate");
+            assert_eq!(ciphertext.as_bytes().as_ref(), vector.ct);
+            assert_eq!(shared_secret.as_bytes().as_ref(), vector.ss);
+
+            // Decapsulate with the loaded secret key
+            let secret_key = FrodoSecretKey::<P>::from_bytes(&vector.sk).expect("Failed to load secret key");
+            let ciphertext = FrodoCiphertext::<P>::from_bytes(&vector.ct).expect("Failed to load ciphertext");
+            let shared_secret = secret_key.decapsulate(&ciphertext).expect("Failed to decapsulate");
+            assert_eq!(shared_secret.as_bytes().as_ref(), vector.ss);
+        }
+    });
+}
+
+/// Tests a round trip, implicit rejection and invalid lengths for the parameter set `P`
+fn roundtrip<P>()
+where
+    P: FrodoParams + 'static,
+{
+    with_stack(|| {
+        let (secret_key, public_key) = FrodoKem::<P>::generate(&mut OsRng).expect("Failed to generate key pair");
+        let (ciphertext, shared_secret) =
+            FrodoKem::encapsulate(&public_key, &mut OsRng).expect("Failed to encapsulate");
+        assert_eq!(FrodoKem::decapsulate(&secret_key, &ciphertext), Ok(shared_secret.clone()));
+
+        // A modified ciphertext yields a pseudorandom shared secret
+        let mut modified = ciphertext.as_bytes().clone();
+        modified.as_mut()[0] ^= 0x01;
+        let modified = FrodoCiphertext::<P>::from_bytes(modified.as_ref()).expect("Failed to load ciphertext");
+        let rejected = FrodoKem::decapsulate(&secret_key, &modified).expect("Failed to decapsulate");
+        assert_ne!(rejected, shared_secret);
+
+        // A fresh encapsulation yields a different shared secret
+        let (ciphertext_, shared_secret_) = public_key.encapsulate(&mut OsRng).expect("Failed to encapsulate");
+        assert_ne!(ciphertext_, ciphertext);
+        assert_ne!(shared_secret_, shared_secret);
+
+        // Encodings must have the exact length
+        let public_key_ = FrodoPublicKey::<P>::from_bytes(public_key.as_bytes().as_ref());
+        assert_eq!(public_key_, Ok(public_key.clone()));
+        let public_key_ = FrodoPublicKey::<P>::from_bytes(&public_key.as_bytes().as_ref()[1..]);
+        assert_eq!(public_key_.unwrap_err(), Error::InvalidKey);
+        let secret_key_ = FrodoSecretKey::<P>::from_bytes(&[0; 1]);
+        assert_eq!(secret_key_.unwrap_err(), Error::InvalidKey);
+        let ciphertext_ = FrodoCiphertext::<P>::from_bytes(&[ciphertext.as_bytes().as_ref(), &[0]].concat());
+        assert_eq!(ciphertext_.unwrap_err(), Error::InvalidLength);
+    });
+}
+
+/// Tests the official FrodoKEM-640-SHAKE KAT vector
+#[test]
+fn frodo640_kat() {
+    kat::<Frodo640>(FRODO640_KAT, FRODO640_KAT_SHA256);
+}
+
+/// Tests the official FrodoKEM-976-SHAKE KAT vector
+#[test]
+fn frodo976_kat() {
+    kat::<Frodo976>(FRODO976_KAT, FRODO976_KAT_SHA256);
+}
+
+/// Tests the official FrodoKEM-1344-SHAKE KAT vector
+#[test]
+fn frodo1344_kat() {
+    kat::<Frodo1344>(FRODO1344_KAT, FRODO1344_KAT_SHA256);
+}
+
+/// Tests round trips for all parameter sets
+#[test]
+fn roundtrips() {
+    roundtrip::<Frodo64>();
+    roundtrip::<Frodo640>();
+    roundtrip::<Frodo976>();
+    roundtrip::<Frodo1344>();
+}
+
+/// Tests that the coins are taken from the RNG only and that RNG failures are reported
+#[test]
+fn coins() {
+    with_stack(|| {
+        // The same coins yield the same keys and ciphertexts
+        let coins: Vec<u8> = (0..).take(Frodo64::KEYPAIR_COINS + Frodo64::ENCAPSULATE_COINS).collect();
+        let (secret_key, public_key) =
+            FrodoKem::<Frodo64>::generate(&mut ReplayRng(coins.clone())).expect("Failed to generate key pair");
+        let (secret_key_, public_key_) =
+            FrodoKem::<Frodo64>::generate(&mut ReplayRng(coins.clone())).expect("Failed to generate key pair");
+        assert_eq!((secret_key.as_bytes(), public_key.as_bytes()), (secret_key_.as_bytes(), public_key_.as_bytes()));
+        let mut rng = ReplayRng(coins[Frodo64::KEYPAIR_COINS..].to_vec());
+        let (ciphertext, shared_secret) = FrodoKem::encapsulate(&public_key, &mut rng).expect("Failed to encapsulate");
+        let mut rng = ReplayRng(coins[Frodo64::KEYPAIR_COINS..].to_vec());
+        assert_eq!(FrodoKem::encapsulate(&public_key, &mut rng), Ok((ciphertext, shared_secret)));
+
+        // Failing RNGs are reported and do not block subsequent calls
+        let mut rng = ReplayRng(vec![0; Frodo64::KEYPAIR_COINS - 1]);
+        assert_eq!(FrodoKem::<Frodo64>::generate(&mut rng).unwrap_err(), Error::RngFailure);
+        let mut rng = ReplayRng(vec![0; Frodo64::ENCAPSULATE_COINS - 1]);
+        assert_eq!(FrodoKem::encapsulate(&public_key, &mut rng).unwrap_err(), Error::RngFailure);
+        FrodoKem::encapsulate(&public_key, &mut OsRng).expect("Failed to encapsulate");
+    });
+}
+
+/// Tests that all parameter sets work on a thread with a 2 MiB stack, since HACL* runs on a thread with its own stack
+#[test]
+#[cfg(feature = "std")]
+fn small_stack() {
+    fn roundtrip<P>()
+    where
+        P: FrodoParams,
+    {
+        let (secret_key, public_key) = FrodoKem::<P>::generate(&mut OsRng).expect("Failed to generate key pair");
+        let (ciphertext, shared_secret) = public_key.encapsulate(&mut OsRng).expect("Failed to encapsulate");
+        assert_eq!(secret_key.decapsulate(&ciphertext), Ok(shared_secret));
+    }
+
+    let thread = thread::Builder::new().stack_size(2 * 1024 * 1024).spawn(|| {
+        roundtrip::<Frodo64>();
+        roundtrip::<Frodo640>();
+        roundtrip::<Frodo976>();
+        roundtrip::<Frodo1344>();
+    });
+    thread.expect("Failed to spawn thread").join().expect("Thread panicked");
+}
+
+/// Tests that key generation is deterministic for a fixed RNG
+#[test]
+fn deterministic_keypair() {
+    with_stack(|| {
+        let generate =
+            |seed| FrodoKem::<Frodo640>::generate(&mut KatDrbg::new(&[seed; 48])).expect("Failed to generate");
+        let (secret_key, public_key) = generate(0x01);
+        let (secret_key_, public_key_) = generate(0x01);
+        assert_eq!(public_key_, public_key);
+        assert_eq!(secret_key_.as_bytes(), secret_key.as_bytes());
+
+        // Another seed yields another key pair
+        let (_, public_key_) = generate(0x02);
+        assert_ne!(public_key_, public_key);
+    });
+}
+
+/// Tests that concurrent calls take their own coins
+#[test]
+fn concurrent() {
+    let threads: Vec<_> = (0..8u8)
+        .map(|seed| {
+            let thread = thread::Builder::new().stack_size(STACK_SIZE).spawn(move || {
+                let coins = vec![seed; Frodo64::KEYPAIR_COINS];
+                (0..16)
+                    .map(|_| {
+                        let (_, public_key) =
+                            FrodoKem::<Frodo64>::generate(&mut ReplayRng(coins.clone())).expect("Failed to generate");
+                        public_key
+                    })
+                    .collect::<Vec<_>>()
+            });
+            thread.expect("Failed to spawn thread")
+        })
+        .collect();
+
+    // Each thread generates the same key 16 times, and different threads generate different keys
+    let keys: Vec<_> = threads.into_iter().map(|thread| thread.join().expect("Thread panicked")).collect();
+    for (index, keys_) in keys.iter().enumerate() {
+        assert!(keys_.iter().all(|key| key == &keys_[0]), "Coins have been mixed up");
+        assert!(keys[..index].iter().all(|other| other[0] != keys_[0]), "Coins have been mixed up");
+    }
+}
@@ -0,0 +1,240 @@
+mod common;
+
+use common::hex;
+use evercrypt_tiny::{
+    hkdf::{Hkdf, MAX_OUTPUT_BLOCKS},
+    Error, HashAlgorithm,
+};
+use wycheproof::{
+    hkdf::{TestName, TestSet},
+    TestResult,
+};
+
+/// An HKDF test vector
+struct TestVector {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The hex encoded input keying material
+    ikm: &'static str,
+    /// The hex encoded salt
+    salt: &'static str,
+    /// The hex encoded info
+    info: &'static str,
+    /// The hex encoded pseudorandom key
+    prk: &'static str,
+    /// The hex encoded output keying material
+    okm: &'static str,
+}
+
+/// The test cases from RFC 5869, appendix A
+const RFC5869: &[TestVector] = &[
+    // Test case 1
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        ikm: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        salt: "000102030405060708090a0b0c",
+        info: "f0f1f2f3f4f5f6f7f8f9",
+        prk: "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+        okm: "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865",
+    },
+    // Test case 2
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        ikm: concat!(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f",
+            "404142434445464748494a4b4c4d4e4f"
+        ),
+        salt: concat!(
+            "606162636465666768696a6b6c6d6e6f707172737475767778797a7b7c7d7e7f",
+            "808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f",
+            "a0a1a2a3a4a5a6a7a8a9aaabacadaeaf"
+        ),
+        info: concat!(
+            "b0b1b2b3b4b5b6b7b8b9babbbcbdbebfc0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+            "d0d1d2d3d4d5d6d7d8d9dadbdcdddedfe0e1e2e3e4e5e6e7e8e9eaebecedeeef",
+            "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff"
+        ),
+        prk: "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+        okm: concat!(
+            "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c",
+            "59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71",
+            "cc30c58179ec3e87c14c01d5c1f3434f1d87"
+        ),
+    },
+    // Test case 3
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        ikm: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        salt: "",
+        info: "",
+        prk: "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+        okm: "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8",
+    },
+    // Test case 4
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        ikm: "0b0b0b0b0b0b0b0b0b0b0b",
+        salt: "000102030405060708090a0b0c",
+        info: "f0f1f2f3f4f5f6f7f8f9",
+        prk: "9b6c18c432a7bf8f0e71c8eb88f4b30baa2ba243",
+        okm: "085a01ea1b10f36933068b56efa5ad81a4f14b822f5b091568a9cdd4f155fda2c22e422478d305f3f896",
+    },
+    // Test case 5
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        ikm: concat!(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f",
+            "404142434445464748494a4b4c4d4e4f"
+        ),
+        salt: concat!(
+            "606162636465666768696a6b6c6d6e6f707172737475767778797a7b7c7d7e7f",
+            "808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f",
+            "a0a1a2a3a4a5a6a7a8a9aaabacadaeaf"
+        ),
+        info: concat!(
+            "b0b1b2b3b4b5b6b7b8b9babbbcbdbebfc0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+            "d0d1d2d3d4d5d6d7d8d9dadbdcdddedfe0e1e2e3e4e5e6e7e8e9eaebecedeeef",
+            "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff"
+        ),
+        prk: "8adae09a2a307059478d309b26c4115a224cfaf6",
+        okm: concat!(
+            "0bd770a74d1160f7c9f12cd5912a06ebff6adcae899d92191fe4305673ba2ffe",
+            "8fa3f1a4e5ad79f3f334b3b202b2173c486ea37ce3d397ed034c7f9dfeb15c5e",
+            "927336d0441f4c4300e2cff0d0900b52d3b4"
+        ),
+    },
+    // Test case 6
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        ikm: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        salt: "",
+        info: "",
+        prk: "da8c8a73c7fa77288ec6f5e7c297786aa0d32d01",
+        okm: "0ac1af7002b3d761d1e55298da9d0506b9ae52057220a306e07b6b87e8df21d0ea00033de03984d34918",
+    },
+    // Test case 7 (the salt is not provided, which is equivalent to an empty salt)
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        ikm: "0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c",
+        salt: "",
+        info: "",
+        prk: "2adccada18779e7c2077ad2eb19d3f3e731385dd",
+        okm: "2c91117204d745f3500d636a62f64f0ab3bae548aa53d423b0d1f27ebba6f5e5673a081d70cce7acfc48",
+    },
+];
+
+/// The inputs of RFC 5869, test case 1 with the remaining hash functions, created with Python's `hmac` module
+const OTHER_HASHES: &[TestVector] = &[
+    TestVector {
+        hash: HashAlgorithm::Sha2_384,
+        ikm: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        salt: "000102030405060708090a0b0c",
+        info: "f0f1f2f3f4f5f6f7f8f9",
+        prk: "704b39990779ce1dc548052c7dc39f303570dd13fb39f7acc564680bef80e8dec70ee9a7e1f3e293ef68eceb072a5ade",
+        okm: "9b5097a86038b805309076a44b3a9f38063e25b516dcbf369f394cfab43685f748b6457763e4f0204fc5",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_512,
+        ikm: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        salt: "000102030405060708090a0b0c",
+        info: "f0f1f2f3f4f5f6f7f8f9",
+        prk: concat!(
+            "665799823737ded04a88e47e54a5890bb2c3d247c7a4254a8e61350723590a26",
+            "c36238127d8661b88cf80ef802d57e2f7cebcf1e00e083848be19929c61b4237"
+        ),
+        okm: "832390086cda71fb47625bb5ceb168e4c8e26a1a16ed34d9fc7fe92c1481579338da362cb8d9f925d7cb",
+    },
+    TestVector {
+        hash: HashAlgorithm::Blake2s,
+        ikm: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        salt: "000102030405060708090a0b0c",
+        info: "f0f1f2f3f4f5f6f7f8f9",
+        prk: "57e878130679f9ea85900980b52df2643d043b82f290eb7dd62175dbb04cca4e",
+        okm: "1472c31f2ff768c71b19f8803683ee3b13c1a5fb3ea59c0c3bf0d44a4a40dcd4329d9cd85bbe35a1b3e7",
+    },
+    TestVector {
+        hash: HashAlgorithm::Blake2b,
+        ikm: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        salt: "000102030405060708090a0b0c",
+        info: "f0f1f2f3f4f5f6f7f8f9",
+        prk: concat!(
+            "02fbaa4ced1e659fe2eb8ae358de5be0edc0fd4526dbc7cc68d2ab9273e1b230",
+            "ab9d6860f65dc7bad92a483c0f90e019ace68b5e4fe65251666eb1e71e57a812"
+        ),
+        okm: "8815e1a85b5e90e6174323fdd180248887a7138af6dc5c8320fde21a60a078808267d6a41b6a938d7b30",
+    },
+];
+
+/// Runs the RFC 5869 vectors and the vectors for the remaining hash functions
+#[test]
+fn test_vectors() {
+    for vector in RFC5869.iter().chain(OTHER_HASHES) {
+        let hkdf = Hkdf::new(vector.hash).expect("Failed to create HKDF");
+        let prk = hkdf.extract(&hex(vector.salt), &hex(vector.ikm));
+        assert_eq!(prk.as_bytes(), hex(vector.prk).as_slice(), "{:?}", vector.hash);
+
+        let mut okm = vec![0; vector.okm.len() / 2];
+        prk.expand(&hex(vector.info), &mut okm).expect("Failed to expand PRK");
+        assert_eq!(okm, hex(vector.okm), "{:?}", vector.hash);
+
+        // Expand the loaded PRK with the info split into parts
+        let prk = hkdf.from_prk(&hex(vector.prk)).expect("Failed to load PRK");
+        let info = hex(vector.info);
+        let (first, second) = info.split_at(info.len() / 3);
+        let mut okm = vec![0; vector.okm.len() / 2];
+        prk.expand_multi_info(&[first, &[], second], &mut okm).expect("Failed to expand PRK");
+        assert_eq!(okm, hex(vector.okm), "{:?}", vector.hash);
+    }
+}
+
+/// Runs the Wycheproof HKDF vectors
+#[test]
+fn wycheproof() {
+    const TEST_NAMES: &[(TestName, HashAlgorithm)] = &[
+        (TestName::HkdfSha1, HashAlgorithm::Sha1),
+        (TestName::HkdfSha256, HashAlgorithm::Sha2_256),
+        (TestName::HkdfSha384, HashAlgorithm::Sha2_384),
+        (TestName::HkdfSha512, HashAlgorithm::Sha2_512),
+    ];
+
+    for (test_name, hash) in TEST_NAMES {
+        let hkdf = Hkdf::new(*hash).expect("Failed to create HKDF");
+        let test_set = TestSet::load(*test_name).expect("Failed to load test set");
+        for group in test_set.test_groups {
+            for test in group.tests {
+                let mut okm = vec![0; test.size];
+                let result = hkdf.extract(&test.salt, &test.ikm).expand(&test.info, &mut okm);
+                match (test.result, result) {
+                    (TestResult::Valid | TestResult::Acceptable, Ok(())) => {
+                        assert_eq!(okm.as_slice(), test.okm.as_slice(), "Test case {}", test.tc_id)
+                    }
+                    (TestResult::Invalid | TestResult::Acceptable, Err(_)) => (/* Expected failure */),
+                    (result, expanded) => {
+                        panic!("Test case {} ({}): expected {result:?}, got {expanded:?}", test.tc_id, test.comment)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Tests that the output length is capped at `255 * HashLen` and that PRKs are validated
+#[test]
+fn invalid_parameters() {
+    let hkdf = Hkdf::new(HashAlgorithm::Sha2_256).expect("Failed to create HKDF");
+    assert_eq!(hkdf.max_output_len(), MAX_OUTPUT_BLOCKS * 32);
+
+    let prk = hkdf.extract(b"", b"Testolope");
+    let mut okm = vec![0; hkdf.max_output_len() + 1];
+    assert_eq!(prk.expand(b"", &mut okm), Err(Error::InvalidLength));
+    prk.expand(b"", &mut okm[..hkdf.max_output_len()]).expect("Failed to expand PRK");
+    prk.expand(b"", &mut []).expect("Failed to expand PRK");
+
+    assert_eq!(hkdf.from_prk(&[0; 31]).unwrap_err(), Error::InvalidLength);
+    assert_eq!(hkdf.from_prk(&[0; 64]).unwrap_err(), Error::InvalidLength);
+    for hash in [HashAlgorithm::Md5, HashAlgorithm::Sha2_224] {
+        assert_eq!(Hkdf::new(hash).unwrap_err(), Error::UnsupportedAlgorithm);
+    }
+}
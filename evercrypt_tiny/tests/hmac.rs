@@ -0,0 +1,400 @@
+mod common;
+
+use common::hex;
+use digest::Mac;
+use evercrypt_tiny::{
+    hmac::{Hmac, HmacBlake2b, HmacSha1, HmacSha2_256, HmacSha2_512},
+    Error, HashAlgorithm,
+};
+use wycheproof::{
+    mac::{TestName, TestSet},
+    TestResult,
+};
+
+/// An HMAC test vector
+struct TestVector {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The hex encoded key
+    key: &'static str,
+    /// The hex encoded data
+    data: &'static str,
+    /// The hex encoded MAC, which may be truncated
+    mac: &'static str,
+}
+
+/// The test cases from RFC 4231, section 4 for SHA2-256, SHA2-384 and SHA2-512
+const RFC4231: &[TestVector] = &[
+    // Test case 1
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        key: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        data: "4869205468657265",
+        mac: "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_384,
+        key: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        data: "4869205468657265",
+        mac: "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_512,
+        key: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        data: "4869205468657265",
+        mac: concat!(
+            "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde",
+            "daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"
+        ),
+    },
+    // Test case 2
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        key: "4a656665",
+        data: "7768617420646f2079612077616e7420666f72206e6f7468696e673f",
+        mac: "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_384,
+        key: "4a656665",
+        data: "7768617420646f2079612077616e7420666f72206e6f7468696e673f",
+        mac: "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_512,
+        key: "4a656665",
+        data: "7768617420646f2079612077616e7420666f72206e6f7468696e673f",
+        mac: concat!(
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554",
+            "9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+        ),
+    },
+    // Test case 3
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        key: "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        data: concat!(
+            "dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd",
+            "dddddddddddddddddddddddddddddddddddd"
+        ),
+        mac: "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_384,
+        key: "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        data: concat!(
+            "dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd",
+            "dddddddddddddddddddddddddddddddddddd"
+        ),
+        mac: "88062608d3e6ad8a0aa2ace014c8a86f0aa635d947ac9febe83ef4e55966144b2a5ab39dc13814b94e3ab6e101a34f27",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_512,
+        key: "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        data: concat!(
+            "dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd",
+            "dddddddddddddddddddddddddddddddddddd"
+        ),
+        mac: concat!(
+            "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39",
+            "bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb"
+        ),
+    },
+    // Test case 4
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        key: "0102030405060708090a0b0c0d0e0f10111213141516171819",
+        data: concat!(
+            "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd",
+            "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"
+        ),
+        mac: "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_384,
+        key: "0102030405060708090a0b0c0d0e0f10111213141516171819",
+        data: concat!(
+            "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd",
+            "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"
+        ),
+        mac: "3e8a69b7783c25851933ab6290af6ca77a9981480850009cc5577c6e1f573b4e6801dd23c4a7d679ccf8a386c674cffb",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_512,
+        key: "0102030405060708090a0b0c0d0e0f10111213141516171819",
+        data: concat!(
+            "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd",
+            "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"
+        ),
+        mac: concat!(
+            "b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3db",
+            "a91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd"
+        ),
+    },
+    // Test case 5 (truncated to 128 bits)
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        key: "0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c",
+        data: "546573742057697468205472756e636174696f6e",
+        mac: "a3b6167473100ee06e0c796c2955552b",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_384,
+        key: "0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c",
+        data: "546573742057697468205472756e636174696f6e",
+        mac: "3abf34c3503b2a23a46efc619baef897",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_512,
+        key: "0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c",
+        data: "546573742057697468205472756e636174696f6e",
+        mac: "415fad6271580a531d4179bc891d87a6",
+    },
+    // Test case 6
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        key: RFC4231_LONG_KEY,
+        data: "54657374205573696e67204c6172676572205468616e20426c6f636b2d53697a65204b6579202d2048617368204b6579204669727374",
+        mac: "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_384,
+        key: RFC4231_LONG_KEY,
+        data: "54657374205573696e67204c6172676572205468616e20426c6f636b2d53697a65204b6579202d2048617368204b6579204669727374",
+        mac: "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_512,
+        key: RFC4231_LONG_KEY,
+        data: "54657374205573696e67204c6172676572205468616e20426c6f636b2d53697a65204b6579202d2048617368204b6579204669727374",
+        mac: concat!(
+            "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352",
+            "6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598"
+        ),
+    },
+    // Test case 7
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        key: RFC4231_LONG_KEY,
+        data: RFC4231_LONG_DATA,
+        mac: "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_384,
+        key: RFC4231_LONG_KEY,
+        data: RFC4231_LONG_DATA,
+        mac: "6617178e941f020d351e2f254e8fd32c602420feb0b8fb9adccebb82461e99c5a678cc31e799176d3860e6110c46523e",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_512,
+        key: RFC4231_LONG_KEY,
+        data: RFC4231_LONG_DATA,
+        mac: concat!(
+            "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944",
+            "b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58"
+        ),
+    },
+];
+/// The 131 byte key from RFC 4231, test cases 6 and 7
+const RFC4231_LONG_KEY: &str = concat!(
+    "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+    "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+    "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+);
+/// The data from RFC 4231, test case 7
+const RFC4231_LONG_DATA: &str = concat!(
+    "5468697320697320612074657374207573696e672061206c6172676572207468",
+    "616e20626c6f636b2d73697a65206b657920616e642061206c61726765722074",
+    "68616e20626c6f636b2d73697a6520646174612e20546865206b6579206e6565",
+    "647320746f20626520686173686564206265666f7265206265696e6720757365",
+    "642062792074686520484d414320616c676f726974686d2e"
+);
+
+/// The SHA-1 test cases from RFC 2202, section 3
+const RFC2202: &[TestVector] = &[
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        key: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+        data: "4869205468657265",
+        mac: "b617318655057264e28bc0b6fb378c8ef146be00",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        key: "4a656665",
+        data: "7768617420646f2079612077616e7420666f72206e6f7468696e673f",
+        mac: "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        key: "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        data: concat!(
+            "dddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddddd",
+            "dddddddddddddddddddddddddddddddddddd"
+        ),
+        mac: "125d7342b9ac11cd91a39af48aa17b4f63f175d3",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        key: "0102030405060708090a0b0c0d0e0f10111213141516171819",
+        data: concat!(
+            "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd",
+            "cdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd"
+        ),
+        mac: "4c9007f4026250c6bc8414f9bf50c86c2d7235da",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        key: "0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c0c",
+        data: "546573742057697468205472756e636174696f6e",
+        mac: "4c1a03424b55e07fe7f27be1d58bb9324a9a5a04",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        key: RFC2202_LONG_KEY,
+        data: "54657374205573696e67204c6172676572205468616e20426c6f636b2d53697a65204b6579202d2048617368204b6579204669727374",
+        mac: "aa4ae5e15272d00e95705637ce8a3b55ed402112",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha1,
+        key: RFC2202_LONG_KEY,
+        data: concat!(
+            "54657374205573696e67204c6172676572205468616e20426c6f636b2d53697a",
+            "65204b657920616e64204c6172676572205468616e204f6e6520426c6f636b2d",
+            "53697a652044617461"
+        ),
+        mac: "e8e99d0f45237d786d6bbaa7965c7808bbff1a91",
+    },
+];
+/// The 80 byte key from RFC 2202, test cases 6 and 7
+const RFC2202_LONG_KEY: &str = concat!(
+    "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+    "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"
+);
+
+/// BLAKE2 vectors over "The quick brown fox jumps over the lazy dog" created with Python's `hmac` module
+const BLAKE2: &[TestVector] = &[
+    TestVector {
+        hash: HashAlgorithm::Blake2s,
+        key: "546573746f6c6f7065",
+        data: "54686520717569636b2062726f776e20666f78206a756d7073206f76657220746865206c617a7920646f67",
+        mac: "4368cbff15b5a40849cb3922a111df760d0525340d72f308a0fe20188e3b52d2",
+    },
+    TestVector {
+        hash: HashAlgorithm::Blake2b,
+        key: "546573746f6c6f7065",
+        data: "54686520717569636b2062726f776e20666f78206a756d7073206f76657220746865206c617a7920646f67",
+        mac: concat!(
+            "394c5c00e54a981fb04136eb872913b090d558d8531dd4fcde239aab2cdc66c4",
+            "1a184d37eedd15837a862e2c0b8fda11678457f9d556005194d741c8848c8416"
+        ),
+    },
+];
+
+/// Runs the RFC 4231, RFC 2202 and BLAKE2 vectors through the one-shot and the streaming API
+#[test]
+fn test_vectors() {
+    for vector in RFC4231.iter().chain(RFC2202).chain(BLAKE2) {
+        let (key, data, expected) = (hex(vector.key), hex(vector.data), hex(vector.mac));
+
+        // Compute the MAC in one shot
+        let mac = Hmac::compute(vector.hash, &key, &data).expect("Failed to compute MAC");
+        assert_eq!(&mac[..expected.len()], expected.as_slice(), "{:?}", vector.hash);
+
+        // Compute the MAC bytewise
+        let mut hmac = Hmac::new(vector.hash, &key).expect("Failed to create HMAC");
+        data.iter().for_each(|byte| hmac.update(&[*byte]));
+        assert_eq!(hmac.finalize(), mac, "{:?}", vector.hash);
+
+        // Verify the full MAC
+        let mut hmac = Hmac::new(vector.hash, &key).expect("Failed to create HMAC");
+        hmac.update(&data);
+        hmac.verify(&mac).expect("Failed to verify MAC");
+    }
+}
+
+/// Runs the Wycheproof HMAC vectors
+#[test]
+fn wycheproof() {
+    const TEST_NAMES: &[(TestName, HashAlgorithm)] = &[
+        (TestName::HmacSha1, HashAlgorithm::Sha1),
+        (TestName::HmacSha256, HashAlgorithm::Sha2_256),
+        (TestName::HmacSha384, HashAlgorithm::Sha2_384),
+        (TestName::HmacSha512, HashAlgorithm::Sha2_512),
+    ];
+
+    for (test_name, hash) in TEST_NAMES {
+        let test_set = TestSet::load(*test_name).expect("Failed to load test set");
+        for group in test_set.test_groups {
+            for test in group.tests {
+                // Compare the (possibly truncated) tag
+                let mut hmac = Hmac::new(*hash, &test.key).expect("Failed to create HMAC");
+                hmac.update(&test.msg);
+                let mac = hmac.finalize();
+                let is_valid = mac.get(..test.tag.len()) == Some(test.tag.as_slice());
+                match (test.result, is_valid) {
+                    (TestResult::Valid | TestResult::Acceptable, true) => (/* Expected success */),
+                    (TestResult::Invalid | TestResult::Acceptable, false) => (/* Expected failure */),
+                    (result, _) => panic!("Test case {} ({}): expected {result:?}", test.tc_id, test.comment),
+                }
+            }
+        }
+    }
+}
+
+/// Tests that verification rejects modified and truncated tags and that `reset` restarts the computation
+#[test]
+fn verify_reset() {
+    let mut hmac = Hmac::new(HashAlgorithm::Sha2_256, b"Testolope").expect("Failed to create HMAC");
+    hmac.update(b"Some data");
+    hmac.reset();
+    hmac.update(b"Testolope");
+    let mac = Hmac::compute(HashAlgorithm::Sha2_256, b"Testolope", b"Testolope").expect("Failed to compute MAC");
+    assert_eq!(hmac.finalize(), mac);
+
+    let new = || {
+        let mut hmac = Hmac::new(HashAlgorithm::Sha2_256, b"Testolope").expect("Failed to create HMAC");
+        hmac.update(b"Testolope");
+        hmac
+    };
+    let mut modified = mac.clone();
+    modified[31] ^= 0x01;
+    assert_eq!(new().verify(&modified), Err(Error::VerificationFailed));
+    assert_eq!(new().verify(&mac[..16]), Err(Error::VerificationFailed));
+    assert_eq!(new().verify(&[]), Err(Error::VerificationFailed));
+}
+
+/// Tests the `digest::Mac` implementations against the runtime typed API
+#[test]
+fn digest_mac() {
+    let key = hex(RFC4231_LONG_KEY);
+    let expected = Hmac::compute(HashAlgorithm::Sha2_256, &key, b"Testolope").expect("Failed to compute MAC");
+
+    let mut mac = HmacSha2_256::new_from_slice(&key).expect("Failed to create HMAC");
+    mac.update(b"Testo");
+    mac.update(b"lope");
+    assert_eq!(mac.into_inner().finalize(), expected);
+
+    let mut mac = HmacSha2_256::new_from_slice(&key).expect("Failed to create HMAC");
+    mac.update(b"Testolope");
+    assert_eq!(mac.finalize_reset().into_bytes().as_slice(), expected.as_slice());
+    mac.update(b"Testolope");
+    mac.verify_slice(&expected).expect("Failed to verify MAC");
+
+    // Test the remaining hash functions
+    let mac = HmacSha1::new_from_slice(b"Testolope").expect("Failed to create HMAC").chain_update(b"Testolope");
+    assert_eq!(mac.finalize().into_bytes().len(), 20);
+    let mac = HmacSha2_512::new_from_slice(b"Testolope").expect("Failed to create HMAC").chain_update(b"Testolope");
+    assert_eq!(mac.finalize().into_bytes().len(), 64);
+    let mac = HmacBlake2b::new_from_slice(&hex(BLAKE2[1].key)).expect("Failed to create HMAC");
+    let mac = mac.chain_update(hex(BLAKE2[1].data));
+    assert_eq!(mac.finalize().into_bytes().as_slice(), hex(BLAKE2[1].mac).as_slice());
+}
+
+/// Tests that hash functions that are not supported by EverCrypt's HMAC are rejected
+#[test]
+fn unsupported_hash() {
+    for hash in [HashAlgorithm::Md5, HashAlgorithm::Sha2_224] {
+        assert_eq!(Hmac::new(hash, b"Testolope").unwrap_err(), Error::UnsupportedAlgorithm);
+        assert_eq!(Hmac::compute(hash, b"Testolope", b"Testolope").unwrap_err(), Error::UnsupportedAlgorithm);
+    }
+}
@@ -0,0 +1,328 @@
+mod common;
+
+use common::{hex, ReplayRng};
+use evercrypt_tiny::{
+    hpke::{Aead, Hpke, HpkePublicKey, HpkeSecretKey, Kdf, Kem, TAG_LEN},
+    Error,
+};
+use rand_core::OsRng;
+use serde::Deserialize;
+
+/// Vectors in the format of the RFC 9180 `test-vectors.json` for all supported suites, computed with an independent
+/// Python implementation on top of pyca/cryptography that reproduces RFC 9180, appendix A.1.1 and interoperates with
+/// OpenSSL's HPKE; the vectors with the `ikmE` and `ikmR` of appendix A.1.1, A.2.1 and A.3.1 are the RFC's vectors
+const RFC9180: &str = include_str!("fixtures/hpke-rfc9180.json");
+
+/// An HPKE test vector
+#[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
+struct Vector {
+    mode: u8,
+    kem_id: u16,
+    kdf_id: u16,
+    aead_id: u16,
+    info: String,
+    ikmR: String,
+    ikmE: String,
+    ikmS: Option<String>,
+    skRm: String,
+    pkRm: String,
+    pkSm: Option<String>,
+    psk: Option<String>,
+    psk_id: Option<String>,
+    enc: String,
+    encryptions: Vec<Encryption>,
+    exports: Vec<Export>,
+}
+/// An encryption of an HPKE test vector
+#[derive(Debug, Deserialize)]
+struct Encryption {
+    aad: String,
+    ct: String,
+    pt: String,
+}
+/// An export of an HPKE test vector
+#[derive(Debug, Deserialize)]
+struct Export {
+    exporter_context: String,
+    #[serde(rename = "L")]
+    len: usize,
+    exported_value: String,
+}
+
+/// All supported suites
+fn suites() -> Vec<Hpke> {
+    let mut suites = Vec::new();
+    for kem in [Kem::DhKemP256HkdfSha256, Kem::DhKemX25519HkdfSha256] {
+        for kdf in [Kdf::HkdfSha256, Kdf::HkdfSha384, Kdf::HkdfSha512] {
+            for aead in [Aead::Aes128Gcm, Aead::Aes256Gcm, Aead::ChaCha20Poly1305] {
+                match Hpke::new(kem, kdf, aead) {
+                    Ok(hpke) => suites.push(hpke),
+                    Err(Error::UnsupportedAlgorithm) => (),
+                    Err(e) => panic!("Failed to create HPKE instance: {e}"),
+                }
+            }
+        }
+    }
+    suites
+}
+
+/// Tests all modes against the RFC 9180 test vectors
+#[test]
+fn rfc9180() {
+    let vectors: Vec<Vector> = serde_json::from_str(RFC9180).expect("Invalid test vectors");
+    let mut tested = [0; 4];
+    for vector in vectors {
+        // Skip unsupported suites
+        let (Ok(kem), Ok(kdf), Ok(aead)) =
+            (Kem::from_id(vector.kem_id), Kdf::from_id(vector.kdf_id), Aead::from_id(vector.aead_id))
+        else {
+            continue;
+        };
+        let Ok(hpke) = Hpke::new(kem, kdf, aead) else {
+            continue;
+        };
+
+        // Derive the recipient and the sender key pair
+        let secret_key = HpkeSecretKey::derive(kem, &hex(&vector.ikmR)).expect("Failed to derive secret key");
+        assert_eq!(secret_key.to_bytes().as_bytes(), hex(&vector.skRm));
+        assert_eq!(secret_key.public_key().to_bytes(), hex(&vector.pkRm));
+        let public_key = HpkePublicKey::from_bytes(kem, &hex(&vector.pkRm)).expect("Failed to load public key");
+        let sender_key = vector.ikmS.as_deref().map(|ikm| HpkeSecretKey::derive(kem, &hex(ikm)));
+        let sender_key = sender_key.transpose().expect("Failed to derive sender key");
+        let sender_public_key = vector.pkSm.as_deref().map(|key| HpkePublicKey::from_bytes(kem, &hex(key)));
+        let sender_public_key = sender_public_key.transpose().expect("Failed to load sender public key");
+        assert_eq!(sender_key.as_ref().map(HpkeSecretKey::public_key), sender_public_key);
+
+        // Set up both contexts with the vector's ephemeral key
+        let (info, enc) = (hex(&vector.info), hex(&vector.enc));
+        let (psk, psk_id) = (hex(vector.psk.as_deref().unwrap_or("")), hex(vector.psk_id.as_deref().unwrap_or("")));
+        let mut rng = ReplayRng(hex(&vector.ikmE));
+        let (enc_, mut sender, receiver) = match (vector.mode, &sender_key, &sender_public_key) {
+            (0, None, None) => {
+                let (enc_, sender) =
+                    hpke.setup_base_sender(&public_key, &info, &mut rng).expect("Failed to set up sender");
+                (enc_, sender, hpke.setup_base_receiver(&enc, &secret_key, &info))
+            }
+            (1, None, None) => {
+                let (enc_, sender) = hpke
+                    .setup_psk_sender(&public_key, &info, &psk, &psk_id, &mut rng)
+                    .expect("Failed to set up sender");
+                (enc_, sender, hpke.setup_psk_receiver(&enc, &secret_key, &info, &psk, &psk_id))
+            }
+            (2, Some(sender_key), Some(sender_public_key)) => {
+                let (enc_, sender) =
+                    hpke.setup_auth_sender(&public_key, &info, sender_key, &mut rng).expect("Failed to set up sender");
+                (enc_, sender, hpke.setup_auth_receiver(&enc, &secret_key, &info, sender_public_key))
+            }
+            (3, Some(sender_key), Some(sender_public_key)) => {
+                let (enc_, sender) = hpke
+                    .setup_auth_psk_sender(&public_key, &info, &psk, &psk_id, sender_key, &mut rng)
+                    .expect("Failed to set up sender");
+                let receiver = hpke.setup_auth_psk_receiver(&enc, &secret_key, &info, &psk, &psk_id, sender_public_key);
+                (enc_, sender, receiver)
+            }
+            _ => panic!("Invalid test vector"),
+        };
+        assert_eq!(enc_, enc);
+        let mut receiver = receiver.expect("Failed to set up receiver");
+
+        // Seal and open all messages in order
+        for (sequence, encryption) in vector.encryptions.iter().enumerate() {
+            assert_eq!(sender.sequence_number(), sequence as u64);
+            let (aad, ciphertext, plaintext) = (hex(&encryption.aad), hex(&encryption.ct), hex(&encryption.pt));
+            assert_eq!(sender.seal(&aad, &plaintext).expect("Failed to seal message"), ciphertext);
+            assert_eq!(receiver.open(&aad, &ciphertext).expect("Failed to open message"), plaintext);
+            assert_eq!(receiver.sequence_number(), sequence as u64 + 1);
+        }
+
+        // Export all secrets on both sides
+        for export in &vector.exports {
+            let (mut sender_okm, mut receiver_okm) = (vec![0; export.len], vec![0; export.len]);
+            let exporter_context = hex(&export.exporter_context);
+            sender.export(&exporter_context, &mut sender_okm).expect("Failed to export secret");
+            receiver.export(&exporter_context, &mut receiver_okm).expect("Failed to export secret");
+            assert_eq!(sender_okm, hex(&export.exported_value));
+            assert_eq!(receiver_okm, hex(&export.exported_value));
+        }
+        tested[vector.mode as usize] += 1;
+    }
+    assert!(tested.iter().all(|&tested| tested > 0), "Not all modes have been tested");
+}
+
+/// Tests single-shot encryption for all suites with random keys
+#[test]
+fn roundtrip() {
+    for hpke in suites() {
+        let secret_key = HpkeSecretKey::generate(hpke.kem(), &mut OsRng).expect("Failed to generate key");
+        let public_key = secret_key.public_key();
+        assert_eq!(public_key.to_bytes().len(), hpke.kem().public_key_len());
+
+        let (enc, ciphertext) =
+            hpke.seal_base(&public_key, b"info", b"aad", b"Testolope", &mut OsRng).expect("Failed to seal message");
+        assert_eq!(enc.len(), hpke.kem().public_key_len());
+        assert_eq!(ciphertext.len(), b"Testolope".len() + TAG_LEN);
+        let plaintext =
+            hpke.open_base(&enc, &secret_key, b"info", b"aad", &ciphertext).expect("Failed to open message");
+        assert_eq!(plaintext, b"Testolope");
+    }
+}
+
+/// Tests algorithm identifiers
+#[test]
+fn identifiers() {
+    for kem in [Kem::DhKemP256HkdfSha256, Kem::DhKemX25519HkdfSha256] {
+        assert_eq!(Kem::from_id(kem.id()), Ok(kem));
+    }
+    for kdf in [Kdf::HkdfSha256, Kdf::HkdfSha384, Kdf::HkdfSha512] {
+        assert_eq!(Kdf::from_id(kdf.id()), Ok(kdf));
+    }
+    for aead in [Aead::Aes128Gcm, Aead::Aes256Gcm, Aead::ChaCha20Poly1305, Aead::ExportOnly] {
+        assert_eq!(Aead::from_id(aead.id()), Ok(aead));
+    }
+    assert_eq!(Kem::from_id(0x0011).unwrap_err(), Error::UnsupportedAlgorithm);
+    assert_eq!(Kdf::from_id(0x0000).unwrap_err(), Error::UnsupportedAlgorithm);
+    assert_eq!(Aead::from_id(0x0004).unwrap_err(), Error::UnsupportedAlgorithm);
+}
+
+/// Tests forged messages, reordered messages, mismatching keys and invalid encapsulated keys
+#[test]
+fn invalid() {
+    let hpke = Hpke::new(Kem::DhKemX25519HkdfSha256, Kdf::HkdfSha256, Aead::ChaCha20Poly1305)
+        .expect("Failed to create HPKE instance");
+    let secret_key = HpkeSecretKey::generate(hpke.kem(), &mut OsRng).expect("Failed to generate key");
+    let (enc, mut sender) =
+        hpke.setup_base_sender(&secret_key.public_key(), b"info", &mut OsRng).expect("Failed to set up sender");
+    let first = sender.seal(b"", b"first").expect("Failed to seal message");
+    let second = sender.seal(b"", b"second").expect("Failed to seal message");
+
+    // Flip every bit once
+    let mut receiver = hpke.setup_base_receiver(&enc, &secret_key, b"info").expect("Failed to set up receiver");
+    for index in 0..first.len() * 8 {
+        let mut forged = first.clone();
+        forged[index / 8] ^= 1 << (index % 8);
+        assert_eq!(receiver.open(b"", &forged).unwrap_err(), Error::DecryptionFailed);
+    }
+
+    // Failed attempts don't advance the sequence number, but reordered messages cannot be opened
+    assert_eq!(receiver.open(b"", &second).unwrap_err(), Error::DecryptionFailed);
+    assert_eq!(receiver.open(b"aad", &first).unwrap_err(), Error::DecryptionFailed);
+    assert_eq!(receiver.open(b"", &first[..TAG_LEN - 1]).unwrap_err(), Error::InvalidLength);
+    assert_eq!(receiver.open(b"", &first).expect("Failed to open message"), b"first");
+    assert_eq!(receiver.open(b"", &second).expect("Failed to open message"), b"second");
+
+    // A different info yields a different context
+    let mut receiver = hpke.setup_base_receiver(&enc, &secret_key, b"other").expect("Failed to set up receiver");
+    assert_eq!(receiver.open(b"", &first).unwrap_err(), Error::DecryptionFailed);
+
+    // Keys of another KEM
+    let p256_key = HpkeSecretKey::generate(Kem::DhKemP256HkdfSha256, &mut OsRng).expect("Failed to generate key");
+    assert_eq!(hpke.setup_base_receiver(&enc, &p256_key, b"").unwrap_err(), Error::InvalidKey);
+    let result = hpke.setup_base_sender(&p256_key.public_key(), b"", &mut OsRng);
+    assert_eq!(result.unwrap_err(), Error::InvalidKey);
+
+    // Invalid and low-order encapsulated keys
+    assert_eq!(hpke.setup_base_receiver(&enc[1..], &secret_key, b"").unwrap_err(), Error::InvalidKey);
+    assert_eq!(hpke.setup_base_receiver(&[0; 32], &secret_key, b"").unwrap_err(), Error::InvalidKey);
+    let low_order = HpkePublicKey::from_bytes(hpke.kem(), &[0; 32]).expect("Failed to load public key");
+    assert_eq!(hpke.setup_base_sender(&low_order, b"", &mut OsRng).unwrap_err(), Error::InvalidKey);
+
+    // P-256 public keys must be valid uncompressed points
+    let p256_public_key = p256_key.public_key().to_bytes();
+    let mut compressed = vec![0x02 | (p256_public_key[64] & 1)];
+    compressed.extend_from_slice(&p256_public_key[1..33]);
+    for bytes in [&compressed[..], &p256_public_key[..64], &[0x04; 65]] {
+        let result = HpkePublicKey::from_bytes(Kem::DhKemP256HkdfSha256, bytes);
+        assert_eq!(result.unwrap_err(), Error::InvalidKey);
+    }
+
+    // A failing RNG
+    let mut rng = ReplayRng(vec![0; 31]);
+    let result = hpke.setup_base_sender(&secret_key.public_key(), b"", &mut rng);
+    assert_eq!(result.unwrap_err(), Error::RngFailure);
+}
+
+/// Tests the authentication of the sender in the PSK, auth and auth PSK modes
+#[test]
+fn sender_authentication() {
+    let hpke = Hpke::new(Kem::DhKemP256HkdfSha256, Kdf::HkdfSha256, Aead::ChaCha20Poly1305)
+        .expect("Failed to create HPKE instance");
+    let secret_key = HpkeSecretKey::generate(hpke.kem(), &mut OsRng).expect("Failed to generate key");
+    let public_key = secret_key.public_key();
+    let sender_key = HpkeSecretKey::generate(hpke.kem(), &mut OsRng).expect("Failed to generate key");
+    let other_key = HpkeSecretKey::generate(hpke.kem(), &mut OsRng).expect("Failed to generate key");
+    let (psk, psk_id) = ([0xa5; 32], b"psk");
+
+    // A wrong PSK or PSK ID yields a different context
+    let (enc, mut sender) =
+        hpke.setup_psk_sender(&public_key, b"", &psk, psk_id, &mut OsRng).expect("Failed to set up sender");
+    let ciphertext = sender.seal(b"", b"Testolope").expect("Failed to seal message");
+    for (psk, psk_id) in [(&[0x5a; 32], &psk_id[..]), (&psk, b"other")] {
+        let mut receiver =
+            hpke.setup_psk_receiver(&enc, &secret_key, b"", psk, psk_id).expect("Failed to set up receiver");
+        assert_eq!(receiver.open(b"", &ciphertext).unwrap_err(), Error::DecryptionFailed);
+    }
+
+    // A wrong sender key yields a different context
+    let (enc, mut sender) =
+        hpke.setup_auth_sender(&public_key, b"", &sender_key, &mut OsRng).expect("Failed to set up sender");
+    let ciphertext = sender.seal(b"", b"Testolope").expect("Failed to seal message");
+    let mut receiver =
+        hpke.setup_auth_receiver(&enc, &secret_key, b"", &other_key.public_key()).expect("Failed to set up receiver");
+    assert_eq!(receiver.open(b"", &ciphertext).unwrap_err(), Error::DecryptionFailed);
+    let mut receiver = hpke.setup_base_receiver(&enc, &secret_key, b"").expect("Failed to set up receiver");
+    assert_eq!(receiver.open(b"", &ciphertext).unwrap_err(), Error::DecryptionFailed);
+
+    // Both are required in the auth PSK mode
+    let (enc, mut sender) = hpke
+        .setup_auth_psk_sender(&public_key, b"", &psk, psk_id, &sender_key, &mut OsRng)
+        .expect("Failed to set up sender");
+    let ciphertext = sender.seal(b"", b"Testolope").expect("Failed to seal message");
+    let mut receiver = hpke
+        .setup_auth_psk_receiver(&enc, &secret_key, b"", &psk, psk_id, &sender_key.public_key())
+        .expect("Failed to set up receiver");
+    assert_eq!(receiver.open(b"", &ciphertext).expect("Failed to open message"), b"Testolope");
+    let mut receiver =
+        hpke.setup_auth_receiver(&enc, &secret_key, b"", &sender_key.public_key()).expect("Failed to set up receiver");
+    assert_eq!(receiver.open(b"", &ciphertext).unwrap_err(), Error::DecryptionFailed);
+
+    // PSK modes require a PSK and a PSK ID
+    for (psk, psk_id) in [(&b""[..], &psk_id[..]), (&psk[..], &b""[..])] {
+        let result = hpke.setup_psk_sender(&public_key, b"", psk, psk_id, &mut OsRng);
+        assert_eq!(result.unwrap_err(), Error::InvalidKey);
+        let result = hpke.setup_auth_psk_receiver(&enc, &secret_key, b"", psk, psk_id, &sender_key.public_key());
+        assert_eq!(result.unwrap_err(), Error::InvalidKey);
+    }
+
+    // Sender keys of another KEM
+    let x25519_key = HpkeSecretKey::generate(Kem::DhKemX25519HkdfSha256, &mut OsRng).expect("Failed to generate key");
+    let result = hpke.setup_auth_sender(&public_key, b"", &x25519_key, &mut OsRng);
+    assert_eq!(result.unwrap_err(), Error::InvalidKey);
+    let result = hpke.setup_auth_receiver(&enc, &secret_key, b"", &x25519_key.public_key());
+    assert_eq!(result.unwrap_err(), Error::InvalidKey);
+}
+
+/// Tests export-only contexts and the export length limit
+#[test]
+fn export() {
+    let hpke = Hpke::new(Kem::DhKemX25519HkdfSha256, Kdf::HkdfSha384, Aead::ExportOnly)
+        .expect("Failed to create HPKE instance");
+    let secret_key = HpkeSecretKey::generate(hpke.kem(), &mut OsRng).expect("Failed to generate key");
+    let (enc, mut sender) =
+        hpke.setup_base_sender(&secret_key.public_key(), b"info", &mut OsRng).expect("Failed to set up sender");
+    let mut receiver = hpke.setup_base_receiver(&enc, &secret_key, b"info").expect("Failed to set up receiver");
+
+    // Both sides export the same secrets of up to `255 * Nh` bytes
+    let (mut sender_okm, mut receiver_okm) = (vec![0; 255 * 48], vec![0; 255 * 48]);
+    sender.export(b"context", &mut sender_okm).expect("Failed to export secret");
+    receiver.export(b"context", &mut receiver_okm).expect("Failed to export secret");
+    assert_eq!(sender_okm, receiver_okm);
+    receiver.export(b"other", &mut receiver_okm[..32]).expect("Failed to export secret");
+    assert_ne!(sender_okm[..32], receiver_okm[..32]);
+    assert_eq!(sender.export(b"", &mut [0; 255 * 48 + 1]).unwrap_err(), Error::InvalidLength);
+
+    // Export-only contexts cannot seal or open messages
+    assert_eq!(sender.seal(b"", b"Testolope").unwrap_err(), Error::UnsupportedAlgorithm);
+    assert_eq!(receiver.open(b"", &[0; TAG_LEN]).unwrap_err(), Error::UnsupportedAlgorithm);
+    assert_eq!(sender.sequence_number(), 0);
+}
@@ -0,0 +1,255 @@
+mod common;
+
+use common::hex;
+use evercrypt_tiny::{
+    merkle::{self, HashChain, MerkleTree},
+    Error, HashAlgorithm,
+};
+
+/// A proof test vector
+struct ProofVector {
+    /// The leaf index or old tree size
+    first: u64,
+    /// The tree size or new tree size
+    second: u64,
+    /// The hex encoded proof nodes
+    proof: &'static [&'static str],
+}
+
+/// The hex encoded leaves of the Certificate Transparency reference test data
+const LEAVES: &[&str] =
+    &["", "00", "10", "2021", "3031", "40414243", "5051525354555657", "606162636465666768696a6b6c6d6e6f"];
+/// The SHA2-256 roots of the trees with the first 1 to 8 leaves
+const ROOTS: &[&str] = &[
+    "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+    "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+    "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+    "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+    "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+    "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+    "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+];
+/// The SHA2-256 root of the empty tree
+const EMPTY_ROOT: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+/// Inclusion proofs over the reference leaves
+const INCLUSION_PROOFS: &[ProofVector] = &[
+    ProofVector { first: 0, second: 1, proof: &[] },
+    ProofVector {
+        first: 0,
+        second: 8,
+        proof: &[
+            "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+            "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+            "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4",
+        ],
+    },
+    ProofVector {
+        first: 5,
+        second: 8,
+        proof: &[
+            "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+            "ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0",
+            "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+        ],
+    },
+    ProofVector { first: 2, second: 3, proof: &["fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125"] },
+    ProofVector {
+        first: 1,
+        second: 5,
+        proof: &[
+            "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+            "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+            "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+        ],
+    },
+];
+/// Consistency proofs over the reference leaves
+const CONSISTENCY_PROOFS: &[ProofVector] = &[
+    ProofVector { first: 1, second: 1, proof: &[] },
+    ProofVector {
+        first: 1,
+        second: 8,
+        proof: &[
+            "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+            "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+            "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4",
+        ],
+    },
+    ProofVector {
+        first: 6,
+        second: 8,
+        proof: &[
+            "0ebc5d3437fbe2db158b9f126a1d118e308181031d0a949f8dededebc558ef6a",
+            "ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0",
+            "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+        ],
+    },
+    ProofVector {
+        first: 2,
+        second: 5,
+        proof: &[
+            "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+            "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+        ],
+    },
+    ProofVector {
+        first: 3,
+        second: 7,
+        proof: &[
+            "0298d122906dcfc10892cb53a73992fc5b9f493ea4c9badb27b791b4127a7fe7",
+            "07506a85fd9dd2f120eb694f86011e5bb4662e5c415a62917033d4a9624487e7",
+            "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+            "837dbb152e9b079010717e84e865da4ebc0fa198a806d59d31bf15accef22d0e",
+        ],
+    },
+];
+
+/// Tests the roots of the reference trees
+#[test]
+fn roots() {
+    let mut tree = MerkleTree::new(HashAlgorithm::Sha2_256);
+    assert_eq!(tree.root(), hex(EMPTY_ROOT), "Invalid empty root");
+    for (leaf, root) in LEAVES.iter().zip(ROOTS) {
+        tree.append(&hex(leaf));
+        assert_eq!(tree.root(), hex(root), "Invalid root for tree size {}", tree.len());
+    }
+
+    // Check the historical roots
+    for (size, root) in (1..).zip(ROOTS) {
+        assert_eq!(tree.root_at(size), Ok(hex(root)), "Invalid root for tree size {size}");
+    }
+    assert_eq!(tree.root_at(0), Ok(hex(EMPTY_ROOT)), "Invalid empty root");
+    assert_eq!(tree.root_at(9), Err(Error::InvalidLength), "Accepted future tree size");
+}
+
+/// Tests the reference inclusion proofs
+#[test]
+fn inclusion_proofs() {
+    let tree = reference_tree();
+    for vector in INCLUSION_PROOFS {
+        let proof = tree.inclusion_proof(vector.first, vector.second).expect("Failed to create inclusion proof");
+        let expected: Vec<Vec<u8>> = vector.proof.iter().map(|node| hex(node)).collect();
+        assert_eq!(proof, expected, "Invalid inclusion proof for leaf {} in tree {}", vector.first, vector.second);
+    }
+
+    // Create and verify proofs for all leaves of all tree sizes
+    for tree_size in 1..=8 {
+        let root = tree.root_at(tree_size).expect("Failed to compute root");
+        for index in 0..tree_size {
+            let leaf = tree.leaf(index).expect("Missing leaf");
+            let proof = tree.inclusion_proof(index, tree_size).expect("Failed to create inclusion proof");
+            merkle::verify_inclusion(HashAlgorithm::Sha2_256, index, tree_size, leaf, &proof, &root)
+                .expect("Failed to verify inclusion proof");
+
+            // Proofs must not verify for other leaves or leaf indices
+            let other_leaf = merkle::leaf_hash(HashAlgorithm::Sha2_256, b"other");
+            let result =
+                merkle::verify_inclusion(HashAlgorithm::Sha2_256, index, tree_size, &other_leaf, &proof, &root);
+            assert_eq!(result, Err(Error::VerificationFailed), "Accepted invalid leaf");
+            if index ^ 1 < tree_size {
+                let result =
+                    merkle::verify_inclusion(HashAlgorithm::Sha2_256, index ^ 1, tree_size, leaf, &proof, &root);
+                assert_eq!(result, Err(Error::VerificationFailed), "Accepted invalid leaf index");
+            }
+        }
+    }
+
+    // Check invalid parameters
+    assert_eq!(tree.inclusion_proof(8, 8), Err(Error::InvalidLength), "Accepted index outside of the tree");
+    assert_eq!(tree.inclusion_proof(0, 9), Err(Error::InvalidLength), "Accepted future tree size");
+}
+
+/// Tests the reference consistency proofs
+#[test]
+fn consistency_proofs() {
+    let tree = reference_tree();
+    for vector in CONSISTENCY_PROOFS {
+        let proof = tree.consistency_proof(vector.first, vector.second).expect("Failed to create consistency proof");
+        let expected: Vec<Vec<u8>> = vector.proof.iter().map(|node| hex(node)).collect();
+        assert_eq!(proof, expected, "Invalid consistency proof from {} to {}", vector.first, vector.second);
+    }
+
+    // Create and verify proofs for all pairs of tree sizes
+    for new_size in 1..=8 {
+        let new_root = tree.root_at(new_size).expect("Failed to compute root");
+        for old_size in 1..=new_size {
+            let old_root = tree.root_at(old_size).expect("Failed to compute root");
+            let proof = tree.consistency_proof(old_size, new_size).expect("Failed to create consistency proof");
+            merkle::verify_consistency(HashAlgorithm::Sha2_256, old_size, new_size, &old_root, &new_root, &proof)
+                .expect("Failed to verify consistency proof");
+
+            // Proofs must not verify for other roots
+            let result =
+                merkle::verify_consistency(HashAlgorithm::Sha2_256, old_size, new_size, &new_root, &old_root, &proof);
+            if old_root != new_root {
+                assert_eq!(result, Err(Error::VerificationFailed), "Accepted swapped roots");
+            }
+        }
+    }
+
+    // Check invalid parameters
+    assert_eq!(tree.consistency_proof(0, 8), Err(Error::InvalidLength), "Accepted empty old tree");
+    assert_eq!(tree.consistency_proof(5, 4), Err(Error::InvalidLength), "Accepted shrinking tree");
+    assert_eq!(tree.consistency_proof(4, 9), Err(Error::InvalidLength), "Accepted future tree size");
+}
+
+/// Tests that batched appends produce the same tree as single appends for all hash functions
+#[test]
+fn append_many() {
+    let entries: Vec<Vec<u8>> = (0..1000u32).map(|i| i.to_be_bytes().repeat(i as usize % 7)).collect();
+    let entries: Vec<&[u8]> = entries.iter().map(Vec::as_slice).collect();
+
+    for hash in [HashAlgorithm::Sha2_256, HashAlgorithm::Sha2_512, HashAlgorithm::Blake2b] {
+        let mut single = MerkleTree::new(hash);
+        entries.iter().for_each(|entry| _ = single.append(entry));
+
+        // Append the same entries in uneven batches
+        let mut batched = MerkleTree::new(hash);
+        for batch in entries.chunks(333) {
+            batched.append_many(batch);
+        }
+        assert_eq!(batched.root(), single.root(), "Invalid root for batched appends");
+
+        // Check a proof in the larger tree
+        let proof = batched.inclusion_proof(777, 1000).expect("Failed to create inclusion proof");
+        let leaf = merkle::leaf_hash(hash, entries[777]);
+        merkle::verify_inclusion(hash, 777, 1000, &leaf, &proof, &single.root()).expect("Invalid inclusion proof");
+        let proof = batched.consistency_proof(333, 1000).expect("Failed to create consistency proof");
+        let old_root = single.root_at(333).expect("Failed to compute root");
+        merkle::verify_consistency(hash, 333, 1000, &old_root, &single.root(), &proof)
+            .expect("Invalid consistency proof");
+    }
+}
+
+/// Tests the hash chain
+#[test]
+fn hash_chain() {
+    let mut chain = HashChain::new(HashAlgorithm::Sha2_256);
+    assert!(chain.is_empty(), "New chain is not empty");
+    assert_eq!(chain.head(), [0; 32], "Invalid initial head");
+
+    let head = chain.append(b"first").to_vec();
+    assert_eq!(head, hex("3db4b4eb1df29e1585bc017b9194e30e583d7dbe9e2a7513a58442c6d4ac96bc"), "Invalid head");
+    chain.append(b"second");
+    assert_eq!(chain.head(), hex("de1e86981ce97f7ca334a50ce77d42ace7c020d4c3d4dd9aa6185f4fd8bf40a0"), "Invalid head");
+    assert_eq!(chain.len(), 2, "Invalid chain length");
+
+    // Resume the chain from the first head
+    let mut resumed = HashChain::from_head(HashAlgorithm::Sha2_256, &head, 1).expect("Failed to resume chain");
+    resumed.append(b"second");
+    assert_eq!(resumed.head(), chain.head(), "Invalid resumed head");
+    let result = HashChain::from_head(HashAlgorithm::Sha2_512, &head, 1);
+    assert_eq!(result.err(), Some(Error::InvalidLength), "Accepted invalid head length");
+}
+
+/// Creates the tree over the reference leaves
+fn reference_tree() -> MerkleTree {
+    let leaves: Vec<Vec<u8>> = LEAVES.iter().map(|leaf| hex(leaf)).collect();
+    let leaves: Vec<&[u8]> = leaves.iter().map(Vec::as_slice).collect();
+
+    let mut tree = MerkleTree::new(HashAlgorithm::Sha2_256);
+    tree.append_many(&leaves);
+    tree
+}
@@ -0,0 +1,199 @@
+mod common;
+
+use common::{hex, ReplayRng};
+use evercrypt_tiny::{
+    nacl::{self, Box, Nonce, SecretBox, SEAL_OVERHEAD, TAG_LEN},
+    x25519::{X25519PublicKey, X25519SecretKey},
+    Error,
+};
+use rand_core::OsRng;
+
+/// `crypto_secretbox_easy` outputs for random keys, nonces and messages, generated with libsodium 1.0.18
+const SECRETBOX_LIBSODIUM: &str = include_str!("fixtures/nacl-secretbox-libsodium.txt");
+/// `crypto_box_beforenm` and `crypto_box_easy` outputs for random keys, nonces and messages, generated with libsodium
+/// 1.0.18
+const BOX_LIBSODIUM: &str = include_str!("fixtures/nacl-box-libsodium.txt");
+/// Sealed boxes for fixed ephemeral keys, computed with libsodium's `crypto_box_easy` and `crypto_generichash` and
+/// checked with `crypto_box_seal_open`, and sealed boxes generated by libsodium 1.0.18's `crypto_box_seal`
+const SEALEDBOX_LIBSODIUM: &str = include_str!("fixtures/nacl-sealedbox-libsodium.txt");
+
+/// Parses the tab-separated values of all vectors in a fixture
+fn parse(fixture: &str) -> Vec<Vec<Vec<u8>>> {
+    fixture
+        .split("\n\n")
+        .map(|vector| vector.lines().map(|line| hex(line.split_once('\t').expect("Invalid fixture line").1)).collect())
+        .collect()
+}
+
+/// Tests the secret box against libsodium in the combined and detached format
+#[test]
+fn secretbox_libsodium() {
+    for vector in parse(SECRETBOX_LIBSODIUM) {
+        let [key, nonce, message, ciphertext] = vector.as_slice() else { panic!("Invalid secretbox vector") };
+        let secretbox = SecretBox::from_bytes(key).expect("Failed to load key");
+        let nonce = Nonce::from_bytes(nonce).expect("Failed to load nonce");
+
+        // Combined format
+        assert_eq!(&secretbox.seal(&nonce, message).expect("Failed to seal message"), ciphertext);
+        assert_eq!(&secretbox.open(&nonce, ciphertext).expect("Failed to open message"), message);
+
+        // Detached format
+        let (tag, detached) = ciphertext.split_at(TAG_LEN);
+        let tag = tag.try_into().expect("Invalid tag length");
+        assert_eq!(secretbox.seal_detached(&nonce, message).expect("Failed to seal message"), (detached.to_vec(), tag));
+        assert_eq!(&secretbox.open_detached(&nonce, detached, &tag).expect("Failed to open message"), message);
+    }
+}
+
+/// Tests the box and its precomputed shared key against libsodium in the combined and detached format
+#[test]
+fn box_libsodium() {
+    for vector in parse(BOX_LIBSODIUM) {
+        let [secret_key, public_key, shared_key, nonce, message, ciphertext] = vector.as_slice() else {
+            panic!("Invalid box vector")
+        };
+        let secret_key = X25519SecretKey::from_bytes(secret_key).expect("Failed to load secret key");
+        let public_key = X25519PublicKey::from_bytes(public_key).expect("Failed to load public key");
+        let nonce = Nonce::from_bytes(nonce).expect("Failed to load nonce");
+
+        // Precompute the shared key
+        let r#box = Box::new(&secret_key, &public_key).expect("Failed to compute shared key");
+        assert_eq!(r#box.to_shared_key().as_slice(), shared_key);
+
+        // Combined format
+        assert_eq!(&r#box.seal(&nonce, message).expect("Failed to seal message"), ciphertext);
+        assert_eq!(&r#box.open(&nonce, ciphertext).expect("Failed to open message"), message);
+
+        // Detached format
+        let (tag, detached) = ciphertext.split_at(TAG_LEN);
+        let tag = tag.try_into().expect("Invalid tag length");
+        assert_eq!(r#box.seal_detached(&nonce, message).expect("Failed to seal message"), (detached.to_vec(), tag));
+        assert_eq!(&r#box.open_detached(&nonce, detached, &tag).expect("Failed to open message"), message);
+
+        // A box loaded from the shared key is equivalent
+        let loaded = Box::from_shared_key(shared_key).expect("Failed to load shared key");
+        assert_eq!(&loaded.open(&nonce, ciphertext).expect("Failed to open message"), message);
+    }
+}
+
+/// Tests that both parties derive the same box
+#[test]
+fn box_roundtrip() {
+    let alice = X25519SecretKey::generate(&mut OsRng).expect("Failed to generate key");
+    let bob = X25519SecretKey::generate(&mut OsRng).expect("Failed to generate key");
+    let alice_box = Box::new(&alice, &bob.public_key()).expect("Failed to compute shared key");
+    let bob_box = Box::new(&bob, &alice.public_key()).expect("Failed to compute shared key");
+    assert_eq!(alice_box.to_shared_key(), bob_box.to_shared_key());
+
+    let nonce = Nonce::generate(&mut OsRng).expect("Failed to generate nonce");
+    let ciphertext = alice_box.seal(&nonce, b"Hello Bob").expect("Failed to seal message");
+    assert_eq!(bob_box.open(&nonce, &ciphertext).expect("Failed to open message"), b"Hello Bob");
+}
+
+/// Tests sealed boxes against libsodium
+#[test]
+fn sealedbox_libsodium() {
+    for vector in parse(SEALEDBOX_LIBSODIUM) {
+        let [secret_key, ephemeral_secret_key, message, sealed, libsodium_sealed] = vector.as_slice() else {
+            panic!("Invalid sealed box vector")
+        };
+        let secret_key = X25519SecretKey::from_bytes(secret_key).expect("Failed to load secret key");
+
+        // Seal with the fixed ephemeral key
+        let mut rng = ReplayRng(ephemeral_secret_key.clone());
+        let sealed_ = nacl::box_seal(&secret_key.public_key(), message, &mut rng).expect("Failed to seal message");
+        assert_eq!(&sealed_, sealed);
+
+        // Open both boxes
+        for sealed in [sealed, libsodium_sealed] {
+            assert_eq!(sealed.len(), message.len() + SEAL_OVERHEAD);
+            assert_eq!(&nacl::box_seal_open(&secret_key, sealed).expect("Failed to open sealed box"), message);
+        }
+    }
+}
+
+/// Tests sealed boxes with random keys and invalid sealed boxes
+#[test]
+fn sealedbox() {
+    let secret_key = X25519SecretKey::generate(&mut OsRng).expect("Failed to generate key");
+    let sealed = nacl::box_seal(&secret_key.public_key(), b"Testolope", &mut OsRng).expect("Failed to seal message");
+    assert_eq!(nacl::box_seal_open(&secret_key, &sealed).expect("Failed to open sealed box"), b"Testolope");
+
+    // Every bit of the ephemeral public key, the tag and the ciphertext is authenticated
+    for index in 0..sealed.len() * 8 {
+        let mut forged = sealed.clone();
+        forged[index / 8] ^= 1 << (index % 8);
+        assert!(nacl::box_seal_open(&secret_key, &forged).is_err());
+    }
+
+    // Wrong key and truncated boxes
+    let other_key = X25519SecretKey::generate(&mut OsRng).expect("Failed to generate key");
+    assert_eq!(nacl::box_seal_open(&other_key, &sealed).unwrap_err(), Error::DecryptionFailed);
+    assert_eq!(nacl::box_seal_open(&secret_key, &sealed[..SEAL_OVERHEAD - 1]).unwrap_err(), Error::InvalidLength);
+    assert_eq!(nacl::box_seal_open(&secret_key, &sealed[..SEAL_OVERHEAD]).unwrap_err(), Error::DecryptionFailed);
+
+    // Low-order recipient keys and a failing RNG
+    let low_order = X25519PublicKey::from_bytes(&[0; 32]).expect("Failed to load public key");
+    assert_eq!(nacl::box_seal(&low_order, b"", &mut OsRng).unwrap_err(), Error::InvalidKey);
+    let mut rng = ReplayRng(vec![0; 31]);
+    assert_eq!(nacl::box_seal(&secret_key.public_key(), b"", &mut rng).unwrap_err(), Error::RngFailure);
+}
+
+/// Tests nonce handling like libsodium's `sodium_increment`
+#[test]
+fn nonce() {
+    let mut nonce = Nonce::from([0xff; 24]);
+    nonce.increment();
+    assert_eq!(nonce.to_bytes(), [0; 24]);
+
+    let mut bytes = [0; 24];
+    bytes[..3].copy_from_slice(&[0xff, 0xff, 0x01]);
+    let mut nonce = Nonce::from(bytes);
+    nonce.increment();
+    assert_eq!(nonce.to_bytes()[..4], [0x00, 0x00, 0x02, 0x00]);
+
+    assert_eq!(Nonce::from_bytes(&[0; 23]).unwrap_err(), Error::InvalidLength);
+    assert_eq!(Nonce::from_bytes(&[0; 25]).unwrap_err(), Error::InvalidLength);
+}
+
+/// Tests forged messages, invalid keys and invalid lengths
+#[test]
+fn invalid() {
+    let secretbox = SecretBox::generate(&mut OsRng).expect("Failed to generate key");
+    let nonce = Nonce::generate(&mut OsRng).expect("Failed to generate nonce");
+    let ciphertext = secretbox.seal(&nonce, b"Testolope").expect("Failed to seal message");
+
+    // Flip every bit once
+    for index in 0..ciphertext.len() * 8 {
+        let mut forged = ciphertext.clone();
+        forged[index / 8] ^= 1 << (index % 8);
+        assert_eq!(secretbox.open(&nonce, &forged).unwrap_err(), Error::DecryptionFailed);
+    }
+
+    // Wrong nonce, wrong key and truncated messages
+    let mut other_nonce = nonce;
+    other_nonce.increment();
+    assert_eq!(secretbox.open(&other_nonce, &ciphertext).unwrap_err(), Error::DecryptionFailed);
+    let other_key = SecretBox::generate(&mut OsRng).expect("Failed to generate key");
+    assert_eq!(other_key.open(&nonce, &ciphertext).unwrap_err(), Error::DecryptionFailed);
+    assert_eq!(secretbox.open(&nonce, &ciphertext[..TAG_LEN - 1]).unwrap_err(), Error::InvalidLength);
+    assert_eq!(secretbox.open(&nonce, &ciphertext[..TAG_LEN]).unwrap_err(), Error::DecryptionFailed);
+    assert_eq!(
+        secretbox.open_detached(&nonce, &ciphertext[TAG_LEN..], &[0; TAG_LEN]).unwrap_err(),
+        Error::DecryptionFailed
+    );
+
+    // Invalid key lengths
+    assert_eq!(SecretBox::from_bytes(&[0; 31]).unwrap_err(), Error::InvalidKey);
+    assert_eq!(Box::from_shared_key(&[0; 33]).unwrap_err(), Error::InvalidKey);
+
+    // Low-order public keys yield an all-zero shared secret
+    let secret_key = X25519SecretKey::generate(&mut OsRng).expect("Failed to generate key");
+    for point in [
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0100000000000000000000000000000000000000000000000000000000000000",
+    ] {
+        let public_key = X25519PublicKey::from_bytes(&hex(point)).expect("Failed to load public key");
+        assert_eq!(Box::new(&secret_key, &public_key).unwrap_err(), Error::InvalidKey);
+    }
+}
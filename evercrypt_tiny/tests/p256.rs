@@ -0,0 +1,133 @@
+mod common;
+
+use common::{hex, ReplayRng};
+use evercrypt_tiny::{
+    p256::{P256PublicKey, P256SecretKey, SECRET_KEY_LEN},
+    Error,
+};
+use rand_core::OsRng;
+use wycheproof::{
+    ecdh::{TestName, TestSet},
+    TestResult,
+};
+
+/// Converts a Wycheproof ASN.1 integer into a fixed-length big-endian scalar
+fn scalar(integer: &[u8]) -> Option<[u8; SECRET_KEY_LEN]> {
+    let start = integer.iter().position(|byte| *byte != 0).unwrap_or(integer.len());
+    let trimmed = &integer[start..];
+    if trimmed.len() > SECRET_KEY_LEN {
+        return None;
+    }
+
+    let mut scalar = [0; SECRET_KEY_LEN];
+    scalar[SECRET_KEY_LEN - trimmed.len()..].copy_from_slice(trimmed);
+    Some(scalar)
+}
+
+/// Runs the Wycheproof `ecdh_secp256r1_ecpoint` vectors
+#[test]
+fn wycheproof_ecdh() {
+    let test_set = TestSet::load(TestName::EcdhSecp256r1Ecpoint).expect("Failed to load test set");
+    for test in test_set.test_groups.iter().flat_map(|group| &group.tests) {
+        let secret_key = scalar(&test.private_key).and_then(|scalar| P256SecretKey::from_bytes(&scalar).ok());
+        let public_key = P256PublicKey::from_sec1(&test.public_key);
+        let shared = match (secret_key, public_key) {
+            (Some(secret_key), Ok(public_key)) => secret_key.ecdh(&public_key),
+            (_, Err(e)) => Err(e),
+            (None, _) => Err(Error::InvalidKey),
+        };
+
+        match (test.result, shared) {
+            (TestResult::Valid | TestResult::Acceptable, Ok(shared)) => {
+                assert_eq!(shared.as_slice(), test.shared_secret.as_slice(), "Test case {}", test.tc_id)
+            }
+            (TestResult::Invalid | TestResult::Acceptable, Err(_)) => (/* Expected failure */),
+            (result, shared) => {
+                panic!("Test case {} ({}): expected {result:?}, got {shared:?}", test.tc_id, test.comment)
+            }
+        }
+    }
+}
+
+/// Tests that SEC1 compressed and uncompressed encodings round-trip and describe the same point
+#[test]
+fn sec1_roundtrip() {
+    const UNCOMPRESSED: &str = "046b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c2964fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5";
+    const COMPRESSED: &str = "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+
+    let uncompressed = hex(UNCOMPRESSED);
+    let compressed = hex(COMPRESSED);
+    let from_uncompressed = P256PublicKey::from_sec1(&uncompressed).expect("Failed to decode uncompressed point");
+    let from_compressed = P256PublicKey::from_sec1(&compressed).expect("Failed to decode compressed point");
+
+    assert_eq!(from_uncompressed, from_compressed);
+    assert_eq!(from_uncompressed.to_uncompressed().as_slice(), uncompressed.as_slice());
+    assert_eq!(from_uncompressed.to_compressed().as_slice(), compressed.as_slice());
+}
+
+/// Tests that malformed encodings and points off the curve are rejected
+#[test]
+fn invalid_public_keys() {
+    const NOT_ON_CURVE: &str = "046b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c2964fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f6";
+
+    assert_eq!(P256PublicKey::from_sec1(&[0x00]), Err(Error::InvalidEncoding));
+    assert_eq!(P256PublicKey::from_sec1(&hex(NOT_ON_CURVE)[..64]), Err(Error::InvalidEncoding));
+    assert!(P256PublicKey::from_sec1(&hex(NOT_ON_CURVE)).is_err());
+}
+
+/// Tests that scalars outside of `[1, n)` are rejected
+#[test]
+fn invalid_secret_keys() {
+    const ORDER: &str = "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551";
+
+    assert_eq!(P256SecretKey::from_bytes(&[0; SECRET_KEY_LEN]).unwrap_err(), Error::InvalidKey);
+    assert_eq!(P256SecretKey::from_bytes(&hex(ORDER)).unwrap_err(), Error::InvalidKey);
+    assert_eq!(P256SecretKey::from_bytes(&[1; 31]).unwrap_err(), Error::InvalidKey);
+}
+
+/// Tests `DeriveKeyPair` against the DHKEM(P-256, HKDF-SHA256) vectors from RFC 9180, appendix A.3
+#[test]
+fn derive_rfc9180() {
+    const VECTORS: &[(&str, &str, &str)] = &[
+        (
+            "4270e54ffd08d79d5928020af4686d8f6b7d35dbe470265f1f5aa22816ce860e",
+            "4995788ef4b9d6132b249ce59a77281493eb39af373d236a1fe415cb0c2d7beb",
+            "04a92719c6195d5085104f469a8b9814d5838ff72b60501e2c4466e5e67b325ac98536d7b61a1af4b78e5b7f951c0900be863c403ce65c9bfcb9382657222d18c4",
+        ),
+        (
+            "668b37171f1072f3cf12ea8a236a45df23fc13b82af3609ad1e354f6ef817550",
+            "f3ce7fdae57e1a310d87f1ebbde6f328be0a99cdbcadf4d6589cf29de4b8ffd2",
+            "04fe8c19ce0905191ebc298a9245792531f26f0cece2460639e8bc39cb7f706a826a779b4cf969b8a0e539c7f62fb3d30ad6aa8f80e30f1d128aafd68a2ce72ea0",
+        ),
+    ];
+
+    for (ikm, secret_key, public_key) in VECTORS {
+        let derived = P256SecretKey::derive(&hex(ikm)).expect("Failed to derive secret key");
+        assert_eq!(derived.to_bytes().as_slice(), hex(secret_key).as_slice());
+        assert_eq!(derived.public_key().to_uncompressed().as_slice(), hex(public_key).as_slice());
+    }
+}
+
+/// Tests that key generation yields usable key pairs and agrees on a shared secret
+#[test]
+fn generate() {
+    let alice = P256SecretKey::generate(&mut OsRng).expect("Failed to generate secret key");
+    let bob = P256SecretKey::generate(&mut OsRng).expect("Failed to generate secret key");
+    assert_ne!(alice.to_bytes(), bob.to_bytes());
+
+    let alice_shared = alice.ecdh(&bob.public_key()).expect("Failed to perform key agreement");
+    let bob_shared = bob.ecdh(&alice.public_key()).expect("Failed to perform key agreement");
+    assert_eq!(alice_shared, bob_shared);
+}
+
+/// Tests that candidates outside of `[1, n)` are rejected and that a broken RNG is detected
+#[test]
+fn generate_rejection_sampling() {
+    let mut rng = ReplayRng([[0x00; SECRET_KEY_LEN], [0xff; SECRET_KEY_LEN], [0x42; SECRET_KEY_LEN]].concat());
+    let secret_key = P256SecretKey::generate(&mut rng).expect("Failed to generate secret key");
+    assert_eq!(secret_key.to_bytes().as_bytes(), &[0x42; SECRET_KEY_LEN]);
+
+    // The key generation gives up after 64 invalid candidates, before the RNG is exhausted
+    let mut rng = ReplayRng([0x00; SECRET_KEY_LEN].repeat(64));
+    assert_eq!(P256SecretKey::generate(&mut rng).unwrap_err(), Error::RngFailure);
+}
@@ -0,0 +1,180 @@
+use evercrypt_tiny::{
+    ed25519::{Ed25519PublicKey, Ed25519SecretKey},
+    p256::{P256PublicKey, P256SecretKey},
+    pkcs::{
+        DecodePrivateKey, DecodePublicKey, DecodeRsaPrivateKey, DecodeRsaPublicKey, EncodePrivateKey, EncodePublicKey,
+        EncodeRsaPrivateKey, EncodeRsaPublicKey,
+    },
+    rsa::{RsaCrtComponents, RsaPssPrivateKey, RsaPssPublicKey},
+    x25519::{X25519PublicKey, X25519SecretKey},
+    Error,
+};
+use wycheproof::eddsa::{TestName, TestSet};
+
+/// Tests that OpenSSL generated RSA keys round-trip through all supported containers
+#[test]
+fn rsa_roundtrip() {
+    const PKCS8_PEM: &str = include_str!("fixtures/rsa2048_pkcs8.pem");
+    const PKCS8_DER: &[u8] = include_bytes!("fixtures/rsa2048_pkcs8.der");
+    const PKCS1_PEM: &str = include_str!("fixtures/rsa2048_pkcs1.pem");
+    const SPKI_PEM: &str = include_str!("fixtures/rsa2048_spki.pem");
+    const PKCS1_PUBLIC_PEM: &str = include_str!("fixtures/rsa2048_pkcs1_public.pem");
+
+    // Decode the private key from all containers
+    let private_key = RsaPssPrivateKey::from_pkcs8_pem(PKCS8_PEM).expect("Failed to decode PKCS#8 PEM");
+    let from_der = RsaPssPrivateKey::from_pkcs8_der(PKCS8_DER).expect("Failed to decode PKCS#8 DER");
+    let from_pkcs1 = RsaPssPrivateKey::from_pkcs1_pem(PKCS1_PEM).expect("Failed to decode PKCS#1 PEM");
+    assert_eq!(private_key.private_exponent(), from_der.private_exponent());
+    assert_eq!(private_key.crt_components(), from_pkcs1.crt_components());
+
+    // Re-encode the private key
+    assert_eq!(private_key.to_pkcs8_pem().expect("Failed to encode PKCS#8 PEM").as_str(), PKCS8_PEM);
+    assert_eq!(private_key.to_pkcs8_der().expect("Failed to encode PKCS#8 DER").as_bytes(), PKCS8_DER);
+    assert_eq!(private_key.to_pkcs1_pem().expect("Failed to encode PKCS#1 PEM").as_str(), PKCS1_PEM);
+
+    // Decode and re-encode the public key
+    let public_key = RsaPssPublicKey::from_public_key_pem(SPKI_PEM).expect("Failed to decode SPKI PEM");
+    let from_pkcs1 = RsaPssPublicKey::from_pkcs1_pem(PKCS1_PUBLIC_PEM).expect("Failed to decode PKCS#1 PEM");
+    assert_eq!(&public_key, private_key.public_key());
+    assert_eq!(public_key, from_pkcs1);
+    assert_eq!(public_key.to_public_key_pem().expect("Failed to encode SPKI PEM"), SPKI_PEM);
+    assert_eq!(public_key.to_pkcs1_pem().expect("Failed to encode PKCS#1 PEM"), PKCS1_PUBLIC_PEM);
+}
+
+/// Tests that RSA private keys without CRT components cannot be exported
+#[test]
+fn rsa_missing_crt_components() {
+    const PKCS1_PEM: &str = include_str!("fixtures/rsa2048_pkcs1.pem");
+
+    let decoded = RsaPssPrivateKey::from_pkcs1_pem(PKCS1_PEM).expect("Failed to decode PKCS#1 PEM");
+    let public_key = decoded.public_key();
+    let private_key = RsaPssPrivateKey::from_components(
+        public_key.modulus(),
+        public_key.public_exponent(),
+        decoded.private_exponent(),
+    )
+    .expect("Failed to load private key");
+    assert_eq!(private_key.to_pkcs1_der().unwrap_err(), Error::InvalidKey);
+    assert_eq!(private_key.to_pkcs8_der().unwrap_err(), Error::InvalidKey);
+}
+
+/// Tests that RSA private keys with inconsistent CRT components are rejected
+#[test]
+fn rsa_inconsistent_crt_components() {
+    const PKCS1_PEM: &str = include_str!("fixtures/rsa2048_pkcs1.pem");
+
+    // Corrupt the last byte of the DER encoding, which belongs to `qInv`
+    let decoded = RsaPssPrivateKey::from_pkcs1_pem(PKCS1_PEM).expect("Failed to decode PKCS#1 PEM");
+    let mut pkcs1 = decoded.to_pkcs1_der().expect("Failed to encode PKCS#1 DER").to_vec();
+    *pkcs1.last_mut().unwrap() ^= 0x01;
+    assert_eq!(RsaPssPrivateKey::from_pkcs1_der(&pkcs1).unwrap_err(), Error::InvalidKey);
+
+    // Corrupt every component individually
+    let crt = decoded.crt_components().expect("Missing CRT components");
+    let corruptions: [fn(&mut RsaCrtComponents); 6] = [
+        |crt| *crt.p.last_mut().unwrap() ^= 0x02,
+        |crt| *crt.q.last_mut().unwrap() ^= 0x02,
+        |crt| *crt.dp.last_mut().unwrap() ^= 0x01,
+        |crt| *crt.dq.last_mut().unwrap() ^= 0x01,
+        |crt| *crt.qinv.last_mut().unwrap() ^= 0x01,
+        |crt| (crt.p, crt.q) = (crt.q.clone(), crt.p.clone()),
+    ];
+    for corrupt in corruptions {
+        let mut corrupted = crt.clone();
+        corrupt(&mut corrupted);
+        assert_eq!(decoded.clone().with_crt_components(corrupted).unwrap_err(), Error::InvalidKey);
+    }
+    decoded.clone().with_crt_components(crt.clone()).expect("Failed to attach valid CRT components");
+}
+
+/// Tests that OpenSSL generated P-256 keys round-trip through PKCS#8 and SPKI
+#[test]
+fn p256_roundtrip() {
+    const PKCS8_PEM: &str = include_str!("fixtures/p256_pkcs8.pem");
+    const PKCS8_DER: &[u8] = include_bytes!("fixtures/p256_pkcs8.der");
+    const SPKI_PEM: &str = include_str!("fixtures/p256_spki.pem");
+    const SPKI_DER: &[u8] = include_bytes!("fixtures/p256_spki.der");
+
+    let secret_key = P256SecretKey::from_pkcs8_pem(PKCS8_PEM).expect("Failed to decode PKCS#8 PEM");
+    let from_der = P256SecretKey::from_pkcs8_der(PKCS8_DER).expect("Failed to decode PKCS#8 DER");
+    assert_eq!(secret_key.to_bytes(), from_der.to_bytes());
+    assert_eq!(secret_key.to_pkcs8_pem().expect("Failed to encode PKCS#8 PEM").as_str(), PKCS8_PEM);
+    assert_eq!(secret_key.to_pkcs8_der().expect("Failed to encode PKCS#8 DER").as_bytes(), PKCS8_DER);
+
+    let public_key = P256PublicKey::from_public_key_pem(SPKI_PEM).expect("Failed to decode SPKI PEM");
+    assert_eq!(public_key, P256PublicKey::from_public_key_der(SPKI_DER).expect("Failed to decode SPKI DER"));
+    assert_eq!(public_key, secret_key.public_key());
+    assert_eq!(public_key.to_public_key_pem().expect("Failed to encode SPKI PEM"), SPKI_PEM);
+    assert_eq!(public_key.to_public_key_der().expect("Failed to encode SPKI DER"), SPKI_DER);
+}
+
+/// Tests that OpenSSL generated Ed25519 keys round-trip through PKCS#8 and SPKI
+#[test]
+fn ed25519_roundtrip() {
+    const PKCS8_PEM: &str = include_str!("fixtures/ed25519_pkcs8.pem");
+    const SPKI_PEM: &str = include_str!("fixtures/ed25519_spki.pem");
+
+    let secret_key = Ed25519SecretKey::from_pkcs8_pem(PKCS8_PEM).expect("Failed to decode PKCS#8 PEM");
+    assert_eq!(secret_key.to_pkcs8_pem().expect("Failed to encode PKCS#8 PEM").as_str(), PKCS8_PEM);
+
+    let public_key = Ed25519PublicKey::from_public_key_pem(SPKI_PEM).expect("Failed to decode SPKI PEM");
+    assert_eq!(public_key, secret_key.public_key());
+    assert_eq!(public_key.to_public_key_pem().expect("Failed to encode SPKI PEM"), SPKI_PEM);
+}
+
+/// Tests that the SPKI encoded public keys of the Wycheproof `ed25519` vectors decode to the raw public keys
+#[test]
+fn ed25519_wycheproof_spki() {
+    let test_set = TestSet::load(TestName::Ed25519).expect("Failed to load test set");
+    for group in test_set.test_groups {
+        let public_key = Ed25519PublicKey::from_public_key_der(&group.der).expect("Failed to decode SPKI DER");
+        assert_eq!(public_key.to_bytes().as_slice(), group.key.pk.as_slice());
+        assert_eq!(public_key.to_public_key_der().expect("Failed to encode SPKI DER"), group.der.as_slice());
+    }
+}
+
+/// Tests that OpenSSL generated X25519 keys round-trip through PKCS#8 and SPKI
+#[test]
+fn x25519_roundtrip() {
+    const PKCS8_PEM: &str = include_str!("fixtures/x25519_pkcs8.pem");
+    const SPKI_PEM: &str = include_str!("fixtures/x25519_spki.pem");
+
+    let secret_key = X25519SecretKey::from_pkcs8_pem(PKCS8_PEM).expect("Failed to decode PKCS#8 PEM");
+    assert_eq!(secret_key.to_pkcs8_pem().expect("Failed to encode PKCS#8 PEM").as_str(), PKCS8_PEM);
+
+    let public_key = X25519PublicKey::from_public_key_pem(SPKI_PEM).expect("Failed to decode SPKI PEM");
+    assert_eq!(public_key, secret_key.public_key());
+    assert_eq!(public_key.to_public_key_pem().expect("Failed to encode SPKI PEM"), SPKI_PEM);
+}
+
+/// Tests that mismatching labels, algorithms and malformed encodings are rejected
+#[test]
+fn invalid_encodings() {
+    const P256_PKCS8_DER: &[u8] = include_bytes!("fixtures/p256_pkcs8.der");
+    const ED25519_PKCS8_PEM: &str = include_str!("fixtures/ed25519_pkcs8.pem");
+    const ED25519_SPKI_PEM: &str = include_str!("fixtures/ed25519_spki.pem");
+
+    // Wrong PEM label and wrong algorithm
+    assert_eq!(Ed25519PublicKey::from_public_key_pem(ED25519_PKCS8_PEM).unwrap_err(), Error::InvalidEncoding);
+    assert_eq!(X25519PublicKey::from_public_key_pem(ED25519_SPKI_PEM).unwrap_err(), Error::UnsupportedAlgorithm);
+    assert_eq!(X25519SecretKey::from_pkcs8_pem(ED25519_PKCS8_PEM).unwrap_err(), Error::UnsupportedAlgorithm);
+
+    // Trailing data, truncation and non-minimal lengths
+    let mut trailing = P256_PKCS8_DER.to_vec();
+    trailing.push(0x00);
+    assert_eq!(P256SecretKey::from_pkcs8_der(&trailing).unwrap_err(), Error::InvalidEncoding);
+    let truncated = &P256_PKCS8_DER[..P256_PKCS8_DER.len() - 1];
+    assert_eq!(P256SecretKey::from_pkcs8_der(truncated).unwrap_err(), Error::InvalidEncoding);
+    let non_minimal = [&[0x30, 0x82, 0x00, 0x87][..], &P256_PKCS8_DER[3..]].concat();
+    assert_eq!(P256SecretKey::from_pkcs8_der(&non_minimal).unwrap_err(), Error::InvalidEncoding);
+
+    // A mismatching embedded public key
+    let mut mismatching = P256_PKCS8_DER.to_vec();
+    let last = mismatching.len() - 1;
+    mismatching[last] ^= 0x01;
+    assert!(P256SecretKey::from_pkcs8_der(&mismatching).is_err());
+
+    // Non-canonical base64
+    let invalid = ED25519_PKCS8_PEM.replace("MC4C", "MC4*");
+    assert_eq!(Ed25519SecretKey::from_pkcs8_pem(&invalid).unwrap_err(), Error::InvalidEncoding);
+}
@@ -0,0 +1,95 @@
+use evercrypt_tiny::{
+    pkcs::{DecodePublicKey, DecodeRsaPrivateKey, EncodePublicKey, EncodeRsaPrivateKey},
+    rsa::{RsaPssPrivateKey, RsaPssPublicKey, PUBLIC_EXPONENT},
+    Error, HashAlgorithm,
+};
+use rand_core::{CryptoRng, OsRng, RngCore};
+
+/// Tests that the private exponent and CRT components computed from OpenSSL generated primes match OpenSSL's
+#[test]
+fn from_primes_fixtures() {
+    const FIXTURES: &[&str] = &[include_str!("fixtures/rsa2048_pkcs1.pem"), include_str!("fixtures/rsa3072_pkcs1.pem")];
+
+    for fixture in FIXTURES {
+        let expected = RsaPssPrivateKey::from_pkcs1_pem(fixture).expect("Failed to decode fixture");
+        let crt = expected.crt_components().expect("Missing CRT components");
+        let e = expected.public_key().public_exponent();
+
+        // Compute the key from the primes in both orders
+        for (p, q) in [(&crt.p, &crt.q), (&crt.q, &crt.p)] {
+            let private_key = RsaPssPrivateKey::from_primes(p, q, e).expect("Failed to compute private key");
+            assert_eq!(private_key.to_pkcs1_pem().expect("Failed to encode private key").as_str(), *fixture);
+        }
+    }
+}
+
+/// Tests that generated keys have the requested size, sign and verify and survive a PKCS#1 round-trip
+#[test]
+fn generate_sign_verify() {
+    let private_key = RsaPssPrivateKey::generate(2048, &mut OsRng).expect("Failed to generate private key");
+    let public_key = private_key.public_key();
+    assert_eq!(public_key.modulus_bits(), 2048);
+    assert_eq!(public_key.public_exponent(), &[0x01, 0x00, 0x01]);
+
+    // Recompute the key from its primes
+    let crt = private_key.crt_components().expect("Missing CRT components");
+    let recomputed = RsaPssPrivateKey::from_primes(&crt.p, &crt.q, public_key.public_exponent())
+        .expect("Failed to compute private key");
+    assert_eq!(recomputed.private_exponent(), private_key.private_exponent());
+    assert_eq!(recomputed.crt_components(), private_key.crt_components());
+
+    // Sign with the reloaded key and verify with the reloaded public key
+    let pkcs1 = private_key.to_pkcs1_der().expect("Failed to encode private key");
+    let reloaded = RsaPssPrivateKey::from_pkcs1_der(&pkcs1).expect("Failed to decode private key");
+    let signature = reloaded.sign(HashAlgorithm::Sha2_256, 32, b"Testolope", &mut OsRng).expect("Failed to sign");
+
+    let spki = public_key.to_public_key_der().expect("Failed to encode public key");
+    let public_key = RsaPssPublicKey::from_public_key_der(&spki).expect("Failed to decode public key");
+    public_key.verify(HashAlgorithm::Sha2_256, 32, b"Testolope", &signature).expect("Failed to verify signature");
+}
+
+/// Tests that larger keys can be generated
+#[test]
+#[ignore = "slow in debug builds"]
+fn generate_large() {
+    for mod_bits in [3072, 4096] {
+        let private_key = RsaPssPrivateKey::generate(mod_bits, &mut OsRng).expect("Failed to generate private key");
+        assert_eq!(private_key.public_key().modulus_bits(), mod_bits);
+
+        let signature =
+            private_key.sign(HashAlgorithm::Sha2_512, 64, b"Testolope", &mut OsRng).expect("Failed to sign");
+        let public_key = private_key.public_key();
+        public_key.verify(HashAlgorithm::Sha2_512, 64, b"Testolope", &signature).expect("Failed to verify signature");
+    }
+}
+
+/// Tests that unsupported sizes, invalid primes and a broken RNG are rejected
+#[test]
+fn invalid_parameters() {
+    /// An RNG that only yields zero bytes
+    struct ZeroRng;
+    impl RngCore for ZeroRng {
+        fn next_u32(&mut self) -> u32 {
+            0
+        }
+        fn next_u64(&mut self) -> u64 {
+            0
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.fill(0);
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+    impl CryptoRng for ZeroRng {}
+
+    assert_eq!(RsaPssPrivateKey::generate(1024, &mut OsRng).unwrap_err(), Error::UnsupportedAlgorithm);
+    assert_eq!(RsaPssPrivateKey::generate(2048, &mut ZeroRng).unwrap_err(), Error::RngFailure);
+
+    let e = PUBLIC_EXPONENT.to_be_bytes();
+    assert_eq!(RsaPssPrivateKey::from_primes(&[61], &[61], &e).unwrap_err(), Error::InvalidKey);
+    assert_eq!(RsaPssPrivateKey::from_primes(&[61], &[62], &e).unwrap_err(), Error::InvalidKey);
+    assert_eq!(RsaPssPrivateKey::from_primes(&[61], &[53], &[2]).unwrap_err(), Error::InvalidKey);
+}
@@ -0,0 +1,133 @@
+use evercrypt_tiny::{
+    pkcs::DecodeRsaPrivateKey,
+    rsa::{Oaep, RsaPrivateKey},
+    Error, HashAlgorithm,
+};
+use rand_core::OsRng;
+use wycheproof::{
+    rsa_oaep::{TestName, TestSet},
+    HashFunction, TestResult,
+};
+
+/// Maps a Wycheproof hash function to a supported hash algorithm
+fn hash_algorithm(hash: HashFunction) -> Option<HashAlgorithm> {
+    match hash {
+        HashFunction::Sha1 => Some(HashAlgorithm::Sha1),
+        HashFunction::Sha2_224 => Some(HashAlgorithm::Sha2_224),
+        HashFunction::Sha2_256 => Some(HashAlgorithm::Sha2_256),
+        HashFunction::Sha2_384 => Some(HashAlgorithm::Sha2_384),
+        HashFunction::Sha2_512 => Some(HashAlgorithm::Sha2_512),
+        _ => None,
+    }
+}
+
+/// Runs the Wycheproof RSAES-OAEP vectors for all supported hash functions and key sizes up to 3072 bits
+#[test]
+fn wycheproof_decrypt() {
+    const TEST_NAMES: &[TestName] = &[
+        TestName::Rsa2048Sha1Mgf1Sha1,
+        TestName::Rsa2048Sha224Mgf1Sha1,
+        TestName::Rsa2048Sha224Mgf1Sha224,
+        TestName::Rsa2048Sha256Mgf1Sha1,
+        TestName::Rsa2048Sha256Mgf1Sha256,
+        TestName::Rsa2048Sha384Mgf1Sha1,
+        TestName::Rsa2048Sha384Mgf1Sha384,
+        TestName::Rsa2048Sha512Mgf1Sha1,
+        TestName::Rsa2048Sha512Mgf1Sha512,
+        TestName::Rsa3072Sha256Mgf1Sha1,
+        TestName::Rsa3072Sha256Mgf1Sha256,
+        TestName::Rsa3072Sha512Mgf1Sha1,
+        TestName::Rsa3072Sha512Mgf1Sha512,
+        TestName::RsaMisc,
+    ];
+    run_wycheproof(TEST_NAMES, 3072);
+}
+
+/// Runs the Wycheproof RSAES-OAEP vectors for key sizes above 3072 bits
+#[test]
+#[ignore = "slow in debug builds"]
+fn wycheproof_decrypt_large() {
+    const TEST_NAMES: &[TestName] = &[
+        TestName::Rsa4096Sha256Mgf1Sha1,
+        TestName::Rsa4096Sha256Mgf1Sha256,
+        TestName::Rsa4096Sha512Mgf1Sha1,
+        TestName::Rsa4096Sha512Mgf1Sha512,
+        TestName::RsaMisc,
+    ];
+    run_wycheproof(TEST_NAMES, usize::MAX);
+}
+
+/// Runs all groups with a supported hash function and a key of at most `max_key_size` bits from the given test sets
+fn run_wycheproof(test_names: &[TestName], max_key_size: usize) {
+    for test_name in test_names {
+        let test_set = TestSet::load(*test_name).expect("Failed to load test set");
+        for group in test_set.test_groups {
+            let (Some(hash), Some(mgf_hash)) = (hash_algorithm(group.hash), hash_algorithm(group.mgf_hash)) else {
+                continue;
+            };
+            if group.key_size > max_key_size {
+                continue;
+            }
+
+            let private_key =
+                RsaPrivateKey::from_components(&group.key.n, &group.key.e, &group.key.d).expect("Failed to load key");
+            for test in group.tests {
+                let params = Oaep::new(hash).with_mgf_hash(mgf_hash).with_label(&test.label);
+                let result = private_key.decrypt_oaep(&params, &test.ct);
+                match (test.result, result) {
+                    (TestResult::Valid | TestResult::Acceptable, Ok(plaintext)) => {
+                        assert_eq!(plaintext.as_slice(), test.pt.as_slice(), "Test case {}", test.tc_id)
+                    }
+                    (TestResult::Invalid | TestResult::Acceptable, Err(_)) => (/* Expected failure */),
+                    (result, plaintext) => {
+                        panic!("Test case {} ({}): expected {result:?}, got {plaintext:?}", test.tc_id, test.comment)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Tests that messages round-trip for all supported hash functions and that the maximum length is enforced
+#[test]
+fn encrypt_decrypt() {
+    let private_key = RsaPrivateKey::from_pkcs1_pem(include_str!("fixtures/rsa2048_pkcs1.pem"))
+        .expect("Failed to decode private key");
+    let public_key = private_key.public_key();
+
+    for hash in [HashAlgorithm::Sha1, HashAlgorithm::Sha2_224, HashAlgorithm::Sha2_256, HashAlgorithm::Sha2_512] {
+        // Encrypt messages of all lengths up to the maximum
+        let params = Oaep::new(hash).with_label(b"Testolope");
+        let max_len = public_key.signature_len() - 2 * hash.digest_len() - 2;
+        for message_len in [0, 1, max_len / 2, max_len] {
+            let message = vec![0x01; message_len];
+            let ciphertext = public_key.encrypt_oaep(&params, &message, &mut OsRng).expect("Failed to encrypt");
+            assert_eq!(private_key.decrypt_oaep(&params, &ciphertext), Ok(message));
+        }
+
+        // Exceed the maximum length
+        let message = vec![0x01; max_len + 1];
+        assert_eq!(public_key.encrypt_oaep(&params, &message, &mut OsRng), Err(Error::InvalidLength));
+    }
+}
+
+/// Tests that ciphertexts are bound to the label and hash functions and that malformed ciphertexts are rejected
+#[test]
+fn invalid_ciphertexts() {
+    let private_key = RsaPrivateKey::from_pkcs1_pem(include_str!("fixtures/rsa2048_pkcs1.pem"))
+        .expect("Failed to decode private key");
+    let public_key = private_key.public_key();
+
+    let params = Oaep::new(HashAlgorithm::Sha2_256).with_label(b"Testolope");
+    let ciphertext = public_key.encrypt_oaep(&params, b"Testolope", &mut OsRng).expect("Failed to encrypt");
+    assert_eq!(private_key.decrypt_oaep(&params.with_label(b""), &ciphertext), Err(Error::DecryptionFailed));
+    assert_eq!(
+        private_key.decrypt_oaep(&params.with_mgf_hash(HashAlgorithm::Sha1), &ciphertext),
+        Err(Error::DecryptionFailed)
+    );
+    assert_eq!(private_key.decrypt_oaep(&params, &ciphertext[1..]), Err(Error::DecryptionFailed));
+    assert_eq!(private_key.decrypt_oaep(&params, public_key.modulus()), Err(Error::DecryptionFailed));
+
+    let params = Oaep::new(HashAlgorithm::Blake2b);
+    assert_eq!(private_key.decrypt_oaep(&params, &ciphertext), Err(Error::UnsupportedAlgorithm));
+}
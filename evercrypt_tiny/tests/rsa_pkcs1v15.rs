@@ -0,0 +1,107 @@
+mod common;
+
+use common::hex;
+use evercrypt_tiny::{
+    pkcs::DecodeRsaPrivateKey,
+    rsa::{RsaPrivateKey, RsaPublicKey},
+    Error, HashAlgorithm,
+};
+use wycheproof::{
+    rsa_pkcs1_verify::{TestName, TestSet},
+    HashFunction, TestResult,
+};
+
+/// A deterministic RSASSA-PKCS1-v1_5 signature (SHA2-256) over [`MESSAGE`] with the fixture key created with
+/// pyca/cryptography
+const SIGNATURE: &str = concat!(
+    "429dd76759e9297c8437378b7c795e5f22e9aaa92f3d921b417cf4c86032791c75569235c3dc2289c3b1c5faec13db60",
+    "8af6e888a6493294d670a8ba9064d4b1be01692ee15558a623f20cd6640d1517ca6046a42e1b27daee1913643444b478",
+    "64443d1a8139d21212e16de5975b81536daddd778a3c37e939e710ed9e7d30f0d6e06756e6f04660de7729d3cdda8cae",
+    "522a21c550e580276205b012a0363bf63eb6e14f06fe235234e7a3abc0bcce38610f224b0c8b53366e084f780a9049e4",
+    "67cc3257d2d3520b7e009a80fd06f5f19c05519f9ac920b6c8112668f0d436bb341e9f5b2ba68e2ccba4a274c8d8ff84",
+    "199bc42580bd5db76f9e8c3de00e6f12"
+);
+/// The signed message
+const MESSAGE: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+/// Runs the Wycheproof RSASSA-PKCS1-v1_5 vectors for all supported hash functions and key sizes
+#[test]
+fn wycheproof_verify() {
+    const TEST_NAMES: &[TestName] = &[
+        TestName::Rsa2048Sha224,
+        TestName::Rsa2048Sha256,
+        TestName::Rsa2048Sha384,
+        TestName::Rsa2048Sha512,
+        TestName::Rsa3072Sha256,
+        TestName::Rsa3072Sha384,
+        TestName::Rsa3072Sha512,
+        TestName::Rsa4096Sha256,
+        TestName::Rsa4096Sha384,
+        TestName::Rsa4096Sha512,
+        TestName::Rsa8192Sha256,
+        TestName::Rsa8192Sha384,
+        TestName::Rsa8192Sha512,
+    ];
+
+    for test_name in TEST_NAMES {
+        let test_set = TestSet::load(*test_name).expect("Failed to load test set");
+        for group in test_set.test_groups {
+            let hash = match group.hash {
+                HashFunction::Sha2_224 => HashAlgorithm::Sha2_224,
+                HashFunction::Sha2_256 => HashAlgorithm::Sha2_256,
+                HashFunction::Sha2_384 => HashAlgorithm::Sha2_384,
+                HashFunction::Sha2_512 => HashAlgorithm::Sha2_512,
+                hash => panic!("Unexpected hash function {hash:?}"),
+            };
+
+            let public_key = RsaPublicKey::from_components(&group.key.n, &group.key.e).expect("Failed to load key");
+            for test in group.tests {
+                let result = public_key.verify_pkcs1v15(hash, &test.msg, &test.sig);
+                match (test.result, result) {
+                    (TestResult::Valid | TestResult::Acceptable, Ok(_)) => (/* Expected success */),
+                    (TestResult::Invalid | TestResult::Acceptable, Err(_)) => (/* Expected failure */),
+                    (result, verified) => {
+                        panic!("Test case {} ({}): expected {result:?}, got {verified:?}", test.tc_id, test.comment)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Tests a deterministic signature against pyca/cryptography and that tampered inputs are rejected
+#[test]
+fn sign_verify() {
+    let private_key = RsaPrivateKey::from_pkcs1_pem(include_str!("fixtures/rsa2048_pkcs1.pem"))
+        .expect("Failed to decode private key");
+    let signature = private_key.sign_pkcs1v15(HashAlgorithm::Sha2_256, MESSAGE).expect("Failed to sign");
+    assert_eq!(signature, hex(SIGNATURE));
+
+    let public_key = private_key.public_key();
+    public_key.verify_pkcs1v15(HashAlgorithm::Sha2_256, MESSAGE, &signature).expect("Failed to verify signature");
+    for hash in [HashAlgorithm::Sha1, HashAlgorithm::Sha2_224, HashAlgorithm::Sha2_384, HashAlgorithm::Sha2_512] {
+        let signature = private_key.sign_pkcs1v15(hash, MESSAGE).expect("Failed to sign");
+        public_key.verify_pkcs1v15(hash, MESSAGE, &signature).expect("Failed to verify signature");
+    }
+
+    let result = public_key.verify_pkcs1v15(HashAlgorithm::Sha2_384, MESSAGE, &signature);
+    assert_eq!(result, Err(Error::VerificationFailed));
+    let result = public_key.verify_pkcs1v15(HashAlgorithm::Sha2_256, b"Another message", &signature);
+    assert_eq!(result, Err(Error::VerificationFailed));
+    let result = public_key.verify_pkcs1v15(HashAlgorithm::Sha2_256, MESSAGE, &signature[1..]);
+    assert_eq!(result, Err(Error::VerificationFailed));
+    let result = public_key.verify_pkcs1v15(HashAlgorithm::Sha2_256, MESSAGE, public_key.modulus());
+    assert_eq!(result, Err(Error::VerificationFailed));
+}
+
+/// Tests that unsupported hash functions are rejected
+#[test]
+fn unsupported_hash() {
+    let private_key = RsaPrivateKey::from_pkcs1_pem(include_str!("fixtures/rsa2048_pkcs1.pem"))
+        .expect("Failed to decode private key");
+    assert_eq!(private_key.sign_pkcs1v15(HashAlgorithm::Blake2b, MESSAGE), Err(Error::UnsupportedAlgorithm));
+    assert_eq!(
+        private_key.public_key().verify_pkcs1v15(HashAlgorithm::Md5, MESSAGE, &hex(SIGNATURE)),
+        Err(Error::UnsupportedAlgorithm)
+    );
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::hex;
+use evercrypt_tiny::{
+    rsa::{RsaPssPrivateKey, RsaPssPublicKey},
+    Error, HashAlgorithm,
+};
+use rand_core::OsRng;
+use wycheproof::{
+    rsa_pss_verify::{TestName, TestSet},
+    HashFunction, TestResult,
+};
+
+/// The modulus of a 2048 bit test key generated with pyca/cryptography
+const N: &str = concat!(
+    "dd1e953e77b9d283788e8de8c16cce1e322126bad0fac4c25d5e465992f8895b694e224c08e6b5bd6bf9256c4bef8a63",
+    "648856153be4c89d20eac2c5a97799422c533567c85cce2a27984a24b5cfd7a70d12e73a4f5e9fa0289ec1049918884c",
+    "21da30aff303ee38e44c766ba79fab37e89f99bbb655918a82803cef4552f7e15ca10c62dcd0fcf61ef381b088888e2e",
+    "9771b66d52747b3d304401475d7317e58755511e9d73aacb7511f62f1d8c1eb64cc2d156da1cc2eb0e55398fb92896f7",
+    "c00ea5f0be5ea4b030c23c79435837847702d3d6794bbbc4c302ece53ebb6e57710cf3944273a563c9e6493c0896ea3d",
+    "bbdf357d778382329137a35b36b02273"
+);
+/// The public exponent of the test key
+const E: &str = "010001";
+/// The private exponent of the test key
+const D: &str = concat!(
+    "14bf9f83191424526374d02a830f1ea5051d95a3fa09b44b38b60f257a3109b8efbfe45ffc85d65cb24dd2a78c6ef82e",
+    "2d04ce08ab3ee3c43ac1b309e45f6d6954310aa2a804d3bd3c184efcc142b51a78939650899a11d53cadbc16eb29ee26",
+    "96b0350c8d9521a303692629ef1b1a09e297c41c6516d0d3c0d652e170da41001f1b13fd489c0cc3e3d4f86186095f69",
+    "4b9d8d980c31cab825a35c6f532019ae7f88df757bf0047a2c90aa9b2d2610495d5f235e13c50ebd6e3cd434cbfb24d2",
+    "a1dfface1be06649dafc51f3191c5077f53d319393b7ddbaab3781b48fcc0ad8999cadd6a34e3b1c51e61248cb5aaef2",
+    "86f175b1df77c037357c7c54109ba0c1"
+);
+/// A deterministic RSASSA-PSS signature (SHA2-256, empty salt) over [`MESSAGE`] created with pyca/cryptography
+const SIGNATURE: &str = concat!(
+    "7b8cb789938564fd04d430c971e3a8dd55a0f25d9ec8ce8a3d319578792f410129243fc864fbf55f43fb79975467d910",
+    "b07e44c3c9321ae479d5f52a3636d87e21baa6653ae2e83232e5ea46bcfd000387a7571cb8906367dc5297e0b7b7981b",
+    "0b566f2a83a065bf44a4529f1be98ede83c1e3f2d44ff9e77dda4448b4a841f6043681d4b91e77b98bd83103d3a0910b",
+    "c61af3301af744cb2fbe8128db27523073e0681b6bef09c1d993a51a203d761f7929d7cf6f7a0e314b12a7de0af3f297",
+    "00ca5586dec6de9291410672f0b1d7555fd71b55af62c08b15a690cbdf536742376355f60021f5cd9ac87db20cfa6ce0",
+    "1842edfda87c4b28ad77a74c43becea8"
+);
+/// The signed message
+const MESSAGE: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+/// Runs the Wycheproof RSASSA-PSS vectors for all supported hash functions and key sizes
+#[test]
+fn wycheproof_verify() {
+    const TEST_NAMES: &[TestName] = &[
+        TestName::RsaPss2048Sha256Mgf1SaltLen0,
+        TestName::RsaPss2048Sha256Mgf1SaltLen32,
+        TestName::RsaPss2048Sha384Mgf1SaltLen48,
+        TestName::RsaPss3072Sha256Mgf1SaltLen32,
+        TestName::RsaPss4096Sha256Mgf1SaltLen32,
+        TestName::RsaPss4096Sha384Mgf1SaltLen48,
+        TestName::RsaPss4096Sha512Mgf1SaltLen32,
+        TestName::RsaPss4096Sha512Mgf1SaltLen64,
+    ];
+
+    for test_name in TEST_NAMES {
+        let test_set = TestSet::load(*test_name).expect("Failed to load test set");
+        for group in test_set.test_groups {
+            let hash = match group.hash {
+                HashFunction::Sha2_256 => HashAlgorithm::Sha2_256,
+                HashFunction::Sha2_384 => HashAlgorithm::Sha2_384,
+                HashFunction::Sha2_512 => HashAlgorithm::Sha2_512,
+                hash => panic!("Unexpected hash function {hash:?}"),
+            };
+            assert_eq!(group.mgf_hash, Some(group.hash));
+
+            let public_key = RsaPssPublicKey::from_components(&group.key.n, &group.key.e).expect("Failed to load key");
+            assert_eq!(public_key.modulus_bits(), group.key_size);
+            for test in group.tests {
+                let result = public_key.verify(hash, group.salt_size, &test.msg, &test.sig);
+                match (test.result, result) {
+                    (TestResult::Valid | TestResult::Acceptable, Ok(_)) => (/* Expected success */),
+                    (TestResult::Invalid | TestResult::Acceptable, Err(_)) => (/* Expected failure */),
+                    (result, verified) => {
+                        panic!("Test case {} ({}): expected {result:?}, got {verified:?}", test.tc_id, test.comment)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Tests signing against a deterministic signature created with pyca/cryptography
+#[test]
+fn sign_deterministic() {
+    let private_key = RsaPssPrivateKey::from_components(&hex(N), &hex(E), &hex(D)).expect("Failed to load key");
+    let signature = private_key.sign(HashAlgorithm::Sha2_256, 0, MESSAGE, &mut OsRng).expect("Failed to sign");
+    assert_eq!(signature, hex(SIGNATURE));
+}
+
+/// Tests that randomized signatures verify and are bound to the message and parameters
+#[test]
+fn sign_verify() {
+    let private_key = RsaPssPrivateKey::from_components(&hex(N), &hex(E), &hex(D)).expect("Failed to load key");
+    let public_key = private_key.public_key().clone();
+    assert_eq!(public_key.signature_len(), 256);
+
+    for hash in [HashAlgorithm::Sha2_256, HashAlgorithm::Sha2_384, HashAlgorithm::Sha2_512] {
+        let salt_len = hash.digest_len();
+        let signature = private_key.sign(hash, salt_len, MESSAGE, &mut OsRng).expect("Failed to sign");
+        public_key.verify(hash, salt_len, MESSAGE, &signature).expect("Failed to verify signature");
+
+        assert_eq!(public_key.verify(hash, salt_len, b"Another message", &signature), Err(Error::VerificationFailed));
+        assert_eq!(public_key.verify(hash, salt_len + 1, MESSAGE, &signature), Err(Error::VerificationFailed));
+        assert_eq!(public_key.verify(hash, salt_len, MESSAGE, &signature[1..]), Err(Error::VerificationFailed));
+    }
+}
+
+/// Tests that invalid keys and parameters are rejected
+#[test]
+fn invalid_parameters() {
+    let private_key = RsaPssPrivateKey::from_components(&hex(N), &hex(E), &hex(D)).expect("Failed to load key");
+    assert_eq!(
+        private_key.sign(HashAlgorithm::Sha1, 20, MESSAGE, &mut OsRng).unwrap_err(),
+        Error::UnsupportedAlgorithm
+    );
+    assert_eq!(private_key.sign(HashAlgorithm::Sha2_512, 191, MESSAGE, &mut OsRng).unwrap_err(), Error::InvalidLength);
+
+    let mut even_modulus = hex(N);
+    *even_modulus.last_mut().unwrap() &= 0xfe;
+    assert_eq!(RsaPssPublicKey::from_components(&even_modulus, &hex(E)).unwrap_err(), Error::InvalidKey);
+    assert_eq!(RsaPssPublicKey::from_components(&hex(N), &[0]).unwrap_err(), Error::InvalidKey);
+    assert_eq!(RsaPssPrivateKey::from_components(&hex(N), &hex(E), &[]).unwrap_err(), Error::InvalidKey);
+}
@@ -0,0 +1,141 @@
+use evercrypt_tiny::{
+    secret::{self, SecretArray, SecretBytes, SecretString},
+    Error,
+};
+use subtle::ConstantTimeEq;
+
+/// Tests erasure via `Lib_Memzero0`
+#[test]
+fn memzero() {
+    for len in [0, 1, 15, 16, 17, 4096] {
+        let mut buf = vec![0xa5; len];
+        secret::memzero(&mut buf);
+        assert_eq!(buf, vec![0; len]);
+    }
+}
+
+/// Tests construction, access, comparison and redaction of secret arrays
+#[test]
+fn secret_array() {
+    let mut array = SecretArray::<4>::from_slice(b"\x01\x02\x03\x04").expect("Failed to load secret");
+    assert_eq!(array.as_bytes(), &[1, 2, 3, 4]);
+    assert_eq!(SecretArray::<4>::from_slice(b"\x01\x02\x03").unwrap_err(), Error::InvalidLength);
+    assert_eq!(SecretArray::<4>::from_slice(b"\x01\x02\x03\x04\x05").unwrap_err(), Error::InvalidLength);
+
+    array[0] = 7;
+    assert_eq!(array, SecretArray::new([7, 2, 3, 4]));
+    assert_ne!(array, SecretArray::from([7, 2, 3, 5]));
+    assert!(bool::from(array.ct_eq(&array.clone())));
+    assert_eq!(SecretArray::<3>::default().as_bytes(), &[0; 3]);
+
+    let debug = format!("{array:?}");
+    assert_eq!(debug, "SecretArray { .. }");
+}
+
+/// Tests construction, access, comparison and redaction of secret byte strings
+#[test]
+fn secret_bytes() {
+    let mut bytes = SecretBytes::from(b"secret".to_vec());
+    assert_eq!(bytes.as_bytes(), b"secret");
+    assert_eq!(bytes.len(), 6);
+
+    bytes[0] = b'S';
+    assert_eq!(bytes, SecretBytes::from_slice(b"Secret"));
+    assert_ne!(bytes, SecretBytes::from_slice(b"Secret!"));
+    assert_ne!(bytes, SecretBytes::new(6));
+    assert_eq!(SecretBytes::new(3).as_bytes(), &[0; 3]);
+
+    let debug = format!("{bytes:?}");
+    assert_eq!(debug, "SecretBytes { len: 6, .. }");
+}
+
+/// Tests construction, access, comparison and redaction of secret strings
+#[test]
+fn secret_string() {
+    let string = SecretString::from(String::from("secret"));
+    assert_eq!(string.as_str(), "secret");
+    assert_eq!(string.len(), 6);
+
+    assert_eq!(string, SecretString::from(String::from("secret")));
+    assert_ne!(string, SecretString::from(String::from("Secret")));
+    assert_ne!(string, SecretString::default());
+
+    let debug = format!("{string:?}");
+    assert_eq!(debug, "SecretString { len: 6, .. }");
+}
+
+/// Tests the `zeroize` integration of the secret and key types
+#[test]
+#[cfg(feature = "zeroize")]
+fn zeroize() {
+    use evercrypt_tiny::{
+        blake2::{Blake2Params, Blake2s},
+        ed25519::Ed25519SecretKey,
+        hkdf::Hkdf,
+        hmac::Hmac,
+        rsa::RsaCrtComponents,
+        sp800_185::Kmac128,
+        tls13::{CipherSuite, EarlySecret},
+        x25519::X25519SecretKey,
+        HashAlgorithm,
+    };
+    use zeroize::Zeroize;
+
+    let mut array = SecretArray::new([0xa5; 32]);
+    array.zeroize();
+    assert_eq!(array.as_bytes(), &[0; 32]);
+
+    let mut bytes = SecretBytes::from_slice(&[0xa5; 17]);
+    bytes.zeroize();
+    assert_eq!(bytes.as_bytes(), &[0; 17]);
+
+    let mut ed25519 = Ed25519SecretKey::from_bytes(&[0xa5; 32]).expect("Failed to load key");
+    ed25519.zeroize();
+    assert_eq!(ed25519.to_bytes().as_bytes(), &[0; 32]);
+
+    let mut x25519 = X25519SecretKey::from_bytes(&[0xa5; 32]).expect("Failed to load key");
+    x25519.zeroize();
+    assert_eq!(x25519.to_bytes().as_bytes(), &[0; 32]);
+
+    let mut prk = Hkdf::new(HashAlgorithm::Sha2_256).expect("Failed to create HKDF").extract(b"salt", b"ikm");
+    let mut keys = CipherSuite::Aes128GcmSha256.traffic_keys(&prk).expect("Failed to derive keys");
+    prk.zeroize();
+    assert_eq!(prk.as_bytes(), [0; 32]);
+    keys.zeroize();
+    assert_eq!(keys.key(), [0; 16]);
+    assert_eq!(keys.iv(), &[0; 12]);
+
+    let mut early = EarlySecret::new(CipherSuite::Aes128GcmSha256, Some(&[0xa5; 32]));
+    let binder_key = early.binder_key(true);
+    early.zeroize();
+    assert_ne!(early.binder_key(true).as_bytes(), binder_key.as_bytes());
+
+    let mut crt = RsaCrtComponents { p: vec![1; 8], q: vec![2; 8], dp: vec![3; 8], dq: vec![4; 8], qinv: vec![5; 8] };
+    crt.zeroize();
+    assert!([&crt.p, &crt.q, &crt.dp, &crt.dq, &crt.qinv].iter().all(|component| component.is_empty()));
+
+    // A zeroized HMAC discards the absorbed data and behaves like one with the empty key
+    let mut hmac = Hmac::new(HashAlgorithm::Sha2_256, &[0xa5; 32]).expect("Failed to create HMAC");
+    hmac.update(b"data");
+    hmac.zeroize();
+    let empty = Hmac::new(HashAlgorithm::Sha2_256, b"").expect("Failed to create HMAC");
+    assert_eq!(hmac.finalize(), empty.finalize());
+
+    // A zeroized BLAKE2 computation is unkeyed
+    let mut blake2 =
+        Blake2s::new(&Blake2Params { key: &[0xa5; 32], ..Blake2Params::new(32) }).expect("Failed to create BLAKE2s");
+    blake2.update(b"data");
+    blake2.zeroize();
+    let unkeyed = Blake2s::new(&Blake2Params::new(32)).expect("Failed to create BLAKE2s");
+    assert_eq!(blake2.finalize(), unkeyed.finalize());
+
+    // A zeroized KMAC no longer depends on its key
+    let [mut kmac, mut other] =
+        [[0xa5; 32], [0x5a; 32]].map(|key| Kmac128::new(&key, b"").expect("Failed to create KMAC"));
+    kmac.zeroize();
+    other.zeroize();
+    let [mut mac, mut other_mac] = [[0; 32]; 2];
+    kmac.finalize_into(&mut mac).expect("Failed to compute MAC");
+    other.finalize_into(&mut other_mac).expect("Failed to compute MAC");
+    assert_eq!(mac, other_mac);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::hex;
+use evercrypt_tiny::{sha2, Error, HashAlgorithm};
+
+/// A batch test vector
+struct TestVector {
+    /// The hash function
+    hash: HashAlgorithm,
+    /// The hex encoded digest of `"abc"`
+    digest: &'static str,
+}
+
+/// The `"abc"` examples from FIPS 180-4
+const ABC: &[TestVector] = &[
+    TestVector { hash: HashAlgorithm::Sha2_224, digest: "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7" },
+    TestVector {
+        hash: HashAlgorithm::Sha2_256,
+        digest: "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_384,
+        digest: "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+    },
+    TestVector {
+        hash: HashAlgorithm::Sha2_512,
+        digest: concat!(
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a",
+            "2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        ),
+    },
+];
+
+/// The SHA2-256 digest of the 4 KiB chunk `[0, 1, ..., 250, 0, 1, ...]`
+const CHUNK_SHA2_256: &str = "d67c656e01756650d77717b0839985a056ec28ffe174601d690fc407a2ceffca";
+
+/// Tests batches that fill the 8 lane, 4 lane and scalar kernels
+#[test]
+fn fips180_batches() {
+    for vector in ABC {
+        for count in [1, 4, 8, 13, 16] {
+            let inputs = vec![b"abc".as_slice(); count];
+            let mut digests = vec![vec![0; vector.hash.digest_len()]; count];
+            sha2::hash_many(vector.hash, &inputs, &mut digests).expect("Failed to hash batch");
+            for digest in digests {
+                assert_eq!(digest, hex(vector.digest), "Invalid digest for batch size {count}");
+            }
+        }
+    }
+}
+
+/// Tests that distinct inputs of mixed lengths are hashed into the right digests
+#[test]
+fn mixed_lengths() {
+    // Create distinct inputs where most lengths occur multiple times
+    let inputs: Vec<Vec<u8>> = (0..100usize)
+        .map(|index| {
+            let len = [0, 55, 56, 64, 111, 112, 128, 4096][index % 8] + index / 50;
+            (0..len).map(|i| (i + index) as u8).collect()
+        })
+        .collect();
+    let inputs: Vec<&[u8]> = inputs.iter().map(Vec::as_slice).collect();
+
+    for vector in ABC {
+        let mut digests = vec![[0; 64]; inputs.len()];
+        let mut digest_slices: Vec<&mut [u8]> =
+            digests.iter_mut().map(|digest| &mut digest[..vector.hash.digest_len()]).collect();
+        sha2::hash_many(vector.hash, &inputs, &mut digest_slices).expect("Failed to hash batch");
+
+        // Compare against single inputs, which are always hashed by the scalar kernel
+        for (input, digest) in inputs.iter().zip(digest_slices) {
+            let mut expected = [vec![0; vector.hash.digest_len()]];
+            sha2::hash_many(vector.hash, &[input], &mut expected).expect("Failed to hash input");
+            assert_eq!(digest, expected[0], "Invalid digest for input length {}", input.len());
+        }
+    }
+}
+
+/// Tests a batch of equally sized 4 KiB chunks
+#[test]
+fn chunks() {
+    let chunk: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+    let inputs = vec![chunk.as_slice(); 27];
+    let mut digests = vec![[0; 32]; inputs.len()];
+    sha2::hash_many(HashAlgorithm::Sha2_256, &inputs, &mut digests).expect("Failed to hash chunks");
+    assert!(digests.iter().all(|digest| digest.as_slice() == hex(CHUNK_SHA2_256)), "Invalid chunk digest");
+}
+
+/// Tests invalid parameters
+#[test]
+fn invalid() {
+    let inputs = [b"abc".as_slice(); 2];
+
+    let mut digests = [[0; 32]; 3];
+    let result = sha2::hash_many(HashAlgorithm::Sha2_256, &inputs, &mut digests);
+    assert_eq!(result, Err(Error::InvalidLength), "Accepted mismatching batch sizes");
+
+    let mut digests = [[0; 32]; 2];
+    let result = sha2::hash_many(HashAlgorithm::Sha2_512, &inputs, &mut digests);
+    assert_eq!(result, Err(Error::InvalidLength), "Accepted invalid digest length");
+
+    let result = sha2::hash_many(HashAlgorithm::Blake2s, &inputs, &mut digests);
+    assert_eq!(result, Err(Error::UnsupportedAlgorithm), "Accepted non-SHA-2 hash");
+}
@@ -0,0 +1,166 @@
+mod common;
+
+use common::hex;
+use digest::{Digest, ExtendableOutput, ExtendableOutputReset, Update, XofReader};
+use evercrypt_tiny::sha3::{Sha3_224, Sha3_256, Sha3_384, Sha3_512, Shake128, Shake256};
+
+/// A fixed-length test vector
+struct TestVector {
+    /// The hex encoded message
+    message: &'static str,
+    /// The hex encoded digest or XOF output
+    digest: &'static str,
+}
+
+/// The first entries of the NIST CAVP `SHA3_224ShortMsg` file
+const SHA3_224: &[TestVector] = &[
+    TestVector { message: "", digest: "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7" },
+    TestVector { message: "01", digest: "488286d9d32716e5881ea1ee51f36d3660d70f0db03b3f612ce9eda4" },
+];
+/// The first entries of the NIST CAVP `SHA3_256ShortMsg` file
+const SHA3_256: &[TestVector] = &[
+    TestVector { message: "", digest: "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a" },
+    TestVector { message: "e9", digest: "f0d04dd1e6cfc29a4460d521796852f25d9ef8d28b44ee91ff5b759d72c1e6d6" },
+    TestVector { message: "d477", digest: "94279e8f5ccdf6e17f292b59698ab4e614dfe696a46c46da78305fc6a3146ab7" },
+];
+/// The first entries of the NIST CAVP `SHA3_384ShortMsg` file
+const SHA3_384: &[TestVector] = &[
+    TestVector {
+        message: "",
+        digest: "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2ac3713831264adb47fb6bd1e058d5f004",
+    },
+    TestVector {
+        message: "80",
+        digest: "7541384852e10ff10d5fb6a7213a4a6c15ccc86d8bc1068ac04f69277142944f4ee50d91fdc56553db06b2f5039c8ab7",
+    },
+];
+/// The first entries of the NIST CAVP `SHA3_512ShortMsg` file
+const SHA3_512: &[TestVector] = &[
+    TestVector {
+        message: "",
+        digest: concat!(
+            "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6",
+            "15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26"
+        ),
+    },
+    TestVector {
+        message: "e5",
+        digest: concat!(
+            "150240baf95fb36f8ccb87a19a41767e7aed95125075a2b2dbba6e565e1ce857",
+            "5f2b042b62e29a04e9440314a821c6224182964d8b557b16a492b3806f4c39c1"
+        ),
+    },
+];
+/// The first entries of the NIST CAVP `SHAKE128ShortMsg` and `SHAKE128VariableOut` files
+const SHAKE128: &[TestVector] = &[
+    TestVector { message: "", digest: "7f9c2ba4e88f827d616045507605853e" },
+    TestVector { message: "84e950051876050dc851fbd99e6247b8", digest: "8599bd89f63a848c49ca593ec37a12c6" },
+];
+/// The first entry of the NIST CAVP `SHAKE256ShortMsg` file
+const SHAKE256: &[TestVector] =
+    &[TestVector { message: "", digest: "46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f" }];
+
+/// The 1600 bit message from the NIST FIPS 202 example values
+const A3_MESSAGE: [u8; 200] = [0xa3; 200];
+
+/// Tests the CAVP short message vectors
+#[test]
+fn cavp_short_messages() {
+    check_digest::<Sha3_224>(SHA3_224);
+    check_digest::<Sha3_256>(SHA3_256);
+    check_digest::<Sha3_384>(SHA3_384);
+    check_digest::<Sha3_512>(SHA3_512);
+    check_xof::<Shake128>(SHAKE128);
+    check_xof::<Shake256>(SHAKE256);
+}
+
+/// Tests the 1600 bit example message, absorbed in chunks of every length up to and beyond the rate
+#[test]
+fn incremental_absorb() {
+    let expected = hex("79f38adec5c20307a98ef76e8324afbfd46cfd81b22e3973c65fa1bd9de31787");
+    for chunk_len in 1..=A3_MESSAGE.len() {
+        let mut sha3 = Sha3_256::new();
+        A3_MESSAGE.chunks(chunk_len).for_each(|chunk| Digest::update(&mut sha3, chunk));
+        assert_eq!(sha3.finalize().as_slice(), expected, "Invalid digest for chunk length {chunk_len}");
+    }
+
+    // Check the other variants with a single update
+    let expected = hex("9376816aba503f72f96ce7eb65ac095deee3be4bf9bbc2a1cb7e11e0");
+    assert_eq!(Sha3_224::digest(A3_MESSAGE).as_slice(), expected);
+    let expected =
+        hex("1881de2ca7e41ef95dc4732b8f5f002b189cc1e42b74168ed1732649ce1dbcdd76197a31fd55ee989f2d7050dd473e8f");
+    assert_eq!(Sha3_384::digest(A3_MESSAGE).as_slice(), expected);
+    let expected = hex(concat!(
+        "e76dfad22084a8b1467fcf2ffa58361bec7628edf5f3fdc0e4805dc48caeeca8",
+        "1b7c13c30adf52a3659584739a2df46be589c51ca1a4a8416df6545a1ce8ba00"
+    ));
+    assert_eq!(Sha3_512::digest(A3_MESSAGE).as_slice(), expected);
+}
+
+/// Tests that the output stream can be squeezed over multiple reads of arbitrary lengths
+#[test]
+fn squeeze_in_parts() {
+    // Squeeze the 4096 bit example output in one shot
+    let mut expected = [0; 512];
+    Shake128::digest_xof(A3_MESSAGE, &mut expected);
+    assert_eq!(expected[480..], hex("44c9fb359fd56ac0a9a75a743cff6862f17d7259ab075216c0699511643b6439"));
+
+    // Squeeze the same output in parts that cross the block boundaries
+    for part_len in [1, 7, 167, 168, 169, 300] {
+        let mut shake = Shake128::new();
+        shake.update(&A3_MESSAGE);
+        let mut reader = shake.finalize_xof();
+
+        let mut output = [0; 512];
+        output.chunks_mut(part_len).for_each(|part| reader.read(part));
+        assert_eq!(output, expected, "Invalid output for part length {part_len}");
+    }
+
+    // Check SHAKE256 with the `digest` traits
+    let mut shake = Shake256::default();
+    Update::update(&mut shake, &A3_MESSAGE[..100]);
+    Update::update(&mut shake, &A3_MESSAGE[100..]);
+    let mut reader = shake.finalize_xof_reset();
+    let mut output = [0; 512];
+    XofReader::read(&mut reader, &mut output[..256]);
+    XofReader::read(&mut reader, &mut output[256..]);
+    assert_eq!(output[480..], hex("6a1a9d7846436e4dca5728b6f760eef0ca92bf0be5615e96959d767197a0beeb"));
+
+    // The reset state must hash the empty message
+    let mut empty = [0; 32];
+    shake.finalize_xof().read(&mut empty);
+    assert_eq!(empty.as_slice(), hex(SHAKE256[0].digest));
+}
+
+/// Tests resetting and reusing a computation
+#[test]
+fn reset() {
+    let mut sha3 = Sha3_256::new();
+    Digest::update(&mut sha3, b"garbage");
+    Digest::reset(&mut sha3);
+    Digest::update(&mut sha3, hex("e9"));
+    assert_eq!(sha3.finalize_reset().as_slice(), hex(SHA3_256[1].digest));
+    assert_eq!(sha3.finalize().as_slice(), hex(SHA3_256[0].digest));
+}
+
+/// Checks fixed-length digests
+fn check_digest<D>(vectors: &[TestVector])
+where
+    D: Digest,
+{
+    for vector in vectors {
+        assert_eq!(D::digest(hex(vector.message)).as_slice(), hex(vector.digest), "Invalid digest");
+    }
+}
+
+/// Checks XOF outputs
+fn check_xof<X>(vectors: &[TestVector])
+where
+    X: ExtendableOutput + Default,
+{
+    for vector in vectors {
+        let mut output = vec![0; vector.digest.len() / 2];
+        X::digest_xof(hex(vector.message), &mut output);
+        assert_eq!(output, hex(vector.digest), "Invalid XOF output");
+    }
+}
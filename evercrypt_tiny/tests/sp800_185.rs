@@ -0,0 +1,234 @@
+mod common;
+
+use common::hex;
+use evercrypt_tiny::{
+    sha3::{Shake128, Shake256},
+    sp800_185::{CShake128, CShake256, Kmac128, Kmac256, TupleHash128, TupleHash256},
+    Error,
+};
+
+/// The customization string of the cSHAKE samples
+const EMAIL_SIGNATURE: &[u8] = b"Email Signature";
+/// The customization string of the KMAC samples
+const TAGGED_APPLICATION: &[u8] = b"My Tagged Application";
+/// The customization string of the TupleHash samples
+const TUPLE_APP: &[u8] = b"My Tuple App";
+
+/// Tests the cSHAKE samples from the NIST SP 800-185 example values
+#[test]
+fn cshake_samples() {
+    // Sample #1 and #2
+    let mut output = [0; 32];
+    let mut cshake = CShake128::new(b"", EMAIL_SIGNATURE).expect("Failed to create cSHAKE");
+    cshake.update(&hex("00010203"));
+    cshake.finalize_xof().read(&mut output);
+    assert_eq!(output.as_slice(), hex("c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5"));
+
+    let mut cshake = CShake128::new(b"", EMAIL_SIGNATURE).expect("Failed to create cSHAKE");
+    counting_bytes(200).chunks(33).for_each(|chunk| cshake.update(chunk));
+    cshake.finalize_xof().read(&mut output);
+    assert_eq!(output.as_slice(), hex("c5221d50e4f822d96a2e8881a961420f294b7b24fe3d2094baed2c6524cc166b"));
+
+    // Sample #3 and #4
+    let mut output = [0; 64];
+    let mut cshake = CShake256::new(b"", EMAIL_SIGNATURE).expect("Failed to create cSHAKE");
+    cshake.update(&hex("00010203"));
+    cshake.finalize_xof().read(&mut output);
+    let expected = concat!(
+        "d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd1",
+        "64020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c"
+    );
+    assert_eq!(output.as_slice(), hex(expected));
+
+    let mut cshake = CShake256::new(b"", EMAIL_SIGNATURE).expect("Failed to create cSHAKE");
+    cshake.update(&counting_bytes(200));
+    cshake.finalize_xof().read(&mut output);
+    let expected = concat!(
+        "07dc27b11e51fbac75bc7b3c1d983e8b4b85fb1defaf218912ac864302730917",
+        "27f42b17ed1df63e8ec118f04b23633c1dfb1574c8fb55cb45da8e25afb092bb"
+    );
+    assert_eq!(output.as_slice(), hex(expected));
+}
+
+/// Tests that cSHAKE with empty strings is SHAKE
+#[test]
+fn cshake_is_shake() {
+    let (mut expected, mut output) = ([0; 300], [0; 300]);
+    let mut shake = Shake128::new();
+    shake.update(b"Testolope");
+    shake.finalize_xof().read(&mut expected);
+    let mut cshake = CShake128::new(b"", b"").expect("Failed to create cSHAKE");
+    cshake.update(b"Testolope");
+    cshake.finalize_xof().read(&mut output);
+    assert_eq!(output, expected);
+
+    let mut shake = Shake256::new();
+    shake.update(b"Testolope");
+    shake.finalize_xof().read(&mut expected);
+    let mut cshake = CShake256::new(b"", b"").expect("Failed to create cSHAKE");
+    cshake.update(b"Testolope");
+    cshake.finalize_xof().read(&mut output);
+    assert_eq!(output, expected);
+}
+
+/// Tests the KMAC samples from the NIST SP 800-185 example values
+#[test]
+fn kmac_samples() {
+    let key = counting_bytes_from(0x40, 32);
+
+    // Sample #1 to #3
+    let mut mac = [0; 32];
+    let mut kmac = Kmac128::new(&key, b"").expect("Failed to create KMAC");
+    kmac.update(&hex("00010203"));
+    kmac.finalize_into(&mut mac).expect("Failed to compute KMAC");
+    assert_eq!(mac.as_slice(), hex("e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e"));
+
+    let mut kmac = Kmac128::new(&key, TAGGED_APPLICATION).expect("Failed to create KMAC");
+    kmac.update(&hex("00010203"));
+    kmac.finalize_into(&mut mac).expect("Failed to compute KMAC");
+    assert_eq!(mac.as_slice(), hex("3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5"));
+
+    let mut kmac = Kmac128::new(&key, TAGGED_APPLICATION).expect("Failed to create KMAC");
+    kmac.update(&counting_bytes(200));
+    kmac.verify(&hex("1f5b4e6cca02209e0dcb5ca635b89a15e271ecc760071dfd805faa38f9729230")).expect("Invalid MAC");
+
+    // Sample #4 to #6
+    let mut mac = [0; 64];
+    let mut kmac = Kmac256::new(&key, TAGGED_APPLICATION).expect("Failed to create KMAC");
+    kmac.update(&hex("00010203"));
+    kmac.finalize_into(&mut mac).expect("Failed to compute KMAC");
+    let expected = concat!(
+        "20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7",
+        "f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd"
+    );
+    assert_eq!(mac.as_slice(), hex(expected));
+
+    let mut kmac = Kmac256::new(&key, b"").expect("Failed to create KMAC");
+    counting_bytes(200).chunks(17).for_each(|chunk| kmac.update(chunk));
+    kmac.finalize_into(&mut mac).expect("Failed to compute KMAC");
+    let expected = concat!(
+        "75358cf39e41494e949707927cee0af20a3ff553904c86b08f21cc414bcfd691",
+        "589d27cf5e15369cbbff8b9a4c2eb17800855d0235ff635da82533ec6b759b69"
+    );
+    assert_eq!(mac.as_slice(), hex(expected));
+
+    let mut kmac = Kmac256::new(&key, TAGGED_APPLICATION).expect("Failed to create KMAC");
+    kmac.update(&counting_bytes(200));
+    let expected = concat!(
+        "b58618f71f92e1d56c1b8c55ddd7cd188b97b4ca4d99831eb2699a837da2e4d9",
+        "70fbacfde50033aea585f1a2708510c32d07880801bd182898fe476876fc8965"
+    );
+    kmac.verify(&hex(expected)).expect("Invalid MAC");
+
+    // A truncated tag is a different MAC and must be rejected
+    let mut kmac = Kmac256::new(&key, TAGGED_APPLICATION).expect("Failed to create KMAC");
+    kmac.update(&counting_bytes(200));
+    assert_eq!(kmac.verify(&hex(expected)[..32]), Err(Error::VerificationFailed));
+}
+
+/// Tests the KMACXOF samples from the NIST SP 800-185 example values
+#[test]
+fn kmac_xof_samples() {
+    let key = counting_bytes_from(0x40, 32);
+
+    // Sample #1 and #2, read in two parts
+    let mut output = [0; 32];
+    let mut kmac = Kmac128::new(&key, b"").expect("Failed to create KMAC");
+    kmac.update(&hex("00010203"));
+    let mut reader = kmac.finalize_xof();
+    reader.read(&mut output[..5]);
+    reader.read(&mut output[5..]);
+    assert_eq!(output.as_slice(), hex("cd83740bbd92ccc8cf032b1481a0f4460e7ca9dd12b08a0c4031178bacd6ec35"));
+
+    let mut kmac = Kmac128::new(&key, TAGGED_APPLICATION).expect("Failed to create KMAC");
+    kmac.update(&hex("00010203"));
+    kmac.finalize_xof().read(&mut output);
+    assert_eq!(output.as_slice(), hex("31a44527b4ed9f5c6101d11de6d26f0620aa5c341def41299657fe9df1a3b16c"));
+
+    // Sample #4
+    let mut output = [0; 64];
+    let mut kmac = Kmac256::new(&key, TAGGED_APPLICATION).expect("Failed to create KMAC");
+    kmac.update(&hex("00010203"));
+    kmac.finalize_xof().read(&mut output);
+    let expected = concat!(
+        "1755133f1534752aad0748f2c706fb5c784512cab835cd15676b16c0c6647fa9",
+        "6faa7af634a0bf8ff6df39374fa00fad9a39e322a7c92065a64eb1fb0801eb2b"
+    );
+    assert_eq!(output.as_slice(), hex(expected));
+}
+
+/// Tests the TupleHash samples from the NIST SP 800-185 example values
+#[test]
+fn tuple_hash_samples() {
+    let elements = [hex("000102"), hex("101112131415"), hex("202122232425262728")];
+
+    // Sample #1 to #3
+    let mut digest = [0; 32];
+    let mut tuple_hash = TupleHash128::new(b"").expect("Failed to create TupleHash");
+    elements[..2].iter().try_for_each(|element| tuple_hash.push(element)).expect("Failed to push element");
+    tuple_hash.finalize_into(&mut digest).expect("Failed to compute TupleHash");
+    assert_eq!(digest.as_slice(), hex("c5d8786c1afb9b82111ab34b65b2c0048fa64e6d48e263264ce1707d3ffc8ed1"));
+
+    let mut tuple_hash = TupleHash128::new(TUPLE_APP).expect("Failed to create TupleHash");
+    elements[..2].iter().try_for_each(|element| tuple_hash.push(element)).expect("Failed to push element");
+    tuple_hash.finalize_into(&mut digest).expect("Failed to compute TupleHash");
+    assert_eq!(digest.as_slice(), hex("75cdb20ff4db1154e841d758e24160c54bae86eb8c13e7f5f40eb35588e96dfb"));
+
+    let mut tuple_hash = TupleHash128::new(TUPLE_APP).expect("Failed to create TupleHash");
+    elements.iter().try_for_each(|element| tuple_hash.push(element)).expect("Failed to push element");
+    tuple_hash.finalize_into(&mut digest).expect("Failed to compute TupleHash");
+    assert_eq!(digest.as_slice(), hex("e60f202c89a2631eda8d4c588ca5fd07f39e5151998deccf973adb3804bb6e84"));
+
+    // Sample #4 to #6
+    let mut digest = [0; 64];
+    let mut tuple_hash = TupleHash256::new(b"").expect("Failed to create TupleHash");
+    elements[..2].iter().try_for_each(|element| tuple_hash.push(element)).expect("Failed to push element");
+    tuple_hash.finalize_into(&mut digest).expect("Failed to compute TupleHash");
+    let expected = concat!(
+        "cfb7058caca5e668f81a12a20a2195ce97a925f1dba3e7449a56f82201ec6073",
+        "11ac2696b1ab5ea2352df1423bde7bd4bb78c9aed1a853c78672f9eb23bbe194"
+    );
+    assert_eq!(digest.as_slice(), hex(expected));
+
+    let mut tuple_hash = TupleHash256::new(TUPLE_APP).expect("Failed to create TupleHash");
+    elements[..2].iter().try_for_each(|element| tuple_hash.push(element)).expect("Failed to push element");
+    tuple_hash.finalize_into(&mut digest).expect("Failed to compute TupleHash");
+    let expected = concat!(
+        "147c2191d5ed7efd98dbd96d7ab5a11692576f5fe2a5065f3e33de6bba9f3aa1",
+        "c4e9a068a289c61c95aab30aee1e410b0b607de3620e24a4e3bf9852a1d4367e"
+    );
+    assert_eq!(digest.as_slice(), hex(expected));
+
+    let mut tuple_hash = TupleHash256::new(TUPLE_APP).expect("Failed to create TupleHash");
+    elements.iter().try_for_each(|element| tuple_hash.push(element)).expect("Failed to push element");
+    tuple_hash.finalize_into(&mut digest).expect("Failed to compute TupleHash");
+    let expected = concat!(
+        "45000be63f9b6bfd89f54717670f69a9bc763591a4f05c50d68891a744bcc6e7",
+        "d6d5b5e82c018da999ed35b0bb49c9678e526abd8e85c13ed254021db9e790ce"
+    );
+    assert_eq!(digest.as_slice(), hex(expected));
+}
+
+/// Tests that the element boundaries are part of the TupleHash input
+#[test]
+fn tuple_hash_boundaries() {
+    let (mut first, mut second) = ([0; 32], [0; 32]);
+    let mut tuple_hash = TupleHash128::new(b"").expect("Failed to create TupleHash");
+    tuple_hash.push(b"ab").and_then(|_| tuple_hash.push(b"c")).expect("Failed to push element");
+    tuple_hash.finalize_into(&mut first).expect("Failed to compute TupleHash");
+
+    let mut tuple_hash = TupleHash128::new(b"").expect("Failed to create TupleHash");
+    tuple_hash.push(b"a").and_then(|_| tuple_hash.push(b"bc")).expect("Failed to push element");
+    tuple_hash.finalize_into(&mut second).expect("Failed to compute TupleHash");
+    assert_ne!(first, second);
+}
+
+/// Creates `len` bytes counting up from zero
+fn counting_bytes(len: usize) -> Vec<u8> {
+    counting_bytes_from(0, len)
+}
+
+/// Creates `len` bytes counting up from `start`
+fn counting_bytes_from(start: u8, len: usize) -> Vec<u8> {
+    (0..len).map(|i| start.wrapping_add(i as u8)).collect()
+}
@@ -0,0 +1,19 @@
+#![cfg(not(feature = "custom-entropy"))]
+
+use evercrypt_tiny::entropy::{self, SystemRng};
+use rand_core::RngCore;
+
+/// Tests that HACL*'s system randomness yields distinct outputs of any length
+#[test]
+fn system_rng() {
+    for len in [0, 1, 31, 32, 33, 4096] {
+        let (mut a, mut b) = (vec![0; len], vec![0; len]);
+        entropy::fill_system_random(&mut a).expect("Failed to read system randomness");
+        SystemRng.try_fill_bytes(&mut b).expect("Failed to read system randomness");
+        if len >= 16 {
+            assert_ne!(a, b, "Identical outputs for {len} bytes");
+            assert_ne!(a, vec![0; len], "All-zero output for {len} bytes");
+        }
+    }
+    assert_ne!(SystemRng.next_u64(), SystemRng.next_u64());
+}
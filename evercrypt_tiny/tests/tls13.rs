@@ -0,0 +1,227 @@
+mod common;
+
+use common::hex;
+use evercrypt_tiny::{
+    hkdf::Hkdf,
+    tls13::{self, CipherSuite, EarlySecret, Transcript},
+    Error, HashAlgorithm,
+};
+
+/// The simple 1-RTT handshake from RFC 8448, section 3
+#[test]
+fn rfc8448_simple_1rtt() {
+    let suite = CipherSuite::Aes128GcmSha256;
+    let early = EarlySecret::new(suite, None);
+    let handshake =
+        early.into_handshake_secret(Some(&hex("8bd4054fb55b9d63fdfbacf9f04b9f0d35e6d63f537563efd46272900f89492d")));
+
+    // Handshake traffic secrets over `ClientHello...ServerHello`
+    let hello_hash = hex("860c06edc07858ee8e78f0e7428c58edd6b43f2ca3e6e95f02ed063cf0e1cad8");
+    let client_hs = handshake.client_handshake_traffic_secret(&hello_hash).expect("Failed to derive secret");
+    assert_eq!(client_hs.as_bytes(), hex("b3eddb126e067f35a780b3abf45e2d8f3b1a950738f52e9600746a0e27a55a21"));
+    let server_hs = handshake.server_handshake_traffic_secret(&hello_hash).expect("Failed to derive secret");
+    assert_eq!(server_hs.as_bytes(), hex("b67b7d690cc16c4e75e54213cb2d37b4e9c912bcded9105d42befd59d391ad38"));
+
+    // Handshake traffic keys
+    let client_keys = suite.traffic_keys(&client_hs).expect("Failed to derive keys");
+    assert_eq!(client_keys.key(), hex("dbfaa693d1762c5b666af5d950258d01"));
+    assert_eq!(client_keys.iv().as_slice(), hex("5bd3c71b836e0b76bb73265f"));
+    let server_keys = suite.traffic_keys(&server_hs).expect("Failed to derive keys");
+    assert_eq!(server_keys.key(), hex("3fce516009c21727d0f2e4e86ee403bc"));
+    assert_eq!(server_keys.iv().as_slice(), hex("5d313eb2671276ee13000b30"));
+
+    // Server `Finished` over `ClientHello...CertificateVerify`
+    let verify_hash = hex("edb7725fa7a3473b031ec8ef65a2485493900138a2b91291407d7951a06110ed");
+    let verify_data = tls13::finished_verify_data(&server_hs, &verify_hash);
+    assert_eq!(verify_data, hex("9b9b141d906337fbd2cbdce71df4deda4ab42c309572cb7fffee5454b78f0718"));
+
+    // Resumption master secret over `ClientHello...client Finished`
+    let master = handshake.into_master_secret();
+    let finished_hash = hex("209145a96ee8e2a122ff810047cc952684658d6049e86429426db87c54ad143d");
+    let resumption = master.resumption_master_secret(&finished_hash).expect("Failed to derive secret");
+    assert_eq!(resumption.as_bytes(), hex("7df235f2031d2a051287d02b0241b0bfdaf86cc856231f2d5aba46c434ec196c"));
+}
+
+/// A full handshake including application traffic secrets and a key update
+#[test]
+fn application_traffic() {
+    let suite = CipherSuite::Aes128GcmSha256;
+    let early = EarlySecret::new(suite, None);
+    let handshake =
+        early.into_handshake_secret(Some(&hex("e7b8fef8903b520cb9a18971b69dd45dca53ce2f12bf3bef9315e31271df4b40")));
+
+    // Handshake traffic
+    let hello_hash = hex("ec147a06dea3c8846c02b2238e41bddc9d89f9aea17b5efd4d7482af75881c0a");
+    let client_hs = handshake.client_handshake_traffic_secret(&hello_hash).expect("Failed to derive secret");
+    assert_eq!(client_hs.as_bytes(), hex("617b35076b9d0e08cf731d94a86614784109ef255551921dd46e040135cf46ab"));
+    let client_keys = suite.traffic_keys(&client_hs).expect("Failed to derive keys");
+    assert_eq!(client_keys.key(), hex("62d0dd00f69619d3b8193ab4a09585a7"));
+    assert_eq!(client_keys.iv().as_slice(), hex("fff75df5ad35d5cb3c53f3a9"));
+
+    // Application traffic
+    let master = handshake.into_master_secret();
+    let finished_hash = hex("751a3d4a14dfabeb68e92ca5918e2408b9bcb0748982ec9c3230ac30bbeb23e2");
+    let client_ap = master.client_application_traffic_secret(&finished_hash).expect("Failed to derive secret");
+    assert_eq!(client_ap.as_bytes(), hex("c14a6d7976d8102b5a0c9951493fee87dcaff82c24cab214e8be71a8206dbda5"));
+    let client_keys = suite.traffic_keys(&client_ap).expect("Failed to derive keys");
+    assert_eq!(client_keys.key(), hex("cc9f5f980b5f10306cbad7be98d7572e"));
+    assert_eq!(client_keys.iv().as_slice(), hex("b80929e8d02c70f61162ed6b"));
+
+    let server_ap = master.server_application_traffic_secret(&finished_hash).expect("Failed to derive secret");
+    assert_eq!(server_ap.as_bytes(), hex("2c907738d3f83702d1e4598f4848531d9f9365491b9f7f52c822290d4c232192"));
+    let server_keys = suite.traffic_keys(&server_ap).expect("Failed to derive keys");
+    assert_eq!(server_keys.key(), hex("0cb29562d8d88f48b02cbfbed7e62bb3"));
+    assert_eq!(server_keys.iv().as_slice(), hex("0db28f988586a1b7e4d5c69c"));
+
+    // Key update
+    let next = tls13::next_traffic_secret(&client_ap);
+    assert_eq!(next.as_bytes(), hex("a8c713cb95053376505685bcd3d7924c45a8169a0eafc750122966064301249e"));
+}
+
+/// The key schedule of `TLS_AES_256_GCM_SHA384` and the PSK binder keys
+#[test]
+fn sha384_and_psk() {
+    let suite = CipherSuite::Aes256GcmSha384;
+    let early = EarlySecret::new(suite, None);
+    let shared_secret: Vec<u8> = (0..32).collect();
+    let handshake = early.into_handshake_secret(Some(&shared_secret));
+
+    // Handshake traffic
+    let hello_hash = hex(concat!(
+        "a58b4ad9c7d94f2854e9c24b773f03eda7290142cf2e8937360e8b9164a59958",
+        "07990af76b6c57642e63a749cffd1180"
+    ));
+    let client_hs = handshake.client_handshake_traffic_secret(&hello_hash).expect("Failed to derive secret");
+    assert_eq!(
+        client_hs.as_bytes(),
+        hex(concat!(
+            "1470454e6003f07811e4d521648058ab186a8ba9bae48f6740d101773fb08e47",
+            "cddd13d72bb464654a49cb17967c6cab"
+        ))
+    );
+    let client_keys = suite.traffic_keys(&client_hs).expect("Failed to derive keys");
+    assert_eq!(client_keys.key(), hex("1dfa7acc168c58d4585d2754dee9c421ab7dfa0d1fad54fe45c6ae1f5f899910"));
+    assert_eq!(client_keys.iv().as_slice(), hex("c9ec2045dfd830fb10e09926"));
+    assert_eq!(
+        tls13::finished_verify_data(&client_hs, &hello_hash),
+        hex(concat!(
+            "759af4fd729f961b598d928c4e3c8a7bb7b15711f28845d65c5b8118cd0fa010",
+            "2e1748840d54dcbc794cfdada72fc23a"
+        ))
+    );
+
+    // Application traffic
+    let master = handshake.into_master_secret();
+    let mut transcript = Transcript::new(suite);
+    transcript.update(b"client hello server hello finished");
+    let finished_hash = transcript.current_hash();
+    let client_ap = master.client_application_traffic_secret(&finished_hash).expect("Failed to derive secret");
+    assert_eq!(
+        client_ap.as_bytes(),
+        hex(concat!(
+            "d5208f6b174240f9bd5803f2e29808ef5a755b51d91934d18246f449954092a8",
+            "6595d79b978a97927d91b13643aa9ddc"
+        ))
+    );
+    let exporter = master.exporter_master_secret(&finished_hash).expect("Failed to derive secret");
+    assert_eq!(
+        exporter.as_bytes(),
+        hex(concat!(
+            "baae641ecaa5458038dddd72ba84f06a260e4e4c0cacadc0759f240a1019bd35",
+            "86ee9487bb09b02f03b19d145095e885"
+        ))
+    );
+
+    // PSK binder keys and early traffic
+    let early = EarlySecret::new(CipherSuite::Aes128GcmSha256, Some(&[1; 32]));
+    let ext_binder = early.binder_key(true);
+    assert_eq!(ext_binder.as_bytes(), hex("6a365f4633a4e43571cdd25b84d4cdb2d05de3eef98b80205f84c5c53effb952"));
+    let res_binder = early.binder_key(false);
+    assert_eq!(res_binder.as_bytes(), hex("28b3998b79622c7f4438618176cb354371495e7dd37e18394a79ea7b4ad768b1"));
+
+    let mut transcript = Transcript::new(CipherSuite::Aes128GcmSha256);
+    transcript.update(b"client hello");
+    let client_early = early.client_early_traffic_secret(&transcript.current_hash()).expect("Failed to derive secret");
+    assert_eq!(client_early.as_bytes(), hex("b41b4477a2189b8e7e5ca58a74e13444a7e85926605fb0359a3ff160ddcc14c6"));
+}
+
+/// The QUIC initial secrets and keys from RFC 9001, appendix A
+#[test]
+fn rfc9001_quic() {
+    let suite = CipherSuite::Aes128GcmSha256;
+    let (client, server) = tls13::quic_initial_secrets(&hex("8394c8f03e515708"));
+
+    // Client initial
+    assert_eq!(client.as_bytes(), hex("c00cf151ca5be075ed0ebfb5c80323c42d6b7db67881289af4008f1f6c357aea"));
+    let keys = suite.quic_keys(&client).expect("Failed to derive keys");
+    assert_eq!(keys.key(), hex("1f369613dd76d5467730efcbe3b1a22d"));
+    assert_eq!(keys.iv().as_slice(), hex("fa044b2f42a3fd3b46fb255c"));
+    assert_eq!(keys.hp(), hex("9f50449e04a0e810283a1e9933adedd2"));
+
+    // Server initial
+    assert_eq!(server.as_bytes(), hex("3c199828fd139efd216c155ad844cc81fb82fa8d7446fa7d78be803acdda951b"));
+    let keys = suite.quic_keys(&server).expect("Failed to derive keys");
+    assert_eq!(keys.key(), hex("cf3a5331653c364c88f0f379b6067e37"));
+    assert_eq!(keys.iv().as_slice(), hex("0ac1493ca1905853b0bba03e"));
+    assert_eq!(keys.hp(), hex("c206b8d9b9f0f37644430b490eeaa314"));
+
+    // ChaCha20-Poly1305 short header packet (appendix A.5)
+    let suite = CipherSuite::ChaCha20Poly1305Sha256;
+    let secret = Hkdf::new(HashAlgorithm::Sha2_256)
+        .and_then(|hkdf| hkdf.from_prk(&hex("9ac312a7f877468ebe69422748ad00a15443f18203a07d6060f688f30f21632b")))
+        .expect("Failed to load secret");
+    let keys = suite.quic_keys(&secret).expect("Failed to derive keys");
+    assert_eq!(keys.key(), hex("c6d98ff3441c3fe1b2182094f69caa2ed4b716b65488960a7a984979fb23e1c8"));
+    assert_eq!(keys.iv().as_slice(), hex("e0459b3474bdd0e44a41c144"));
+    assert_eq!(keys.hp(), hex("25a282b9e82f06f21f488917a4fc8f1b73573685608597d0efcb076b0ab7a7a4"));
+
+    let next = tls13::quic_next_secret(&secret);
+    assert_eq!(next.as_bytes(), hex("1223504755036d556342ee9361d253421a826c9ecdf3c7148684b36b714881f9"));
+}
+
+/// Transcript hashing including the `HelloRetryRequest` replacement
+#[test]
+fn transcript() {
+    let mut transcript = Transcript::new(CipherSuite::Aes128GcmSha256);
+    assert_eq!(transcript.current_hash(), hex("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"));
+
+    transcript.update(b"ab");
+    transcript.update(b"c");
+    assert_eq!(transcript.current_hash(), hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"));
+
+    transcript.hello_retry_request();
+    assert_eq!(transcript.current_hash(), hex("074b42838dd03790195abb7c5bce239eefce25188a520b67cf3a1af85663dd0b"));
+
+    let mut transcript = Transcript::new(CipherSuite::Aes256GcmSha384);
+    transcript.update(b"abc");
+    transcript.hello_retry_request();
+    transcript.update(b"def");
+    assert_eq!(
+        transcript.current_hash(),
+        hex(concat!(
+            "b5e98b8ddecdb5965b27e9eb90562861f1f99e5ac8367140344fde390775e1ed",
+            "12207e8172bd8d1398e94d349349486d"
+        ))
+    );
+}
+
+/// Tests that invalid parameters are rejected
+#[test]
+fn invalid_parameters() {
+    let early = EarlySecret::new(CipherSuite::Aes128GcmSha256, None);
+    assert_eq!(early.client_early_traffic_secret(&[0; 31]).unwrap_err(), Error::InvalidLength);
+    assert_eq!(early.early_exporter_master_secret(&[0; 48]).unwrap_err(), Error::InvalidLength);
+
+    // Secrets of a different hash function
+    let secret = early.binder_key(true);
+    assert_eq!(CipherSuite::Aes256GcmSha384.traffic_keys(&secret).unwrap_err(), Error::InvalidKey);
+    assert_eq!(CipherSuite::Aes256GcmSha384.quic_keys(&secret).unwrap_err(), Error::InvalidKey);
+
+    // Oversized labels, contexts and outputs
+    let mut okm = [0; 32];
+    assert_eq!(tls13::hkdf_expand_label(&secret, &[b'a'; 250], b"", &mut okm), Err(Error::InvalidLength));
+    assert_eq!(tls13::hkdf_expand_label(&secret, b"key", &[0; 256], &mut okm), Err(Error::InvalidLength));
+    let mut okm = vec![0; 255 * 32 + 1];
+    assert_eq!(tls13::hkdf_expand_label(&secret, b"key", b"", &mut okm), Err(Error::InvalidLength));
+    tls13::hkdf_expand_label(&secret, b"key", b"", &mut okm[..255 * 32]).expect("Failed to expand label");
+}
@@ -0,0 +1,45 @@
+use evercrypt_tiny::{
+    x25519::{X25519PublicKey, X25519SecretKey},
+    Error,
+};
+use rand_core::OsRng;
+use wycheproof::{
+    xdh::{TestName, TestSet},
+    TestResult,
+};
+
+/// Runs the Wycheproof `x25519` vectors
+#[test]
+fn wycheproof_ecdh() {
+    let test_set = TestSet::load(TestName::X25519).expect("Failed to load test set");
+    for test in test_set.test_groups.iter().flat_map(|group| &group.tests) {
+        let secret_key = X25519SecretKey::from_bytes(&test.private_key).expect("Failed to load secret key");
+        let shared =
+            X25519PublicKey::from_bytes(&test.public_key).and_then(|public_key| secret_key.diffie_hellman(&public_key));
+
+        match (test.result, shared) {
+            (TestResult::Valid | TestResult::Acceptable, Ok(shared)) => {
+                assert_eq!(shared.as_slice(), test.shared_secret.as_slice(), "Test case {}", test.tc_id)
+            }
+            // Low-order public keys yield an all-zero shared secret which is rejected
+            (TestResult::Acceptable, Err(Error::InvalidKey)) => {
+                assert!(test.shared_secret.iter().all(|byte| *byte == 0), "Test case {}", test.tc_id)
+            }
+            (TestResult::Invalid, Err(_)) => (/* Expected failure */),
+            (result, shared) => {
+                panic!("Test case {} ({}): expected {result:?}, got {shared:?}", test.tc_id, test.comment)
+            }
+        }
+    }
+}
+
+/// Tests that generated key pairs agree on a shared secret
+#[test]
+fn generate() {
+    let alice = X25519SecretKey::generate(&mut OsRng).expect("Failed to generate secret key");
+    let bob = X25519SecretKey::generate(&mut OsRng).expect("Failed to generate secret key");
+
+    let alice_shared = alice.diffie_hellman(&bob.public_key()).expect("Failed to perform key agreement");
+    let bob_shared = bob.diffie_hellman(&alice.public_key()).expect("Failed to perform key agreement");
+    assert_eq!(alice_shared, bob_shared);
+}